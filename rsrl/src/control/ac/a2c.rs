@@ -8,11 +8,15 @@ use crate::{
 use rand::Rng;
 
 /// Advantage actor-critic.
+///
+/// A non-zero `entropy_coef` adds the gradient of the policy entropy to each
+/// actor update, discouraging premature convergence to a deterministic policy.
 pub struct A2C<C, P> {
     pub critic: C,
     pub policy: P,
 
     pub alpha: f64,
+    pub entropy_coef: f64,
 }
 
 impl<C, P> A2C<C, P> {
@@ -22,6 +26,7 @@ impl<C, P> A2C<C, P> {
             policy,
 
             alpha,
+            entropy_coef: 0.0,
         }
     }
 }
@@ -40,6 +45,12 @@ where
         let qsa = self.critic.predict_q(s, &t.action);
 
         self.policy.update(s, &t.action, self.alpha * (qsa - v));
+
+        if self.entropy_coef != 0.0 {
+            let ge = self.policy.grad_entropy(s);
+
+            self.policy.update_grad_scaled(&ge.view(), self.alpha * self.entropy_coef);
+        }
     }
 
     fn handle_terminal(&mut self) {
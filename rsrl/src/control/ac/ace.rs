@@ -0,0 +1,168 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::LinearStateFunction,
+    },
+    policies::{Policy, DifferentiablePolicy},
+    prediction::ValuePredictor,
+};
+use ndarray::Array1;
+use rand::Rng;
+
+/// Actor-critic with emphatic weightings.
+///
+/// An off-policy actor-critic in which each actor update is weighted by the
+/// importance sampling ratio, `rho`, and an emphasis, `M`, derived from a
+/// follow-on trace of the user-specified `interest` in each state:
+///
+/// `F_t = gamma rho_{t-1} F_{t-1} + i(s_t)`, `M_t = (1 - eta) i(s_t) + eta
+/// F_t`.
+///
+/// With `eta = 1` (the default) the emphasis corrects for the difference
+/// between the state distributions of the `target` and `behaviour` policies,
+/// which per-step importance ratios alone do not; with `eta = 0` and unit
+/// interest, the actor update reduces to that of `OffPAC`. The critic is a
+/// linear state-value function, `v_func`, learnt by off-policy TDC with
+/// secondary weights, `u`, updated with step size `alpha_u`.
+///
+/// # References
+/// - Imani, E., Graves, E., White, M. (2018). An off-policy policy gradient
+///   theorem using emphatic weightings. In Advances in Neural Information
+///   Processing Systems, pp. 96–106.
+/// - Sutton, R. S., Mahmood, A. R., White, M. (2016). An emphatic approach to
+///   the problem of off-policy temporal-difference learning. Journal of
+///   Machine Learning Research, 17(73):1–29.
+#[derive(Parameterised)]
+pub struct ACE<V, T, B, I> {
+    #[weights] pub v_func: V,
+
+    pub target: T,
+    pub behaviour: B,
+    pub interest: I,
+
+    pub alpha_theta: f64,
+    pub alpha_v: f64,
+    pub alpha_u: f64,
+    pub gamma: f64,
+    pub eta: f64,
+
+    u: Array1<f64>,
+    follow_on: f64,
+    rho_old: f64,
+}
+
+impl<V: Parameterised, T, B, I> ACE<V, T, B, I> {
+    pub fn new(
+        v_func: V,
+        target: T,
+        behaviour: B,
+        interest: I,
+        alpha_theta: f64,
+        alpha_v: f64,
+        gamma: f64,
+    ) -> Self {
+        let n_features = v_func.weights_dim()[0];
+
+        ACE {
+            v_func,
+
+            target,
+            behaviour,
+            interest,
+
+            alpha_theta,
+            alpha_v,
+            alpha_u: alpha_v,
+            gamma,
+            eta: 1.0,
+
+            u: Array1::zeros(n_features),
+            follow_on: 0.0,
+            rho_old: 1.0,
+        }
+    }
+
+    /// Return the current value of the follow-on trace.
+    pub fn follow_on(&self) -> f64 { self.follow_on }
+
+    fn reset(&mut self) {
+        self.follow_on = 0.0;
+        self.rho_old = 1.0;
+    }
+}
+
+impl<S, V, T, B, I> OnlineLearner<S, T::Action> for ACE<V, T, B, I>
+where
+    V: LinearStateFunction<S, Output = f64>,
+    T: DifferentiablePolicy<S>,
+    B: Policy<S, Action = T::Action>,
+    I: Fn(&S) -> f64,
+{
+    fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
+        let s = t.from.state();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let interest = (self.interest)(s);
+
+        self.follow_on = self.gamma * self.rho_old * self.follow_on + interest;
+
+        let emphasis = (1.0 - self.eta) * interest + self.eta * self.follow_on;
+
+        // Off-policy TDC critic:
+        let phi_s = self.v_func.features(s);
+        let phi = phi_s.clone().expanded();
+        let v = self.v_func.evaluate_features(&phi_s);
+        let estimate = phi.dot(&self.u);
+
+        let td_error = if t.terminated() {
+            let td_error = t.reward - v;
+
+            self.v_func.update_features(&phi_s, self.alpha_v * rho * td_error);
+
+            td_error
+        } else {
+            let phi_ns = self.v_func.features(t.to.state());
+            let td_error = t.reward + self.gamma * self.v_func.evaluate_features(&phi_ns) - v;
+
+            self.v_func.update_features(&phi_s, self.alpha_v * rho * td_error);
+            self.v_func.update_features(&phi_ns, -self.alpha_v * rho * self.gamma * estimate);
+
+            td_error
+        };
+
+        self.u.scaled_add(self.alpha_u * (rho * td_error - estimate), &phi);
+
+        // Emphatically weighted actor:
+        self.target.update(s, &t.action, self.alpha_theta * rho * emphasis * td_error);
+
+        if t.terminated() { self.reset(); } else { self.rho_old = rho; }
+    }
+
+    fn handle_terminal(&mut self) { self.reset(); }
+}
+
+impl<S, V, T, B, I> ValuePredictor<S> for ACE<V, T, B, I>
+where
+    V: LinearStateFunction<S, Output = f64>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.v_func.evaluate_features(&self.v_func.features(s))
+    }
+}
+
+impl<S, V, T, B, I> Controller<S, T::Action> for ACE<V, T, B, I>
+where
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> T::Action {
+        self.target.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> B::Action {
+        self.behaviour.sample(rng, s)
+    }
+}
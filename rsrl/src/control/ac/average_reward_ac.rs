@@ -0,0 +1,134 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    policies::{Policy, DifferentiablePolicy},
+    prediction::ValuePredictor,
+    traces::{Accumulating, Trace},
+};
+use ndarray::Array2;
+use rand::Rng;
+use std::ops::Deref;
+
+/// Average-reward actor-critic for continuing tasks.
+///
+/// Maximises the long-run average reward per step rather than a discounted
+/// return. The critic learns the differential value function, `v_func`, by
+/// differential TD learning, using the TD error `r - r_bar + v(s') - v(s)`,
+/// and the running estimate of the average reward, `r_bar`, is updated with
+/// step size `alpha_r` along the same error. The actor follows the policy
+/// gradient, with `grad log pi(a | s)` scaled by the differential TD error.
+///
+/// Both the actor and the critic accumulate eligibility traces, decaying at
+/// rates `lambda_theta` and `lambda_w`, respectively; these default to zero,
+/// which recovers the one-step algorithm. Episodes are not expected to
+/// terminate, but if they do the traces are reset and the terminal state is
+/// assigned zero differential value.
+///
+/// # References
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 13.6.
+/// - Bhatnagar, S., Sutton, R. S., Ghavamzadeh, M., Lee, M. (2009). Natural
+///   actor-critic algorithms. Automatica, 45(11), 2471–2482.
+#[derive(Parameterised)]
+pub struct AverageRewardAC<V, P> {
+    #[weights] pub policy: P,
+    pub v_func: V,
+
+    pub alpha_theta: f64,
+    pub alpha_w: f64,
+    pub alpha_r: f64,
+
+    pub lambda_theta: f64,
+    pub lambda_w: f64,
+
+    avg_reward: f64,
+    trace_theta: Accumulating<Array2<f64>>,
+    trace_w: Accumulating<LFAGradient>,
+}
+
+impl<V: Parameterised, P: Parameterised> AverageRewardAC<V, P> {
+    pub fn new(policy: P, v_func: V, alpha_theta: f64, alpha_w: f64, alpha_r: f64) -> Self {
+        let trace_theta = Accumulating::zeros(policy.weights_dim());
+        let trace_w = Accumulating::zeros(v_func.weights_dim());
+
+        AverageRewardAC {
+            policy,
+            v_func,
+
+            alpha_theta,
+            alpha_w,
+            alpha_r,
+
+            lambda_theta: 0.0,
+            lambda_w: 0.0,
+
+            avg_reward: 0.0,
+            trace_theta,
+            trace_w,
+        }
+    }
+}
+
+impl<V, P> AverageRewardAC<V, P> {
+    /// Return the current estimate of the average reward per step.
+    pub fn average_reward(&self) -> f64 { self.avg_reward }
+}
+
+impl<S, V, P> OnlineLearner<S, P::Action> for AverageRewardAC<V, P>
+where
+    V: LinearStateFunction<S, Output = f64>,
+    P: DifferentiablePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state();
+        let v = self.v_func.evaluate_features(&self.v_func.features(s));
+        let nv = if t.terminated() {
+            0.0
+        } else {
+            self.v_func.evaluate_features(&self.v_func.features(t.to.state()))
+        };
+        let td_error = t.reward - self.avg_reward + nv - v;
+
+        self.avg_reward += self.alpha_r * td_error;
+
+        self.trace_w.scaled_update(self.lambda_w, &self.v_func.grad(s));
+        self.trace_theta.scaled_update(self.lambda_theta, &self.policy.grad_log(s, &t.action));
+
+        self.v_func.update_grad_scaled(self.trace_w.deref(), self.alpha_w * td_error);
+        self.policy.update_grad_scaled(&self.trace_theta.view(), self.alpha_theta * td_error);
+
+        if t.terminated() {
+            self.trace_w.reset();
+            self.trace_theta.reset();
+        }
+    }
+
+    fn handle_terminal(&mut self) {
+        self.trace_w.reset();
+        self.trace_theta.reset();
+    }
+}
+
+impl<S, V, P> ValuePredictor<S> for AverageRewardAC<V, P>
+where
+    V: LinearStateFunction<S, Output = f64>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.v_func.evaluate_features(&self.v_func.features(s))
+    }
+}
+
+impl<S, V, P: Policy<S>> Controller<S, P::Action> for AverageRewardAC<V, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
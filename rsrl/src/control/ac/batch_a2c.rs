@@ -0,0 +1,102 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateFunction},
+    policies::{Policy, DifferentiablePolicy},
+    prediction::ValuePredictor,
+};
+use ndarray::Array2;
+use rand::Rng;
+use super::GAE;
+
+/// Batch advantage actor-critic with generalised advantage estimation.
+///
+/// The policy takes a single step along the mean of `grad log pi(a|s) A`
+/// over each batch, where the advantages, `A`, are computed with `GAE(gamma,
+/// lambda)` from the critic prior to any update. The critic is then regressed
+/// onto the `critic_lambda`-returns of the batch for `critic_epochs` passes
+/// with step size `beta`, recomputing the targets at each pass. Setting
+/// `critic_lambda = 0` gives TD(0) targets and `critic_lambda = 1` Monte Carlo
+/// targets; by default it matches `lambda`.
+#[derive(Parameterised)]
+pub struct BatchA2C<C, P> {
+    #[weights] pub policy: P,
+    pub critic: C,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    pub critic_lambda: f64,
+    pub critic_epochs: usize,
+}
+
+impl<C, P> BatchA2C<C, P> {
+    pub fn new(policy: P, critic: C, alpha: f64, beta: f64, gamma: f64, lambda: f64) -> Self {
+        BatchA2C {
+            policy,
+            critic,
+
+            alpha,
+            beta,
+            gamma,
+            lambda,
+
+            critic_lambda: lambda,
+            critic_epochs: 1,
+        }
+    }
+}
+
+impl<S, C, P> BatchLearner<S, P::Action> for BatchA2C<C, P>
+where
+    S: PartialEq,
+    C: StateFunction<S, Output = f64>,
+    P: DifferentiablePolicy<S>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        if batch.is_empty() { return; }
+
+        let advantages = GAE::new(self.gamma, self.lambda).advantages(&*self, batch);
+        let mut grad = Array2::zeros(self.policy.weights_dim());
+
+        for (t, adv) in batch.iter().zip(advantages) {
+            grad.scaled_add(adv, &self.policy.grad_log(t.from.state(), &t.action));
+        }
+
+        self.policy.update_grad_scaled(&grad.view(), self.alpha / batch.len() as f64);
+
+        let critic_gae = GAE::new(self.gamma, self.critic_lambda);
+
+        for _ in 0..self.critic_epochs {
+            let targets = critic_gae.returns(&*self, batch);
+
+            for (t, target) in batch.iter().zip(targets) {
+                let s = t.from.state();
+
+                self.critic.update(s, self.beta * (target - self.critic.evaluate(s)));
+            }
+        }
+    }
+}
+
+impl<S, C, P> ValuePredictor<S> for BatchA2C<C, P>
+where
+    C: StateFunction<S, Output = f64>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.critic.evaluate(s)
+    }
+}
+
+impl<S, C, P: Policy<S>> Controller<S, P::Action> for BatchA2C<C, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised, StateFunction,
+        linear::LinearStateFunction,
+    },
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// Compatible off-policy deterministic actor-critics, COPDAC-Q and COPDAC-GQ.
+///
+/// The actor is a deterministic linear policy, `mu(s) = theta^T phi(s)`, and
+/// the behaviour policy perturbs it with Gaussian noise of standard deviation
+/// `exploration`. The critic is compatible with the actor: `Q(s, a) = (a -
+/// mu(s)) phi(s)^T w + V(s)`, where the advantage features are built from the
+/// action gradient of the actor, `grad_theta mu(s) = phi(s)`, and `V` is
+/// learnt by `v_func`. Both parts of the critic are trained by off-policy
+/// Q-learning towards `r + gamma Q(s', mu(s')) = r + gamma V(s')`, and the
+/// actor ascends the deterministic policy gradient, `phi(s) phi(s)^T w`.
+///
+/// With `with_gq_correction`, the critic is instead trained by gradient
+/// Q-learning, GQ(0), which remains stable off-policy with linear function
+/// approximation. Secondary weights, learnt with step size `alpha_u`, estimate
+/// the expected TD error given the critic features, and are used to correct the
+/// update to `v_func`. The advantage features vanish at `mu(s')`, so the
+/// advantage weights need no correction.
+///
+/// # References
+/// - Silver, D., Lever, G., Heess, N., Degris, T., Wierstra, D., Riedmiller,
+///   M. (2014). Deterministic policy gradient algorithms. In Proceedings of
+///   the 31st International Conference on Machine Learning, pp. 387–395.
+/// - Maei, H. R., Szepesvári, C., Bhatnagar, S., Sutton, R. S. (2010). Toward
+///   off-policy learning control with function approximation. In Proceedings
+///   of the 27th International Conference on Machine Learning, pp. 719–726.
+#[derive(Parameterised)]
+pub struct COPDACQ<F, V> {
+    #[weights] pub actor: F,
+    pub v_func: V,
+
+    pub alpha_theta: f64,
+    pub alpha_w: f64,
+    pub alpha_v: f64,
+    pub gamma: f64,
+
+    pub exploration: f64,
+
+    w_adv: Array1<f64>,
+    gq: Option<GQCorrection>,
+}
+
+/// Secondary weights of the GQ(0) critic correction.
+struct GQCorrection {
+    alpha_u: f64,
+
+    u_adv: Array1<f64>,
+    u_v: Array1<f64>,
+}
+
+impl<F: Parameterised, V> COPDACQ<F, V> {
+    pub fn new(
+        actor: F,
+        v_func: V,
+        alpha_theta: f64,
+        alpha_w: f64,
+        alpha_v: f64,
+        gamma: f64,
+    ) -> Self {
+        let n_features = actor.weights_dim()[0];
+
+        COPDACQ {
+            actor,
+            v_func,
+
+            alpha_theta,
+            alpha_w,
+            alpha_v,
+            gamma,
+
+            exploration: 1.0,
+
+            w_adv: Array1::zeros(n_features),
+            gq: None,
+        }
+    }
+
+    /// Train the critic by GQ(0), learning the secondary weights with step size
+    /// `alpha_u`.
+    pub fn with_gq_correction(mut self, alpha_u: f64) -> Self where V: Parameterised {
+        self.gq = Some(GQCorrection {
+            alpha_u,
+
+            u_adv: Array1::zeros(self.w_adv.len()),
+            u_v: Array1::zeros(self.v_func.weights_dim()[0]),
+        });
+
+        self
+    }
+
+    /// Return the weights of the advantage function, which also estimate the
+    /// deterministic policy gradient.
+    pub fn advantage_weights(&self) -> &Array1<f64> { &self.w_adv }
+}
+
+impl<S, F, V> OnlineLearner<S, f64> for COPDACQ<F, V>
+where
+    F: LinearStateFunction<S, Output = f64>,
+    V: LinearStateFunction<S, Output = f64>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, f64>) {
+        let s = t.from.state();
+
+        let phi_s = self.actor.features(s);
+        let mu = self.actor.evaluate_features(&phi_s);
+        let phi = phi_s.clone().expanded();
+        let phi_sa = &phi * (t.action - mu);
+
+        let phi_v = self.v_func.features(s);
+        let qsa = phi_sa.dot(&self.w_adv) + self.v_func.evaluate_features(&phi_v);
+
+        let (td_error, phi_v_ns) = if t.terminated() {
+            (t.reward - qsa, None)
+        } else {
+            let phi_v_ns = self.v_func.features(t.to.state());
+            let nv = self.v_func.evaluate_features(&phi_v_ns);
+
+            (t.reward + self.gamma * nv - qsa, Some(phi_v_ns))
+        };
+
+        // Action gradient of the critic at mu(s), along phi(s):
+        let grad_q = phi.dot(&self.w_adv);
+
+        self.actor.update_features(&phi_s, self.alpha_theta * grad_q);
+        self.w_adv.scaled_add(self.alpha_w * td_error, &phi_sa);
+        self.v_func.update_features(&phi_v, self.alpha_v * td_error);
+
+        if let Some(gq) = self.gq.as_mut() {
+            let phi_v = phi_v.expanded();
+            let estimate = phi_sa.dot(&gq.u_adv) + phi_v.dot(&gq.u_v);
+
+            if let Some(phi_v_ns) = phi_v_ns {
+                self.v_func.update_features(&phi_v_ns, -self.alpha_v * self.gamma * estimate);
+            }
+
+            gq.u_adv.scaled_add(gq.alpha_u * (td_error - estimate), &phi_sa);
+            gq.u_v.scaled_add(gq.alpha_u * (td_error - estimate), &phi_v);
+        }
+    }
+}
+
+impl<S, F, V> ValuePredictor<S> for COPDACQ<F, V>
+where
+    V: StateFunction<S, Output = f64>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.v_func.evaluate(s)
+    }
+}
+
+impl<S, F, V> ActionValuePredictor<S, f64> for COPDACQ<F, V>
+where
+    F: LinearStateFunction<S, Output = f64>,
+    V: StateFunction<S, Output = f64>,
+{
+    fn predict_q(&self, s: &S, a: &f64) -> f64 {
+        let phi_s = self.actor.features(s);
+        let mu = self.actor.evaluate_features(&phi_s);
+
+        (a - mu) * phi_s.expanded().dot(&self.w_adv) + self.v_func.evaluate(s)
+    }
+}
+
+impl<S, F, V> Controller<S, f64> for COPDACQ<F, V>
+where
+    F: StateFunction<S, Output = f64>,
+{
+    fn sample_target(&self, _: &mut impl Rng, s: &S) -> f64 {
+        self.actor.evaluate(s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> f64 {
+        self.actor.evaluate(s) + self.exploration * rng.sample::<f64, _>(StandardNormal)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{domains::Transition, prediction::ValuePredictor};
+
+/// Generalised advantage estimation.
+///
+/// Estimates the advantage of each transition as the exponentially-weighted
+/// sum of the critic's TD errors that follow it within the same episode,
+/// `A_t = sum_k (gamma lambda)^k delta_{t+k}`. Setting `lambda = 0` recovers
+/// the one-step TD error, and `lambda = 1` the discounted return minus the
+/// critic's prediction; intermediate values trade bias against variance.
+///
+/// Episodes end at terminal transitions, where the value of the next state is
+/// taken to be zero. An episode that is cut short, by a time limit or by the
+/// end of the batch, is bootstrapped from the critic's estimate of the last
+/// state reached. Within a batch, a truncated episode is detected by the next
+/// transition starting from a state other than the one reached; when the two
+/// may coincide, the episodes should be passed separately to
+/// `advantages_episodes`.
+///
+/// # References
+/// - Schulman, J., Moritz, P., Levine, S., Jordan, M., Abbeel, P. (2016).
+///   High-dimensional continuous control using generalized advantage
+///   estimation. In Proceedings of the 4th International Conference on
+///   Learning Representations.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GAE {
+    pub gamma: f64,
+    pub lambda: f64,
+}
+
+impl GAE {
+    pub fn new(gamma: f64, lambda: f64) -> Self {
+        GAE { gamma, lambda }
+    }
+
+    /// Return the advantages over a sequence of transitions. Episodes end at
+    /// terminal transitions, and are truncated wherever a transition does not
+    /// start from the state reached by the one before it, or at the end of the
+    /// batch.
+    pub fn advantages<S, A, V>(&self, critic: &V, batch: &[Transition<S, A>]) -> Vec<f64>
+    where
+        S: PartialEq,
+        V: ValuePredictor<S>,
+    {
+        let mut acc = 0.0;
+        let mut advantages: Vec<f64> = batch.iter().enumerate().rev().map(|(i, t)| {
+            let v = critic.predict_v(t.from.state());
+
+            acc = if t.terminated() {
+                t.reward - v
+            } else {
+                let ns = t.to.state();
+                let nv = critic.predict_v(ns);
+                let td_error = t.reward + self.gamma * nv - v;
+
+                match batch.get(i + 1) {
+                    Some(next) if next.from.state() == ns => {
+                        td_error + self.gamma * self.lambda * acc
+                    },
+                    _ => td_error,
+                }
+            };
+
+            acc
+        }).collect();
+
+        advantages.reverse();
+        advantages
+    }
+
+    /// Return the advantages over a collection of episodes, each of which may
+    /// be truncated, concatenated in order.
+    pub fn advantages_episodes<S, A, V, E>(&self, critic: &V, episodes: &[E]) -> Vec<f64>
+    where
+        S: PartialEq,
+        V: ValuePredictor<S>,
+        E: AsRef<[Transition<S, A>]>,
+    {
+        episodes.iter().flat_map(|e| self.advantages(critic, e.as_ref())).collect()
+    }
+
+    /// Return the lambda-returns over a sequence of transitions; that is, the
+    /// advantages plus the critic's predictions.
+    pub fn returns<S, A, V>(&self, critic: &V, batch: &[Transition<S, A>]) -> Vec<f64>
+    where
+        S: PartialEq,
+        V: ValuePredictor<S>,
+    {
+        self.advantages(critic, batch).into_iter().zip(batch.iter())
+            .map(|(adv, t)| adv + critic.predict_v(t.from.state()))
+            .collect()
+    }
+}
@@ -5,3 +5,580 @@ import_all!(tdac);
 import_all!(a2c);
 import_all!(nac);
 import_all!(offpac);
+import_all!(mvac);
+import_all!(ppo);
+import_all!(gae);
+import_all!(batch_a2c);
+import_all!(trpo);
+import_all!(copdac_q);
+import_all!(ace);
+import_all!(average_reward_ac);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BatchLearner, OnlineLearner, make_shared,
+        control::Controller,
+        domains::{Observation, Transition},
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::{Polynomial, Projector, UniformGrid}, optim::SGD},
+        },
+        policies::{Gibbs, EnumerablePolicy, Policy, Random, gaussian::{self, Gaussian}},
+        prediction::{ValuePredictor, td::{TD, VarianceTD}},
+        spaces::{Equipartition, ProductSpace},
+    };
+    use ndarray::{Array2, Axis};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use super::{ACE, AverageRewardAC, BatchA2C, COPDACQ, GAE, MeanVarianceAC, PPO, TDAC, TRPO};
+
+    /// Collect a batch of one-step episodes from the state `[0.0]`.
+    fn collect<A, C: Controller<Vec<f64>, A>>(
+        agent: &C,
+        rng: &mut impl Rng,
+        n: usize,
+        reward: impl Fn(&A) -> f64,
+    ) -> Vec<Transition<Vec<f64>, A>> {
+        (0..n).map(|_| {
+            let action = agent.sample_behaviour(rng, &vec![0.0]);
+
+            Transition {
+                from: Observation::Full(vec![0.0]),
+                reward: reward(&action),
+                action,
+                to: Observation::Terminal(vec![0.0]),
+            }
+        }).collect()
+    }
+
+    /// Return the probability of the risky arm of a bandit, with mean reward
+    /// 1 and variance 4, against a safe arm with reward `safe`, after training.
+    fn risky_probability(kappa: f64, safe: f64) -> f64 {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let critic = make_shared(TD::new(LFA::scalar(basis.clone(), SGD(1.0)), 0.1, 1.0));
+        let variance = VarianceTD::new(critic.clone(), LFA::scalar(basis, SGD(1.0)), 0.1, 1.0);
+
+        let mut agent = MeanVarianceAC::new(critic, variance, policy, 0.01, 1.0, kappa);
+        let s = vec![0.0];
+
+        for _ in 0..5000 {
+            let action = agent.sample_behaviour(&mut rng, &s);
+            let reward = if action == 1 {
+                if rng.gen_bool(0.5) { 3.0 } else { -1.0 }
+            } else {
+                safe
+            };
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s.clone()),
+                action,
+                reward,
+                to: Observation::Terminal(s.clone()),
+            });
+        }
+
+        agent.policy.probabilities(&s)[1]
+    }
+
+    /// Return the entropy of a TD actor-critic on a bandit with a single rewarding
+    /// arm after training.
+    fn tdac_entropy(entropy_coef: f64) -> f64 {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let critic = TD::new(LFA::scalar(basis, SGD(1.0)), 0.1, 1.0);
+
+        let mut agent = TDAC::new(critic, policy, 0.1, 1.0);
+
+        agent.entropy_coef = entropy_coef;
+
+        for _ in 0..2000 {
+            let t = collect(&agent, &mut rng, 1, |&a| if a == 1 { 1.0 } else { 0.0 });
+
+            agent.handle_transition(&t[0]);
+        }
+
+        agent.policy.entropy(&vec![0.0])
+    }
+
+    #[test]
+    fn test_entropy_bonus() {
+        let h_greedy = tdac_entropy(0.0);
+        let h_regularised = tdac_entropy(1.0);
+
+        assert!(h_greedy < 0.2);
+        assert!(h_regularised > h_greedy + 0.2);
+    }
+
+    /// Return the probability of hedging in the two-step episodes below after
+    /// training.
+    ///
+    /// The first step pays 5 or -5 with equal probability, moving to a state
+    /// `[1.0]` or `[-1.0]` respectively. In `[1.0]`, action 1 hedges at a cost
+    /// of 4, and action 0 pays nothing; in `[-1.0]` both actions pay nothing.
+    /// Hedging lowers the mean return from 0 to -2, and its variance from 25
+    /// to 9, although both actions are riskless given the state they are
+    /// taken in.
+    fn hedge_probability(kappa: f64) -> f64 {
+        let mut rng = StdRng::seed_from_u64(0);
+        let grid = UniformGrid::new(ProductSpace::new(vec![Equipartition::new(-1.5, 1.5, 3)]));
+
+        let policy = Gibbs::standard(LFA::vector(grid.clone(), SGD(1.0), 2));
+        let critic = make_shared(TD::new(LFA::scalar(grid.clone(), SGD(1.0)), 0.1, 1.0));
+        let variance = VarianceTD::new(critic.clone(), LFA::scalar(grid, SGD(1.0)), 0.1, 1.0);
+
+        let mut agent = MeanVarianceAC::new(critic, variance, policy, 0.01, 1.0, kappa);
+        let (s0, s_up, s_down) = (vec![0.0], vec![1.0], vec![-1.0]);
+
+        for _ in 0..5000 {
+            let up = rng.gen_bool(0.5);
+            let s1 = if up { s_up.clone() } else { s_down.clone() };
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s0.clone()),
+                action: agent.sample_behaviour(&mut rng, &s0),
+                reward: if up { 5.0 } else { -5.0 },
+                to: Observation::Full(s1.clone()),
+            });
+
+            let action = agent.sample_behaviour(&mut rng, &s1);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s1.clone()),
+                action,
+                reward: if up && action == 1 { -4.0 } else { 0.0 },
+                to: Observation::Terminal(s1),
+            });
+        }
+
+        agent.policy.probabilities(&s_up)[1]
+    }
+
+    #[test]
+    fn test_mean_variance_ac() {
+        assert!(risky_probability(0.0, 0.0) > 0.9);
+        assert!(risky_probability(1.0, 0.0) < 0.1);
+
+        // With p the probability of the risky arm, the objective is
+        // p + (1 - p) / 2 - kappa (4p + p (1 - p) / 4), whose slope at p = 1 is
+        // 1/2 - 15 kappa / 4, changing sign at kappa = 2/15:
+        assert!(risky_probability(0.05, 0.5) > 0.9);
+        assert!(risky_probability(0.25, 0.5) < 0.1);
+    }
+
+    #[test]
+    fn test_mean_variance_ac_episodes() {
+        // The objective is -2p - kappa (25 - 12p - 4p^2) for p the probability
+        // of hedging, with slope kappa (12 + 8p) - 2, which is negative for all
+        // p when kappa is below 1/10, and positive when it is above 1/6:
+        assert!(hedge_probability(0.0) < 0.1);
+        assert!(hedge_probability(1.0) > 0.9);
+    }
+
+    #[test]
+    fn test_ppo_softmax() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let mut agent = PPO::new(policy, LFA::scalar(basis, SGD(1.0)), 0.1, 0.1, 1.0);
+
+        agent.target_kl = Some(0.05);
+
+        for _ in 0..50 {
+            let batch = collect(&agent, &mut rng, 32, |&a| if a == 1 { 1.0 } else { 0.0 });
+
+            agent.handle_batch(&batch);
+        }
+
+        assert!(agent.policy.probabilities(&vec![0.0])[1] > 0.9);
+    }
+
+    /// Return the weights of a softmax PPO agent after learning from a single
+    /// batch from the state `[0.0]`, in which action 1 earns one and action 0
+    /// nothing.
+    fn ppo_weights(
+        n_epochs: usize,
+        epsilon: f64,
+        target_kl: Option<f64>,
+        entropy_coef: f64,
+    ) -> Array2<f64> {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let mut agent = PPO::new(policy, LFA::scalar(basis, SGD(1.0)), 1.0, 0.0, 1.0);
+
+        agent.n_epochs = n_epochs;
+        agent.epsilon = epsilon;
+        agent.target_kl = target_kl;
+        agent.entropy_coef = entropy_coef;
+
+        let batch: Vec<_> = [1, 1, 0, 0].iter().map(|&action| Transition {
+            from: Observation::Full(vec![0.0]),
+            action,
+            reward: action as f64,
+            to: Observation::Terminal(vec![0.0]),
+        }).collect();
+
+        agent.handle_batch(&batch);
+        agent.weights()
+    }
+
+    fn all_close(a: &Array2<f64>, b: &Array2<f64>) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-10)
+    }
+
+    #[test]
+    fn test_ppo_clipping() {
+        // The first epoch moves the probability of action 1 from 1/2 to about
+        // 0.73, beyond 1 + epsilon, so later epochs have zero gradient:
+        let one_epoch = ppo_weights(1, 0.2, None, 0.0);
+
+        assert!(all_close(&ppo_weights(5, 0.2, None, 0.0), &one_epoch));
+
+        // Without clipping, the later epochs keep going:
+        assert!(!all_close(&ppo_weights(5, 10.0, None, 0.0), &one_epoch));
+    }
+
+    #[test]
+    fn test_ppo_target_kl() {
+        let one_epoch = ppo_weights(1, 10.0, None, 0.0);
+
+        assert!(all_close(&ppo_weights(5, 10.0, Some(1e-3), 0.0), &one_epoch));
+        assert!(!all_close(&ppo_weights(5, 10.0, Some(1.0), 0.0), &one_epoch));
+    }
+
+    #[test]
+    fn test_ppo_entropy() {
+        // The policy is uniform during the first epoch, where the entropy is
+        // stationary, but the bonus holds back the later epochs:
+        let prob = |w: Array2<f64>| {
+            let q = w.sum_axis(Axis(0));
+
+            1.0 / (1.0 + (q[0] - q[1]).exp())
+        };
+
+        let one_epoch = ppo_weights(1, 10.0, None, 1.0);
+
+        assert!(all_close(&one_epoch, &ppo_weights(1, 10.0, None, 0.0)));
+        assert!(prob(ppo_weights(5, 10.0, None, 1.0)) < prob(ppo_weights(5, 10.0, None, 0.0)));
+    }
+
+    #[test]
+    fn test_ppo_seeded() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let weights = |seed| {
+            let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+            let mut agent = PPO::new(policy, LFA::scalar(basis.clone(), SGD(1.0)), 0.1, 0.1, 1.0)
+                .with_seed(seed);
+            let mut rng = StdRng::seed_from_u64(0);
+
+            agent.mini_batch_size = 4;
+
+            let batch = collect(&agent, &mut rng, 32, |&a| if a == 1 { 1.0 } else { 0.0 });
+
+            agent.handle_batch(&batch);
+            agent.weights()
+        };
+
+        assert_eq!(weights(0), weights(0));
+    }
+
+    #[test]
+    fn test_ppo_gaussian() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gaussian::new(
+            gaussian::mean::Scalar(LFA::scalar(basis.clone(), SGD(1.0))),
+            gaussian::stddev::Constant(0.5),
+        );
+        let mut agent = PPO::new(policy, LFA::scalar(basis, SGD(1.0)), 0.01, 0.1, 1.0);
+
+        for _ in 0..100 {
+            let batch = collect(&agent, &mut rng, 32, |&a: &f64| -(a - 2.0) * (a - 2.0));
+
+            agent.handle_batch(&batch);
+        }
+
+        assert!((agent.policy.mpa(&vec![0.0]) - 2.0).abs() < 0.2);
+    }
+
+    struct Identity;
+
+    impl ValuePredictor<f64> for Identity {
+        fn predict_v(&self, s: &f64) -> f64 { *s }
+    }
+
+    #[test]
+    fn test_gae() {
+        let batch = vec![
+            Transition { from: Observation::Full(0.0), action: (), reward: 1.0, to: Observation::Full(1.0) },
+            Transition { from: Observation::Full(1.0), action: (), reward: 0.0, to: Observation::Terminal(2.0) },
+            Transition { from: Observation::Full(3.0), action: (), reward: 2.0, to: Observation::Full(4.0) },
+        ];
+        let gae = GAE::new(0.5, 0.5);
+
+        assert_eq!(gae.advantages(&Identity, &batch), vec![1.25, -1.0, 1.0]);
+        assert_eq!(gae.returns(&Identity, &batch), vec![1.25, 0.0, 4.0]);
+
+        // Splitting after the first transition truncates the first episode:
+        let episodes = vec![&batch[..1], &batch[1..]];
+
+        assert_eq!(gae.advantages_episodes(&Identity, &episodes), vec![1.5, -1.0, 1.0]);
+
+        // As does a transition that starts from a state other than the one reached:
+        let truncated = vec![batch[0], batch[2]];
+
+        assert_eq!(gae.advantages(&Identity, &truncated), vec![1.5, 1.0]);
+    }
+
+    #[test]
+    fn test_batch_a2c() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let mut agent = BatchA2C::new(policy, LFA::scalar(basis, SGD(1.0)), 0.5, 0.1, 1.0, 0.95);
+
+        agent.critic_epochs = 5;
+
+        for _ in 0..100 {
+            let batch = collect(&agent, &mut rng, 32, |&a| if a == 1 { 1.0 } else { 0.0 });
+
+            agent.handle_batch(&batch);
+        }
+
+        assert!(agent.policy.probabilities(&vec![0.0])[1] > 0.9);
+        assert!((agent.predict_v(&vec![0.0]) - 1.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_trpo_softmax() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let mut agent = TRPO::new(policy, LFA::scalar(basis, SGD(1.0)), 0.1, 1.0, 0.05);
+
+        for _ in 0..50 {
+            let batch = collect(&agent, &mut rng, 32, |&a| if a == 1 { 1.0 } else { 0.0 });
+
+            agent.handle_batch(&batch);
+        }
+
+        assert!(agent.policy.probabilities(&vec![0.0])[1] > 0.9);
+    }
+
+    #[test]
+    fn test_trpo_kl() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let mut agent = TRPO::new(policy, LFA::scalar(basis, SGD(1.0)), 0.1, 1.0, 0.01);
+        let log_probs = |agent: &TRPO<_, Gibbs<_>>, batch: &[Transition<Vec<f64>, usize>]| {
+            batch.iter().map(|t| agent.policy.probability(t.from.state(), &t.action).ln())
+                .collect::<Vec<f64>>()
+        };
+
+        for _ in 0..20 {
+            let batch = collect(&agent, &mut rng, 32, |&a| if a == 1 { 1.0 } else { 0.0 });
+            let before = log_probs(&agent, &batch);
+
+            agent.handle_batch(&batch);
+
+            let after = log_probs(&agent, &batch);
+            let kl = before.iter().zip(after).map(|(b, a)| b - a).sum::<f64>() / 32.0;
+
+            assert!(kl <= agent.max_kl);
+        }
+    }
+
+    #[test]
+    fn test_trpo_restore() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let new = |max_backtracks| {
+            let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+            let mut agent = TRPO::new(policy, LFA::scalar(basis.clone(), SGD(1.0)), 0.1, 1.0, 1e-6);
+
+            agent.max_backtracks = max_backtracks;
+            agent
+        };
+
+        // Every sample penalises action 0, so the sample KL divergence is first
+        // order in the step, about 1e-3 for the full step, and exceeds max_kl
+        // for each of the first few backtracks:
+        let batch = vec![Transition {
+            from: Observation::Full(vec![0.0]),
+            action: 0,
+            reward: -1.0,
+            to: Observation::Terminal(vec![0.0]),
+        }; 4];
+
+        let mut agent = new(3);
+        let initial = agent.weights();
+
+        agent.handle_batch(&batch);
+
+        assert!(all_close(&agent.weights(), &initial));
+
+        // Given enough backtracks, a small enough step is accepted:
+        let mut agent = new(20);
+
+        agent.handle_batch(&batch);
+
+        assert!(!all_close(&agent.weights(), &initial));
+    }
+
+    #[test]
+    fn test_trpo_gaussian() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let policy = Gaussian::new(
+            gaussian::mean::Scalar(LFA::scalar(basis.clone(), SGD(1.0))),
+            gaussian::stddev::Constant(0.5),
+        );
+        let mut agent = TRPO::new(policy, LFA::scalar(basis, SGD(1.0)), 0.1, 1.0, 0.05);
+
+        for _ in 0..100 {
+            let batch = collect(&agent, &mut rng, 32, |&a: &f64| -(a - 2.0) * (a - 2.0));
+
+            agent.handle_batch(&batch);
+        }
+
+        assert!((agent.policy.mpa(&vec![0.0]) - 2.0).abs() < 0.2);
+    }
+
+    /// Train a deterministic actor on a contextual bandit with states drawn
+    /// uniformly from `[-1, 1]` and reward `-(a - 2 s)^2`, and return its action
+    /// in the state `0.5`.
+    fn copdac_action<C>(agent: &mut C) -> f64
+    where
+        C: OnlineLearner<Vec<f64>, f64> + Controller<Vec<f64>, f64>,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..20000 {
+            let s = vec![rng.gen_range(-1.0, 1.0)];
+            let action = agent.sample_behaviour(&mut rng, &s);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s.clone()),
+                reward: -(action - 2.0 * s[0]).powi(2),
+                action,
+                to: Observation::Terminal(s),
+            });
+        }
+
+        agent.sample_target(&mut rng, &vec![0.5])
+    }
+
+    #[test]
+    fn test_copdac_q() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let mut agent = COPDACQ::new(
+            LFA::scalar(basis.clone(), SGD(1.0)),
+            LFA::scalar(basis, SGD(1.0)),
+            0.01, 0.05, 0.05, 0.9,
+        );
+
+        agent.exploration = 0.5;
+
+        assert!((copdac_action(&mut agent) - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_copdac_gq() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let mut agent = COPDACQ::new(
+            LFA::scalar(basis.clone(), SGD(1.0)),
+            LFA::scalar(basis, SGD(1.0)),
+            0.01, 0.05, 0.05, 0.9,
+        ).with_gq_correction(0.01);
+
+        agent.exploration = 0.5;
+
+        assert!((copdac_action(&mut agent) - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_ace() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+
+        let target = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+        let v_func = LFA::scalar(basis, SGD(1.0));
+
+        let interest = |_: &Vec<f64>| 1.0;
+
+        let mut agent = ACE::new(v_func, target, Random::new(2), interest, 0.05, 0.1, 0.9);
+
+        // Two-step episodes, rewarding the second action in the second state only:
+        for _ in 0..2000 {
+            let (s0, s1) = (vec![0.0], vec![1.0]);
+
+            let a0 = agent.sample_behaviour(&mut rng, &s0);
+            let t0 = Transition {
+                from: Observation::Full(s0.clone()),
+                action: a0,
+                reward: 0.0,
+                to: Observation::Full(s1.clone()),
+            };
+
+            // The follow-on trace is reset at the start of each episode:
+            agent.handle_transition(&t0);
+
+            assert_eq!(agent.follow_on(), 1.0);
+
+            let a1 = agent.sample_behaviour(&mut rng, &s1);
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s1.clone()),
+                action: a1,
+                reward: if a1 == 1 { 1.0 } else { 0.0 },
+                to: Observation::Terminal(s1),
+            });
+        }
+
+        assert!(agent.target.probabilities(&vec![1.0])[1] > 0.9);
+        assert!(agent.predict_v(&vec![1.0]) > 0.5);
+    }
+
+    #[test]
+    fn test_average_reward_ac() {
+        for &lambda in [0.0, 0.5].iter() {
+            let mut rng = StdRng::seed_from_u64(0);
+            let basis = Polynomial::new(1, 1).with_constant();
+
+            let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+            let v_func = LFA::scalar(basis, SGD(1.0));
+
+            let mut agent = AverageRewardAC::new(policy, v_func, 0.05, 0.1, 0.01);
+
+            agent.lambda_theta = lambda;
+            agent.lambda_w = lambda;
+
+            // A single continuing state, rewarding the second action:
+            let s = vec![0.0];
+
+            for _ in 0..5000 {
+                let action = agent.sample_behaviour(&mut rng, &s);
+
+                agent.handle_transition(&Transition {
+                    from: Observation::Full(s.clone()),
+                    action,
+                    reward: if action == 1 { 1.0 } else { 0.0 },
+                    to: Observation::Full(s.clone()),
+                });
+            }
+
+            assert!(agent.policy.probabilities(&s)[1] > 0.9);
+            assert!(agent.average_reward() > 0.8);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    policies::{Policy, DifferentiablePolicy},
+    prediction::{ValuePredictor, VariancePredictor},
+};
+use rand::Rng;
+
+/// Mean-variance risk-sensitive actor-critic.
+///
+/// Ascends `E[G] - kappa Var[G]`, for the return `G` from the start state
+/// `s0` of each episode, using two critics: `critic` for the value, `J`, and
+/// `variance` for the variance of the return. Writing the second moment as
+/// `M = Var + J^2`, the gradient of the variance is
+/// `grad M(s0) - 2 J(s0) grad J(s0)`. The return from `s0` splits at step `t`
+/// into the discounted rewards so far, `R_t`, and `gamma^t` times the return
+/// from `s_t`, so the policy is updated along the likelihood-ratio direction
+/// weighted by
+///
+/// `delta_J - kappa (gamma^t delta_M + 2 (R_t - J(s0)) delta_J)`.
+///
+/// Here `delta_J = r + gamma J(s') - J(s)` is the TD error of the value and
+/// `delta_M = r^2 + 2 gamma r J(s') + gamma^2 M(s') - M(s)` that of the second
+/// moment, each an estimate of the advantage of the action taken. `J(s0)` and
+/// `R_t` are tracked over the episode, and reset on termination or in
+/// `handle_terminal`.
+///
+/// # References
+/// - Tamar, A., Di Castro, D., Mannor, S. (2012). Policy gradients with
+///   variance related risk criteria. In Proceedings of the 29th International
+///   Conference on Machine Learning, pp. 387–396.
+/// - Tamar, A., Di Castro, D., Mannor, S. (2013). Temporal difference methods
+///   for the variance of the reward to go. In Proceedings of the 30th
+///   International Conference on Machine Learning, pp. 495–503.
+/// - Prashanth, L. A., Ghavamzadeh, M. (2013). Actor-critic algorithms for
+///   risk-sensitive MDPs. In Advances in Neural Information Processing
+///   Systems 26, pp. 252–260.
+pub struct MeanVarianceAC<C, V, P> {
+    pub critic: C,
+    pub variance: V,
+    pub policy: P,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub kappa: f64,
+
+    // J(s0), R_t and gamma^t of the current episode:
+    episode: Option<(f64, f64, f64)>,
+}
+
+impl<C, V, P> MeanVarianceAC<C, V, P> {
+    pub fn new(critic: C, variance: V, policy: P, alpha: f64, gamma: f64, kappa: f64) -> Self {
+        MeanVarianceAC {
+            critic,
+            variance,
+            policy,
+
+            alpha,
+            gamma,
+            kappa,
+
+            episode: None,
+        }
+    }
+}
+
+impl<S, C, V, P> OnlineLearner<S, P::Action> for MeanVarianceAC<C, V, P>
+where
+    C: OnlineLearner<S, P::Action> + ValuePredictor<S>,
+    V: OnlineLearner<S, P::Action> + VariancePredictor<S>,
+    P: DifferentiablePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state();
+        let v = self.critic.predict_v(s);
+        let m = self.variance.predict_var(s) + v * v;
+
+        let (td_error, m_error) = if t.terminated() {
+            (t.reward - v, t.reward * t.reward - m)
+        } else {
+            let ns = t.to.state();
+            let nv = self.critic.predict_v(ns);
+            let nm = self.variance.predict_var(ns) + nv * nv;
+            let gamma_m = self.gamma * self.gamma;
+
+            (
+                t.reward + self.gamma * nv - v,
+                t.reward * t.reward + 2.0 * self.gamma * t.reward * nv + gamma_m * nm - m,
+            )
+        };
+
+        self.critic.handle_transition(t);
+        self.variance.handle_transition(t);
+
+        let (v0, rewards, discount) = self.episode.unwrap_or((v, 0.0, 1.0));
+        let var_error = discount * m_error + 2.0 * (rewards - v0) * td_error;
+
+        self.policy.update(s, &t.action, self.alpha * (td_error - self.kappa * var_error));
+
+        self.episode = if t.terminated() {
+            None
+        } else {
+            Some((v0, rewards + discount * t.reward, discount * self.gamma))
+        };
+    }
+
+    fn handle_terminal(&mut self) {
+        self.episode = None;
+        self.critic.handle_terminal();
+        self.variance.handle_terminal();
+    }
+}
+
+impl<S, C, V, P> ValuePredictor<S> for MeanVarianceAC<C, V, P>
+where
+    C: ValuePredictor<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.critic.predict_v(s)
+    }
+}
+
+impl<S, C, V, P> VariancePredictor<S> for MeanVarianceAC<C, V, P>
+where
+    V: VariancePredictor<S>,
+{
+    fn predict_var(&self, s: &S) -> f64 {
+        self.variance.predict_var(s)
+    }
+}
+
+impl<S, C, V, P> Controller<S, P::Action> for MeanVarianceAC<C, V, P>
+where
+    P: Policy<S>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
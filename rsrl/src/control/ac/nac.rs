@@ -9,12 +9,17 @@ use crate::{
 use rand::Rng;
 
 /// Natural actor-critic.
+///
+/// The natural gradient steps are taken every `update_freq` transitions. An
+/// entropy bonus, weighted by `entropy_coef`, is instead applied at every
+/// transition along the vanilla gradient of the entropy.
 pub struct NAC<C, P> {
     pub critic: C,
     pub policy: P,
 
     pub alpha: f64,
     pub update_freq: usize,
+    pub entropy_coef: f64,
 
     counter: usize,
 }
@@ -27,6 +32,7 @@ impl<C, P> NAC<C, P> {
 
             alpha,
             update_freq,
+            entropy_coef: 0.0,
 
             counter: 0,
         }
@@ -64,6 +70,12 @@ where
         self.counter += 1;
 
         self.update_policy();
+
+        if self.entropy_coef != 0.0 {
+            let ge = self.policy.grad_entropy(t.from.state());
+
+            self.policy.update_grad_scaled(&ge.view(), self.alpha * self.entropy_coef);
+        }
     }
 
     fn handle_terminal(&mut self) {
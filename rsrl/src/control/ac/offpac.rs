@@ -9,6 +9,10 @@ use crate::{
 use rand::Rng;
 
 /// Off-policy TD-based actor-critic.
+///
+/// With a non-zero `entropy_coef`, the entropy of the target policy at each
+/// visited state is also ascended; no importance weighting is required since
+/// the entropy does not depend on the action taken.
 #[derive(Parameterised)]
 pub struct OffPAC<C, T, B> {
     #[weights] pub critic: C,
@@ -18,6 +22,7 @@ pub struct OffPAC<C, T, B> {
 
     pub alpha: f64,
     pub gamma: f64,
+    pub entropy_coef: f64,
 }
 
 impl<C, T: Parameterised, B> OffPAC<C, T, B> {
@@ -36,6 +41,7 @@ impl<C, T: Parameterised, B> OffPAC<C, T, B> {
 
             alpha,
             gamma,
+            entropy_coef: 0.0,
         }
     }
 }
@@ -64,6 +70,12 @@ impl<C, T, B> OffPAC<C, T, B> {
         };
 
         self.target.update(s, &t.action, self.alpha * residual * is_ratio);
+
+        if self.entropy_coef != 0.0 {
+            let ge = self.target.grad_entropy(s);
+
+            self.target.update_grad_scaled(&ge.view(), self.alpha * self.entropy_coef);
+        }
     }
 }
 
@@ -0,0 +1,159 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateFunction},
+    policies::{Policy, DifferentiablePolicy},
+    prediction::ValuePredictor,
+};
+use ndarray::Array2;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use super::GAE;
+
+/// Proximal policy optimisation with a clipped surrogate objective.
+///
+/// Each batch is used for `n_epochs` passes of mini-batch gradient ascent on
+/// the clipped surrogate, `min(rho A, clip(rho, 1 - epsilon, 1 + epsilon)
+/// A)`, where `rho` is the probability ratio between the current policy and
+/// the one that collected the batch. The advantages, `A`, are estimated with
+/// `GAE(gamma, lambda)`, which by default reduces to the discounted returns in
+/// the batch minus the critic's predictions, and the critic is regressed onto
+/// the corresponding lambda-returns with step size `beta`.
+///
+/// An entropy bonus with weight `entropy_coef` may be added to the objective,
+/// in which case the policy must implement `DifferentiablePolicy::grad_entropy`.
+/// If `target_kl` is set, the epochs are stopped early once the approximate KL
+/// divergence from the behaviour policy exceeds `1.5 * target_kl`. The
+/// mini-batches are drawn using an internal generator, which may be seeded
+/// with `with_seed` for reproducibility.
+///
+/// # References
+/// - Schulman, J., Wolski, F., Dhariwal, P., Radford, A., Klimov, O. (2017).
+///   Proximal policy optimization algorithms. arXiv:1707.06347.
+#[derive(Parameterised)]
+pub struct PPO<C, P> {
+    #[weights] pub policy: P,
+    pub critic: C,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    pub epsilon: f64,
+    pub n_epochs: usize,
+    pub mini_batch_size: usize,
+    pub entropy_coef: f64,
+    pub target_kl: Option<f64>,
+
+    rng: StdRng,
+}
+
+impl<C, P> PPO<C, P> {
+    pub fn new(policy: P, critic: C, alpha: f64, beta: f64, gamma: f64) -> Self {
+        PPO {
+            policy,
+            critic,
+
+            alpha,
+            beta,
+            gamma,
+            lambda: 1.0,
+
+            epsilon: 0.2,
+            n_epochs: 10,
+            mini_batch_size: 64,
+            entropy_coef: 0.0,
+            target_kl: None,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the generator used to shuffle the mini-batches.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl<S, C, P> BatchLearner<S, P::Action> for PPO<C, P>
+where
+    S: PartialEq,
+    C: StateFunction<S, Output = f64>,
+    P: DifferentiablePolicy<S>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        if batch.is_empty() { return; }
+
+        let advantages = GAE::new(self.gamma, self.lambda).advantages(&*self, batch);
+        let returns: Vec<f64> = batch.iter().zip(advantages.iter()).map(|(t, adv)| {
+            adv + self.critic.evaluate(t.from.state())
+        }).collect();
+        let log_probs_old: Vec<f64> = batch.iter().map(|t| {
+            self.policy.probability(t.from.state(), &t.action).ln()
+        }).collect();
+
+        let mut indices: Vec<usize> = (0..batch.len()).collect();
+
+        for _ in 0..self.n_epochs {
+            indices.shuffle(&mut self.rng);
+
+            for mb in indices.chunks(self.mini_batch_size.max(1)) {
+                let mut grad = Array2::zeros(self.policy.weights_dim());
+
+                for &i in mb {
+                    let t = &batch[i];
+                    let s = t.from.state();
+
+                    let log_prob = self.policy.probability(s, &t.action).ln();
+                    let ratio = (log_prob - log_probs_old[i]).exp();
+                    let adv = advantages[i];
+
+                    // The clipped objective is flat beyond the trust region:
+                    let clipped = (adv > 0.0 && ratio > 1.0 + self.epsilon)
+                        || (adv < 0.0 && ratio < 1.0 - self.epsilon);
+
+                    if !clipped {
+                        grad.scaled_add(ratio * adv, &self.policy.grad_log(s, &t.action));
+                    }
+
+                    if self.entropy_coef != 0.0 {
+                        grad.scaled_add(self.entropy_coef, &self.policy.grad_entropy(s));
+                    }
+
+                    self.critic.update(s, self.beta * (returns[i] - self.critic.evaluate(s)));
+                }
+
+                self.policy.update_grad_scaled(&grad.view(), self.alpha / mb.len() as f64);
+            }
+
+            if let Some(target_kl) = self.target_kl {
+                let kl = batch.iter().zip(log_probs_old.iter()).fold(0.0, |acc, (t, lp)| {
+                    acc + lp - self.policy.probability(t.from.state(), &t.action).ln()
+                }) / batch.len() as f64;
+
+                if kl > 1.5 * target_kl { break; }
+            }
+        }
+    }
+}
+
+impl<S, C, P> ValuePredictor<S> for PPO<C, P>
+where
+    C: StateFunction<S, Output = f64>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.critic.evaluate(s)
+    }
+}
+
+impl<S, C, P: Policy<S>> Controller<S, P::Action> for PPO<C, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
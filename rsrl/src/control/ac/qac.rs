@@ -8,12 +8,16 @@ use crate::{
 use rand::Rng;
 
 /// Action-value actor-critic.
+///
+/// The actor update can be regularised by an entropy bonus, weighted by
+/// `entropy_coef`, which is disabled by default.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct QAC<C, P> {
     pub critic: C,
     pub policy: P,
 
     pub alpha: f64,
+    pub entropy_coef: f64,
 }
 
 impl<C, P> QAC<C, P> {
@@ -23,6 +27,7 @@ impl<C, P> QAC<C, P> {
             policy,
 
             alpha,
+            entropy_coef: 0.0,
         }
     }
 }
@@ -38,6 +43,12 @@ impl<C, P> QAC<C, P> {
         let qsa = self.critic.predict_q(s, &t.action);
 
         self.policy.update(s, &t.action, self.alpha * qsa);
+
+        if self.entropy_coef != 0.0 {
+            let ge = self.policy.grad_entropy(s);
+
+            self.policy.update_grad_scaled(&ge.view(), self.alpha * self.entropy_coef);
+        }
     }
 }
 
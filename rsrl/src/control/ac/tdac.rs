@@ -8,6 +8,9 @@ use crate::{
 use rand::Rng;
 
 /// TD-error actor-critic.
+///
+/// Setting `entropy_coef` above zero regularises the actor towards higher
+/// entropy policies by ascending `alpha * entropy_coef * grad H(pi(s))`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TDAC<C, P> {
     pub critic: C,
@@ -15,6 +18,7 @@ pub struct TDAC<C, P> {
 
     pub alpha: f64,
     pub gamma: f64,
+    pub entropy_coef: f64,
 }
 
 impl<C, P> TDAC<C, P> {
@@ -25,6 +29,7 @@ impl<C, P> TDAC<C, P> {
 
             alpha,
             gamma,
+            entropy_coef: 0.0,
         }
     }
 }
@@ -46,6 +51,12 @@ where
 
         self.critic.handle_transition(t);
         self.policy.update(s, &t.action, self.alpha * td_error);
+
+        if self.entropy_coef != 0.0 {
+            let ge = self.policy.grad_entropy(s);
+
+            self.policy.update_grad_scaled(&ge.view(), self.alpha * self.entropy_coef);
+        }
     }
 
     fn handle_terminal(&mut self) {
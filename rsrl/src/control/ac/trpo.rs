@@ -0,0 +1,211 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateFunction},
+    policies::{Policy, DifferentiablePolicy},
+    prediction::ValuePredictor,
+};
+use ndarray::{Array1, Array2};
+use rand::Rng;
+use super::GAE;
+
+/// Solve `F x = b` for symmetric positive-definite `F`, given only the
+/// matrix-vector product, by the method of conjugate gradients.
+fn conjugate_gradient(
+    fvp: impl Fn(&Array1<f64>) -> Array1<f64>,
+    b: &Array1<f64>,
+    n_iterations: usize,
+    tol: f64,
+) -> Array1<f64> {
+    let mut x = Array1::zeros(b.len());
+    let mut r = b.clone();
+    let mut p = b.clone();
+    let mut rr = r.dot(&r);
+
+    for _ in 0..n_iterations {
+        if rr < tol { break; }
+
+        let fp = fvp(&p);
+        let alpha = rr / p.dot(&fp);
+
+        x.scaled_add(alpha, &p);
+        r.scaled_add(-alpha, &fp);
+
+        let rr_new = r.dot(&r);
+
+        p = &r + &(rr_new / rr * &p);
+        rr = rr_new;
+    }
+
+    x
+}
+
+/// Trust-region natural actor-critic.
+///
+/// A batch variant of `NAC` in which the natural gradient of the surrogate
+/// objective, `E[rho A]`, is found by conjugate gradient on products with the
+/// empirical Fisher information matrix, `F = E[grad log pi grad log pi^T] +
+/// damping I`, built from `DifferentiablePolicy::grad_log`. The step is scaled
+/// so that the quadratic approximation of the KL divergence equals `max_kl`,
+/// then shrunk by `backtrack_ratio` until the sample estimate of the KL
+/// divergence from the behaviour policy is within `max_kl` and the surrogate
+/// improves, for at most `max_backtracks` attempts. Only additive updates are
+/// made to the policy, so any `DifferentiablePolicy`, including `Gaussian`,
+/// may be used.
+///
+/// The advantages are estimated with `GAE(gamma, lambda)`, and the critic is
+/// regressed onto the corresponding lambda-returns with step size `beta`.
+///
+/// # References
+/// - Kakade, S. (2002). A natural policy gradient. In Advances in Neural
+///   Information Processing Systems, pp. 1531–1538.
+/// - Schulman, J., Levine, S., Abbeel, P., Jordan, M., Moritz, P. (2015). Trust
+///   region policy optimization. In Proceedings of the 32nd International
+///   Conference on Machine Learning, pp. 1889–1897.
+#[derive(Parameterised)]
+pub struct TRPO<C, P> {
+    #[weights] pub policy: P,
+    pub critic: C,
+
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+    pub max_kl: f64,
+
+    pub damping: f64,
+    pub cg_iterations: usize,
+    pub max_backtracks: usize,
+    pub backtrack_ratio: f64,
+}
+
+impl<C, P> TRPO<C, P> {
+    pub fn new(policy: P, critic: C, beta: f64, gamma: f64, max_kl: f64) -> Self {
+        TRPO {
+            policy,
+            critic,
+
+            beta,
+            gamma,
+            lambda: 1.0,
+            max_kl,
+
+            damping: 1e-3,
+            cg_iterations: 10,
+            max_backtracks: 10,
+            backtrack_ratio: 0.5,
+        }
+    }
+}
+
+impl<C, P> TRPO<C, P> {
+    /// Return the surrogate objective and the estimated KL divergence from the
+    /// behaviour policy over the batch.
+    fn evaluate<S>(
+        &self,
+        batch: &[Transition<S, P::Action>],
+        advantages: &[f64],
+        log_probs_old: &[f64],
+    ) -> (f64, f64)
+    where
+        P: Policy<S>,
+    {
+        let n = batch.len() as f64;
+        let (surrogate, kl) = batch.iter().zip(advantages).zip(log_probs_old).fold(
+            (0.0, 0.0),
+            |(surrogate, kl), ((t, adv), lp_old)| {
+                let lp = self.policy.probability(t.from.state(), &t.action).ln();
+
+                (surrogate + (lp - lp_old).exp() * adv, kl + lp_old - lp)
+            },
+        );
+
+        (surrogate / n, kl / n)
+    }
+}
+
+impl<S, C, P> BatchLearner<S, P::Action> for TRPO<C, P>
+where
+    S: PartialEq,
+    C: StateFunction<S, Output = f64>,
+    P: DifferentiablePolicy<S>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        if batch.is_empty() { return; }
+
+        let n = batch.len() as f64;
+        let dim = self.policy.weights_dim();
+
+        let advantages = GAE::new(self.gamma, self.lambda).advantages(&*self, batch);
+        let returns: Vec<f64> = batch.iter().zip(advantages.iter()).map(|(t, adv)| {
+            adv + self.critic.evaluate(t.from.state())
+        }).collect();
+        let log_probs_old: Vec<f64> = batch.iter().map(|t| {
+            self.policy.probability(t.from.state(), &t.action).ln()
+        }).collect();
+
+        // Score functions of each sample, flattened:
+        let scores: Vec<Array1<f64>> = batch.iter().map(|t| {
+            self.policy.grad_log(t.from.state(), &t.action).iter().cloned().collect()
+        }).collect();
+
+        let grad = scores.iter().zip(advantages.iter()).fold(
+            Array1::zeros(dim[0] * dim[1]),
+            |acc, (g, &adv)| acc + &(g * adv),
+        ) / n;
+
+        let damping = self.damping;
+        let fvp = |v: &Array1<f64>| -> Array1<f64> {
+            scores.iter().fold(v * damping, |acc, g| acc + &(g * (g.dot(v) / n)))
+        };
+
+        let direction = conjugate_gradient(fvp, &grad, self.cg_iterations, 1e-10);
+        let shs = direction.dot(&fvp(&direction));
+
+        if shs > 0.0 {
+            let step: Array2<f64> = (direction * (2.0 * self.max_kl / shs).sqrt())
+                .into_shape(dim)
+                .unwrap();
+
+            let (surrogate_old, _) = self.evaluate(batch, &advantages, &log_probs_old);
+            let mut scale = 1.0;
+
+            // Backtracking line search; the policy is left unchanged on failure:
+            for _ in 0..self.max_backtracks {
+                self.policy.update_grad_scaled(&step.view(), scale);
+
+                let (surrogate, kl) = self.evaluate(batch, &advantages, &log_probs_old);
+
+                if kl <= self.max_kl && surrogate > surrogate_old { break; }
+
+                self.policy.update_grad_scaled(&step.view(), -scale);
+                scale *= self.backtrack_ratio;
+            }
+        }
+
+        for (t, ret) in batch.iter().zip(returns) {
+            let s = t.from.state();
+
+            self.critic.update(s, self.beta * (ret - self.critic.evaluate(s)));
+        }
+    }
+}
+
+impl<S, C, P> ValuePredictor<S> for TRPO<C, P>
+where
+    C: StateFunction<S, Output = f64>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.critic.evaluate(s)
+    }
+}
+
+impl<S, C, P: Policy<S>> Controller<S, P::Action> for TRPO<C, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
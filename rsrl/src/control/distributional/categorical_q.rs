@@ -0,0 +1,203 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{EnumerableStateActionFunction, Parameterised, Weights, WeightsView, WeightsViewMut},
+    prediction::{ValuePredictor, ActionValuePredictor, DistributionPredictor},
+    utils::{argmax_choose, argmax_choose_rng},
+};
+use rand::Rng;
+use super::criteria;
+
+/// Categorical distributional Q-learning.
+///
+/// Represents the return distribution of each action by a softmax over
+/// `n_atoms` equally spaced atoms on `[v_min, v_max]`, with logits given by
+/// the `q_func` outputs `a * n_atoms + i`. The distributional Bellman target
+/// is projected onto the support, and the logits are trained to minimise the
+/// cross-entropy to it.
+///
+/// Actions are ranked by the conditional value at risk of their return at
+/// `cvar_level`, which reduces to the mean for the default level of 1. The
+/// behaviour policy is epsilon-greedy with respect to the same criterion, with
+/// `epsilon = 0` by default.
+///
+/// # References
+/// - Bellemare, M. G., Dabney, W., Munos, R. (2017). A distributional
+///   perspective on reinforcement learning. In Proceedings of the 34th
+///   International Conference on Machine Learning, pp. 449–458.
+/// - Bellemare, M. G., Le Roux, N., Castro, P. S., Moitra, S. (2019).
+///   Distributional reinforcement learning with linear function
+///   approximation. In Proceedings of the 22nd International Conference on
+///   Artificial Intelligence and Statistics, pp. 2203–2211.
+#[derive(Parameterised)]
+pub struct CategoricalQLearning<Q> {
+    #[weights] pub q_func: Q,
+
+    pub n_actions: usize,
+    pub atoms: Vec<f64>,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub epsilon: f64,
+    pub cvar_level: f64,
+}
+
+impl<Q> CategoricalQLearning<Q> {
+    pub fn new(
+        q_func: Q,
+        n_actions: usize,
+        v_min: f64,
+        v_max: f64,
+        n_atoms: usize,
+        alpha: f64,
+        gamma: f64,
+    ) -> Self {
+        assert!(n_atoms >= 2, "CategoricalQLearning requires at least two atoms.");
+
+        let delta = (v_max - v_min) / (n_atoms - 1) as f64;
+
+        CategoricalQLearning {
+            q_func,
+
+            n_actions,
+            atoms: (0..n_atoms).map(|i| v_min + delta * i as f64).collect(),
+
+            alpha,
+            gamma,
+            epsilon: 0.0,
+            cvar_level: 1.0,
+        }
+    }
+
+    /// Return the probabilities of the atoms for each action in state `s`.
+    pub fn probabilities<S>(&self, s: &S) -> Vec<Vec<f64>>
+    where
+        Q: EnumerableStateActionFunction<S>,
+    {
+        self.q_func.evaluate_all(s).chunks(self.atoms.len()).map(|logits| {
+            let max = logits.iter().fold(f64::MIN, |acc, &x| acc.max(x));
+            let exps: Vec<f64> = logits.iter().map(|x| (x - max).exp()).collect();
+            let z: f64 = exps.iter().sum();
+
+            exps.into_iter().map(|x| x / z).collect()
+        }).collect()
+    }
+
+    fn distributions<S>(&self, s: &S) -> Vec<Vec<(f64, f64)>>
+    where
+        Q: EnumerableStateActionFunction<S>,
+    {
+        self.probabilities(s).into_iter().map(|ps| {
+            self.atoms.iter().cloned().zip(ps).collect()
+        }).collect()
+    }
+
+    /// Project the distribution `r + gamma Z` onto the support.
+    fn project(&self, reward: f64, next: Option<&[f64]>) -> Vec<f64> {
+        let n = self.atoms.len();
+        let v_min = self.atoms[0];
+        let v_max = self.atoms[n - 1];
+        let delta = (v_max - v_min) / (n - 1) as f64;
+
+        let mut target = vec![0.0; n];
+        let mut add = |z: f64, p: f64| {
+            let b = (z.max(v_min).min(v_max) - v_min) / delta;
+            let (l, u) = (b.floor() as usize, (b.ceil() as usize).min(n - 1));
+
+            if l == u {
+                target[l] += p;
+            } else {
+                target[l] += p * (u as f64 - b);
+                target[u] += p * (b - l as f64);
+            }
+        };
+
+        match next {
+            Some(ps) => {
+                for (z, &p) in self.atoms.iter().zip(ps.iter()) {
+                    add(reward + self.gamma * z, p);
+                }
+            },
+            None => add(reward, 1.0),
+        }
+
+        target
+    }
+}
+
+impl<S, Q> OnlineLearner<S, usize> for CategoricalQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+
+        let target = if t.terminated() {
+            self.project(t.reward, None)
+        } else {
+            let ns = t.to.state();
+            let scores = criteria(self.distributions(ns), self.cvar_level);
+            let na = argmax_choose(&scores).1;
+
+            self.project(t.reward, Some(&self.probabilities(ns)[na]))
+        };
+
+        // Gradient of the cross-entropy with respect to the logits:
+        let n = self.atoms.len();
+        let ps = &self.probabilities(s)[t.action];
+        let mut errors = vec![0.0; self.n_actions * n];
+
+        for i in 0..n {
+            errors[t.action * n + i] = self.alpha * (target[i] - ps[i]);
+        }
+
+        self.q_func.update_all(s, errors);
+    }
+}
+
+impl<S, Q> ValuePredictor<S> for CategoricalQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        let a = argmax_choose(&criteria(self.distributions(s), self.cvar_level)).1;
+
+        self.predict_q(s, &a)
+    }
+}
+
+impl<S, Q> ActionValuePredictor<S, usize> for CategoricalQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 {
+        self.distributions(s)[*a].iter().map(|(x, p)| x * p).sum()
+    }
+}
+
+impl<S, Q> DistributionPredictor<S, usize> for CategoricalQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_distribution(&self, s: &S, a: &usize) -> Vec<(f64, f64)> {
+        self.distributions(s).swap_remove(*a)
+    }
+}
+
+impl<S, Q> Controller<S, usize> for CategoricalQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> usize {
+        argmax_choose_rng(rng, &criteria(self.distributions(s), self.cvar_level)).1
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> usize {
+        if rng.gen_bool(self.epsilon) {
+            rng.gen_range(0, self.n_actions)
+        } else {
+            self.sample_target(rng, s)
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Distributional control agents that learn the full distribution of returns.
+use crate::prediction::cvar;
+
+import_all!(categorical_q);
+import_all!(quantile_q);
+
+/// Score each action's return distribution by its conditional value at risk
+/// at `level`.
+fn criteria(dists: Vec<Vec<(f64, f64)>>, level: f64) -> Vec<f64> {
+    dists.into_iter().map(|d| cvar(d, level)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        control::Controller,
+        domains::{Observation, Transition},
+        fa::{
+            linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+            tabular::Tabular,
+        },
+        prediction::DistributionPredictor,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use super::{CategoricalQLearning, QuantileQLearning, cvar};
+
+    /// One-step bandit with a safe arm, paying 4, and a risky arm, paying 0 or
+    /// 10 with equal probability.
+    fn bandit<S: Clone>(agent: &mut impl OnlineLearner<S, usize>, s: S, n_steps: usize) {
+        for i in 0..n_steps {
+            let action = i % 2;
+            let reward = if action == 0 { 4.0 } else if i % 4 == 1 { 0.0 } else { 10.0 };
+
+            agent.handle_transition(&Transition {
+                from: Observation::Full(s.clone()),
+                action,
+                reward,
+                to: Observation::Terminal(s.clone()),
+            });
+        }
+    }
+
+    fn check<S>(agent: &mut (impl DistributionPredictor<S, usize> + Controller<S, usize>), s: &S) {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert!((agent.predict_q(s, &0) - 4.0).abs() < 0.5);
+        assert!((agent.predict_q(s, &1) - 5.0).abs() < 0.5);
+
+        assert!(agent.predict_quantile(s, &1, 0.25) < 1.0);
+        assert!(agent.predict_quantile(s, &1, 0.75) > 9.0);
+        assert!(agent.predict_cvar(s, &1, 0.5) < 1.0);
+
+        // Risk-neutral selection favours the risky arm:
+        assert_eq!(agent.sample_target(&mut rng, s), 1);
+    }
+
+    #[test]
+    fn test_categorical_tabular() {
+        let mut agent = CategoricalQLearning::new(Tabular::zeros([1, 22]), 2, 0.0, 10.0, 11, 0.1, 0.9);
+
+        bandit(&mut agent, 0, 4000);
+        check(&mut agent, &0);
+
+        agent.cvar_level = 0.5;
+
+        assert_eq!(agent.sample_target(&mut StdRng::seed_from_u64(0), &0), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_categorical_one_atom() {
+        CategoricalQLearning::new(Tabular::zeros([1, 2]), 2, 0.0, 10.0, 1, 0.1, 0.9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quantile_no_quantiles() {
+        QuantileQLearning::new(Tabular::zeros([1, 2]), 2, 0, 0.1, 0.9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cvar_zero_level() { cvar(vec![(0.0, 0.5), (1.0, 0.5)], 0.0); }
+
+    #[test]
+    fn test_quantile_linear() {
+        let fa = LFA::vector(Polynomial::new(1, 1).with_constant(), SGD(1.0), 8);
+        let mut agent = QuantileQLearning::new(fa, 2, 4, 0.05, 0.9);
+
+        bandit(&mut agent, vec![0.0], 8000);
+        check(&mut agent, &vec![0.0]);
+
+        agent.cvar_level = 0.5;
+
+        assert_eq!(agent.sample_target(&mut StdRng::seed_from_u64(0), &vec![0.0]), 0);
+    }
+}
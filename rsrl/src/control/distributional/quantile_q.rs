@@ -0,0 +1,158 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{EnumerableStateActionFunction, Parameterised, Weights, WeightsView, WeightsViewMut},
+    prediction::{ValuePredictor, ActionValuePredictor, DistributionPredictor},
+    utils::{argmax_choose, argmax_choose_rng},
+};
+use rand::Rng;
+use super::criteria;
+
+/// Quantile-regression distributional Q-learning.
+///
+/// Represents the return distribution of each action by `n_quantiles`
+/// equally weighted atoms, given by the `q_func` outputs `a * n_quantiles +
+/// i`, which estimate the quantiles at the midpoints `(2i + 1) / (2
+/// n_quantiles)`. Each atom follows the subgradient of the quantile
+/// regression loss against the atoms of the distributional Bellman target.
+///
+/// Actions are ranked by the conditional value at risk of their return at
+/// `cvar_level`, which reduces to the mean for the default level of 1. The
+/// behaviour policy is epsilon-greedy with respect to the same criterion, with
+/// `epsilon = 0` by default.
+///
+/// # References
+/// - Dabney, W., Rowland, M., Bellemare, M. G., Munos, R. (2018).
+///   Distributional reinforcement learning with quantile regression. In
+///   Proceedings of the 32nd AAAI Conference on Artificial Intelligence, pp.
+///   2892–2901.
+#[derive(Parameterised)]
+pub struct QuantileQLearning<Q> {
+    #[weights] pub q_func: Q,
+
+    pub n_actions: usize,
+    pub n_quantiles: usize,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub epsilon: f64,
+    pub cvar_level: f64,
+}
+
+impl<Q> QuantileQLearning<Q> {
+    pub fn new(q_func: Q, n_actions: usize, n_quantiles: usize, alpha: f64, gamma: f64) -> Self {
+        assert!(n_quantiles >= 1, "QuantileQLearning requires at least one quantile.");
+
+        QuantileQLearning {
+            q_func,
+
+            n_actions,
+            n_quantiles,
+
+            alpha,
+            gamma,
+            epsilon: 0.0,
+            cvar_level: 1.0,
+        }
+    }
+
+    /// Return the estimated quantiles of the return for each action in state
+    /// `s`.
+    pub fn quantiles<S>(&self, s: &S) -> Vec<Vec<f64>>
+    where
+        Q: EnumerableStateActionFunction<S>,
+    {
+        self.q_func.evaluate_all(s).chunks(self.n_quantiles).map(|qs| qs.to_vec()).collect()
+    }
+
+    fn distributions<S>(&self, s: &S) -> Vec<Vec<(f64, f64)>>
+    where
+        Q: EnumerableStateActionFunction<S>,
+    {
+        let p = 1.0 / self.n_quantiles as f64;
+
+        self.quantiles(s).into_iter().map(|qs| qs.into_iter().map(|x| (x, p)).collect()).collect()
+    }
+}
+
+impl<S, Q> OnlineLearner<S, usize> for QuantileQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+
+        let targets: Vec<f64> = if t.terminated() {
+            vec![t.reward]
+        } else {
+            let ns = t.to.state();
+            let scores = criteria(self.distributions(ns), self.cvar_level);
+            let na = argmax_choose(&scores).1;
+
+            self.quantiles(ns).swap_remove(na).into_iter().map(|x| t.reward + self.gamma * x).collect()
+        };
+
+        let n = self.n_quantiles;
+        let m = targets.len() as f64;
+        let thetas = self.quantiles(s).swap_remove(t.action);
+        let mut errors = vec![0.0; self.n_actions * n];
+
+        for (i, theta) in thetas.into_iter().enumerate() {
+            let tau = (2 * i + 1) as f64 / (2 * n) as f64;
+            let below = targets.iter().filter(|&&y| y < theta).count() as f64 / m;
+
+            errors[t.action * n + i] = self.alpha * (tau - below);
+        }
+
+        self.q_func.update_all(s, errors);
+    }
+}
+
+impl<S, Q> ValuePredictor<S> for QuantileQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        let a = argmax_choose(&criteria(self.distributions(s), self.cvar_level)).1;
+
+        self.predict_q(s, &a)
+    }
+}
+
+impl<S, Q> ActionValuePredictor<S, usize> for QuantileQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 {
+        let qs = self.quantiles(s).swap_remove(*a);
+
+        qs.iter().sum::<f64>() / qs.len() as f64
+    }
+}
+
+impl<S, Q> DistributionPredictor<S, usize> for QuantileQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_distribution(&self, s: &S, a: &usize) -> Vec<(f64, f64)> {
+        self.distributions(s).swap_remove(*a)
+    }
+}
+
+impl<S, Q> Controller<S, usize> for QuantileQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> usize {
+        argmax_choose_rng(rng, &criteria(self.distributions(s), self.cvar_level)).1
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> usize {
+        if rng.gen_bool(self.epsilon) {
+            rng.gen_range(0, self.n_actions)
+        } else {
+            self.sample_target(rng, s)
+        }
+    }
+}
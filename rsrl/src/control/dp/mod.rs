@@ -0,0 +1,140 @@
+//! Dynamic programming methods for finite MDPs with known dynamics.
+use crate::{
+    domains::FiniteMDP,
+    fa::tabular::Tabular,
+    policies::Greedy,
+};
+
+import_all!(value_iteration);
+import_all!(policy_iteration);
+import_all!(modified_policy_iteration);
+
+/// Value function and greedy policy computed by dynamic programming.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// State values, indexed by state.
+    pub v_func: Vec<f64>,
+
+    /// Action values, indexed by state and action.
+    pub q_func: Tabular,
+
+    /// Number of iterations performed before termination.
+    pub n_iterations: usize,
+}
+
+impl Solution {
+    fn new<M: FiniteMDP>(mdp: &M, v_func: Vec<f64>, gamma: f64, n_iterations: usize) -> Self {
+        let q_func = Tabular::new((0..mdp.n_actions()).map(|a| {
+            (0..mdp.n_states()).map(|s| if mdp.is_terminal(s) {
+                0.0
+            } else {
+                backup(mdp, &v_func, gamma, s, a)
+            }).collect()
+        }).collect());
+
+        Solution { v_func, q_func, n_iterations, }
+    }
+
+    /// Return the policy that is greedy with respect to `q_func`.
+    pub fn policy(&self) -> Greedy<Tabular> { Greedy::new(self.q_func.clone()) }
+}
+
+/// One-step expected return of taking action `a` in state `s` and following
+/// on with values `v`.
+fn backup<M: FiniteMDP>(mdp: &M, v: &[f64], gamma: f64, s: usize, a: usize) -> f64 {
+    let nv = mdp.transition_probabilities(s, a).into_iter()
+        .fold(0.0, |acc, (ns, p)| acc + p * v[ns]);
+
+    mdp.expected_reward(s, a) + gamma * nv
+}
+
+/// Greedy action and associated value in state `s` with respect to values `v`.
+fn greedy<M: FiniteMDP>(mdp: &M, v: &[f64], gamma: f64, s: usize) -> (usize, f64) {
+    (0..mdp.n_actions()).fold((0, f64::NEG_INFINITY), |(a_max, q_max), a| {
+        let q = backup(mdp, v, gamma, s, a);
+
+        if q > q_max { (a, q) } else { (a_max, q_max) }
+    })
+}
+
+/// Iteratively evaluate a deterministic `policy` in place, performing at most
+/// `max_sweeps` sweeps, or until the largest change falls below `tol`.
+fn evaluate_policy<M: FiniteMDP>(
+    mdp: &M,
+    policy: &[usize],
+    v: &mut [f64],
+    gamma: f64,
+    tol: f64,
+    max_sweeps: usize,
+) {
+    for _ in 0..max_sweeps {
+        let mut delta: f64 = 0.0;
+
+        for s in (0..mdp.n_states()).filter(|&s| !mdp.is_terminal(s)) {
+            let new_v = backup(mdp, v, gamma, s, policy[s]);
+
+            delta = delta.max((new_v - v[s]).abs());
+            v[s] = new_v;
+        }
+
+        if delta < tol { break; }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domains::{CliffWalk, FiniteMDP, GridMaze},
+        fa::StateActionFunction,
+        policies::Policy,
+    };
+    use super::*;
+
+    const LAYOUT: &str = ". . . . G\n. # # . X\n. . . . .\nS # . X .";
+
+    #[test]
+    fn test_cliff_walk() {
+        let cw = CliffWalk::default();
+        let solution = ValueIteration::new(0.9, 1e-10, 1000).solve(&cw);
+
+        assert!((solution.v_func[0] - 50.0 * 0.9f64.powi(12)).abs() < 1e-8);
+
+        let policy = solution.policy();
+
+        assert_eq!(policy.mpa(&0), 0);
+        assert_eq!(policy.mpa(&cw.state_index(&[5, 1])), 1);
+        assert_eq!(policy.mpa(&cw.state_index(&[11, 1])), 2);
+    }
+
+    #[test]
+    fn test_consistency() {
+        let gm = GridMaze::new(LAYOUT, 0.1);
+
+        let vi = ValueIteration::new(0.95, 1e-10, 10000).solve(&gm);
+        let pi = PolicyIteration::new(0.95, 1e-10, 100).solve(&gm);
+        let mpi = ModifiedPolicyIteration::new(0.95, 5, 1e-10, 10000).solve(&gm);
+
+        for s in 0..gm.n_states() {
+            assert!((vi.v_func[s] - pi.v_func[s]).abs() < 1e-6);
+            assert!((vi.v_func[s] - mpi.v_func[s]).abs() < 1e-6);
+
+            for a in 0..gm.n_actions() {
+                assert!((vi.q_func.evaluate(&s, &a) - pi.q_func.evaluate(&s, &a)).abs() < 1e-6);
+            }
+        }
+
+        assert!(pi.n_iterations < vi.n_iterations);
+    }
+
+    #[test]
+    fn test_policy_iteration_undiscounted() {
+        let gm = GridMaze::new(LAYOUT, 0.0);
+
+        // The initial policy of always moving north never terminates:
+        let solution = PolicyIteration::new(1.0, 1e-10, 100).solve(&gm);
+
+        assert!(solution.n_iterations < 100);
+        assert_eq!(solution.v_func[gm.state_index(&[0, 0])], -7.0);
+        assert_eq!(solution.v_func[gm.state_index(&[4, 1])], -4.0);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::domains::FiniteMDP;
+use super::{Solution, evaluate_policy, greedy};
+
+/// Modified policy iteration.
+///
+/// Interleaves greedy policy improvement with `n_evaluations` sweeps of
+/// partial policy evaluation, interpolating between value iteration
+/// (`n_evaluations = 0`) and policy iteration (`n_evaluations -> inf`).
+/// Terminates once an improvement step changes no value by more than `tol`,
+/// or `max_iterations` improvements have been made.
+///
+/// # References
+/// - Puterman, M. L., Shin, M. C. (1978). Modified policy iteration algorithms
+///   for discounted Markov decision problems. Management Science,
+///   24(11):1127–1137.
+#[derive(Clone, Copy, Debug)]
+pub struct ModifiedPolicyIteration {
+    pub gamma: f64,
+    pub n_evaluations: usize,
+    pub tol: f64,
+
+    pub max_iterations: usize,
+}
+
+impl ModifiedPolicyIteration {
+    pub fn new(gamma: f64, n_evaluations: usize, tol: f64, max_iterations: usize) -> Self {
+        ModifiedPolicyIteration {
+            gamma,
+            n_evaluations,
+            tol,
+
+            max_iterations,
+        }
+    }
+
+    pub fn solve<M: FiniteMDP>(&self, mdp: &M) -> Solution {
+        let mut v = vec![0.0; mdp.n_states()];
+        let mut policy = vec![0; mdp.n_states()];
+        let mut n_iterations = 0;
+
+        while n_iterations < self.max_iterations {
+            let mut delta: f64 = 0.0;
+
+            for s in (0..mdp.n_states()).filter(|&s| !mdp.is_terminal(s)) {
+                let (a, new_v) = greedy(mdp, &v, self.gamma, s);
+
+                delta = delta.max((new_v - v[s]).abs());
+                policy[s] = a;
+                v[s] = new_v;
+            }
+
+            n_iterations += 1;
+
+            if delta < self.tol { break; }
+
+            evaluate_policy(mdp, &policy, &mut v, self.gamma, 0.0, self.n_evaluations);
+        }
+
+        Solution::new(mdp, v, self.gamma, n_iterations)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::domains::FiniteMDP;
+use super::{Solution, backup, evaluate_policy, greedy};
+
+/// Policy iteration.
+///
+/// Alternates between evaluating the current deterministic policy to within
+/// `tol` and improving it greedily, terminating once the policy is stable or
+/// `max_iterations` improvements have been made. Each evaluation performs at
+/// most `max_sweeps` sweeps, so that it terminates even when `gamma = 1` and
+/// the current policy never reaches a terminal state; the values of such an
+/// improper policy then diverge, and it is improved upon in the next step.
+///
+/// # References
+/// - Howard, R. A. (1960). Dynamic Programming and Markov Processes. MIT
+///   Press.
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 4.3.
+#[derive(Clone, Copy, Debug)]
+pub struct PolicyIteration {
+    pub gamma: f64,
+    pub tol: f64,
+
+    pub max_iterations: usize,
+    pub max_sweeps: usize,
+}
+
+impl PolicyIteration {
+    pub fn new(gamma: f64, tol: f64, max_iterations: usize) -> Self {
+        PolicyIteration {
+            gamma,
+            tol,
+
+            max_iterations,
+            max_sweeps: 1000,
+        }
+    }
+
+    pub fn solve<M: FiniteMDP>(&self, mdp: &M) -> Solution {
+        let mut v = vec![0.0; mdp.n_states()];
+        let mut policy = vec![0; mdp.n_states()];
+        let mut n_iterations = 0;
+
+        while n_iterations < self.max_iterations {
+            evaluate_policy(mdp, &policy, &mut v, self.gamma, self.tol, self.max_sweeps);
+
+            n_iterations += 1;
+
+            if !improve_policy(mdp, &mut policy, &v, self.gamma, self.tol) { break; }
+        }
+
+        Solution::new(mdp, v, self.gamma, n_iterations)
+    }
+}
+
+/// Make `policy` greedy with respect to `v`, retaining the current action
+/// unless another is better by more than `tol`. Returns true if any action
+/// changed.
+pub(super) fn improve_policy<M: FiniteMDP>(
+    mdp: &M,
+    policy: &mut [usize],
+    v: &[f64],
+    gamma: f64,
+    tol: f64,
+) -> bool {
+    let mut changed = false;
+
+    for s in (0..mdp.n_states()).filter(|&s| !mdp.is_terminal(s)) {
+        let (a, q) = greedy(mdp, v, gamma, s);
+
+        if q - backup(mdp, v, gamma, s, policy[s]) > tol {
+            policy[s] = a;
+            changed = true;
+        }
+    }
+
+    changed
+}
@@ -0,0 +1,53 @@
+use crate::domains::FiniteMDP;
+use super::{Solution, greedy};
+
+/// Value iteration.
+///
+/// Performs in-place sweeps of the Bellman optimality backup until the largest
+/// change in value falls below `tol`, or `max_iterations` sweeps have been
+/// made.
+///
+/// # References
+/// - Bellman, R. (1957). Dynamic Programming. Princeton University Press.
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 4.4.
+#[derive(Clone, Copy, Debug)]
+pub struct ValueIteration {
+    pub gamma: f64,
+    pub tol: f64,
+
+    pub max_iterations: usize,
+}
+
+impl ValueIteration {
+    pub fn new(gamma: f64, tol: f64, max_iterations: usize) -> Self {
+        ValueIteration {
+            gamma,
+            tol,
+
+            max_iterations,
+        }
+    }
+
+    pub fn solve<M: FiniteMDP>(&self, mdp: &M) -> Solution {
+        let mut v = vec![0.0; mdp.n_states()];
+        let mut n_iterations = 0;
+
+        while n_iterations < self.max_iterations {
+            let mut delta: f64 = 0.0;
+
+            for s in (0..mdp.n_states()).filter(|&s| !mdp.is_terminal(s)) {
+                let (_, new_v) = greedy(mdp, &v, self.gamma, s);
+
+                delta = delta.max((new_v - v[s]).abs());
+                v[s] = new_v;
+            }
+
+            n_iterations += 1;
+
+            if delta < self.tol { break; }
+        }
+
+        Solution::new(mdp, v, self.gamma, n_iterations)
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{fa::linear::LFAGradient, linalg::MatrixLike};
+use super::{MirrorProxGQ, MirrorProxRule};
+
+/// GTD2 update direction, `w^T phi(s) (grad q(s, a) - gamma grad q(s', a'))`.
+pub struct GTD2Rule;
+
+impl MirrorProxRule for GTD2Rule {
+    fn direction(
+        _: f64,
+        estimate: f64,
+        gamma: f64,
+        grad_s: &LFAGradient,
+        grad_ns: Option<&LFAGradient>,
+    ) -> LFAGradient {
+        match grad_ns {
+            Some(grad_ns) => {
+                grad_s.clone().combine(grad_ns, move |x, y| estimate * (x - gamma * y))
+            },
+            None => grad_s.clone() * estimate,
+        }
+    }
+}
+
+/// Greedy GTD2 control algorithm with mirror-prox updates.
+///
+/// Follows the GTD2 update direction for the action-value weights, with
+/// optional L1 regularisation; see `MirrorProxGQ`.
+///
+/// # References
+/// - Liu, B., Liu, J., Ghavamzadeh, M., Mahadevan, S., Petrik, M. (2015).
+///   Finite-sample analysis of proximal gradient TD algorithms. In
+///   Proceedings of the 31st Conference on Uncertainty in Artificial
+///   Intelligence, pp. 504–513.
+/// - Mahadevan, S., Liu, B., Thomas, P., Dabney, W., Giguere, S., Jacek, N.,
+///   Gemp, I., Liu, J. (2014). Proximal reinforcement learning: A new theory
+///   of sequential decision making in primal-dual spaces. arXiv:1405.6757.
+pub type GTD2MP<Q, W, PB> = MirrorProxGQ<Q, W, PB, GTD2Rule>;
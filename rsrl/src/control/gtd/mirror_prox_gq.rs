@@ -0,0 +1,209 @@
+use crate::{
+    OnlineLearner, Shared, make_shared,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        StateActionFunction, EnumerableStateActionFunction,
+        linear::{LFAGradient, LinearStateFunction, LinearStateActionFunction},
+    },
+    policies::{Greedy, Policy, EnumerablePolicy},
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::Rng;
+use std::marker::PhantomData;
+use super::{LazyShrinkage, mirror_prox};
+
+/// Rule defining the action-value update direction of a `MirrorProxGQ` agent.
+pub trait MirrorProxRule {
+    /// Return the action-value update direction given the TD residual, the
+    /// auxiliary estimate of its expectation, and the gradients of the
+    /// action-value function at the current pair and, if non-terminal, at the
+    /// next pair.
+    fn direction(
+        residual: f64,
+        estimate: f64,
+        gamma: f64,
+        grad_s: &LFAGradient,
+        grad_ns: Option<&LFAGradient>,
+    ) -> LFAGradient;
+}
+
+/// Greedy gradient-TD control with mirror-prox updates.
+///
+/// Shares the structure of `GreedyGQ`, but takes an extragradient step: the
+/// gradients at the current weights define an intermediate point, and the
+/// gradients at that point are applied to the current weights. The direction
+/// followed by the action-value weights is given by the rule `R`; see
+/// `ProximalGQ` and `GTD2MP`. If `l1` is positive, the action-value weights are
+/// additionally regularised by the proximal operator of the L1 norm after each
+/// step, yielding sparse solutions.
+///
+/// Each update touches only the weights of the active features. The L1
+/// shrinkage owed by inactive weights is accumulated and applied lazily, the
+/// next time their features are active, so weights of features that have not
+/// been seen recently lag until `shrink_all` is called.
+#[derive(Parameterised)]
+pub struct MirrorProxGQ<Q, W, PB, R> {
+    #[weights] pub fa_q: Q,
+    pub fa_w: W,
+
+    pub target_policy: Greedy<Q>,
+    pub behaviour_policy: PB,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub l1: f64,
+
+    shrinkage: LazyShrinkage,
+    rule: PhantomData<R>,
+}
+
+impl<Q: Parameterised, W, PB, R> MirrorProxGQ<Shared<Q>, W, PB, R> {
+    pub fn new(
+        fa_q: Q,
+        fa_w: W,
+        behaviour_policy: PB,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+    ) -> Self {
+        let shrinkage = LazyShrinkage::new(fa_q.weights_dim());
+        let fa_q = make_shared(fa_q);
+
+        MirrorProxGQ {
+            fa_q: fa_q.clone(),
+            fa_w,
+
+            target_policy: Greedy::new(fa_q),
+            behaviour_policy,
+
+            alpha,
+            beta,
+            gamma,
+            l1: 0.0,
+
+            shrinkage,
+            rule: PhantomData,
+        }
+    }
+}
+
+impl<Q: Parameterised, W, PB, R> MirrorProxGQ<Q, W, PB, R> {
+    /// Apply the L1 shrinkage still owed by weights whose features have not
+    /// been active since it accrued.
+    pub fn shrink_all(&mut self) {
+        self.shrinkage.flush(self.fa_q.weights_view_mut());
+    }
+}
+
+/// Return the action-value and auxiliary update directions at the current
+/// weights.
+fn directions<S, Q, W, R>(
+    fa_q: &Q,
+    fa_w: &W,
+    gamma: f64,
+    t: &Transition<S, usize>,
+    next: Option<(usize, &LFAGradient)>,
+    grad_s: &LFAGradient,
+    grad_w: &LFAGradient,
+) -> (LFAGradient, LFAGradient)
+where
+    Q: LinearStateActionFunction<S, usize>,
+    W: LinearStateFunction<S>,
+    R: MirrorProxRule,
+{
+    let s = t.from.state();
+    let qsa = fa_q.evaluate_features(&fa_q.features(s, &t.action), &t.action);
+    let estimate = fa_w.evaluate_features(&fa_w.features(s));
+
+    let residual = match next {
+        Some((na, _)) => {
+            let ns = t.to.state();
+            let nqsa = fa_q.evaluate_features(&fa_q.features(ns, &na), &na);
+
+            t.reward + gamma * nqsa - qsa
+        },
+        None => t.reward - qsa,
+    };
+    let g_q = R::direction(residual, estimate, gamma, grad_s, next.map(|(_, g)| g));
+
+    (g_q, grad_w.clone() * (residual - estimate))
+}
+
+impl<S, Q, W, PB, R> OnlineLearner<S, usize> for MirrorProxGQ<Q, W, PB, R>
+where
+    Q: EnumerableStateActionFunction<S> + LinearStateActionFunction<S, usize>,
+    W: LinearStateFunction<S>,
+    PB: EnumerablePolicy<S>,
+    R: MirrorProxRule,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+        let grad_s = self.fa_q.grad(s, &t.action);
+        let grad_w = self.fa_w.grad(s);
+
+        self.shrinkage.catch_up(self.fa_q.weights_view_mut(), &grad_s);
+
+        let next = if t.terminated() {
+            None
+        } else {
+            let ns = t.to.state();
+
+            // The target policy reads the values of every action:
+            for a in 0..self.fa_q.n_actions() {
+                let grad_ns = self.fa_q.grad(ns, &a);
+
+                self.shrinkage.catch_up(self.fa_q.weights_view_mut(), &grad_ns);
+            }
+
+            let na = self.target_policy.mpa(ns);
+
+            Some((na, self.fa_q.grad(ns, &na)))
+        };
+        let next = next.as_ref().map(|(na, g)| (*na, g));
+        let gamma = self.gamma;
+
+        mirror_prox(
+            &mut self.fa_q, &mut self.fa_w, &mut self.shrinkage,
+            self.alpha, self.beta, self.l1,
+            |fa_q, fa_w| directions::<_, _, _, R>(fa_q, fa_w, gamma, t, next, &grad_s, &grad_w),
+        );
+    }
+}
+
+impl<S, Q, W, PB, R> ValuePredictor<S> for MirrorProxGQ<Q, W, PB, R>
+where
+    Q: StateActionFunction<S, <Greedy<Q> as Policy<S>>::Action, Output = f64>,
+    Greedy<Q>: Policy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_q.evaluate(s, &self.target_policy.mpa(s))
+    }
+}
+
+impl<S, Q, W, PB, R> ActionValuePredictor<S, <Greedy<Q> as Policy<S>>::Action>
+    for MirrorProxGQ<Q, W, PB, R>
+where
+    Q: StateActionFunction<S, <Greedy<Q> as Policy<S>>::Action, Output = f64>,
+    Greedy<Q>: Policy<S>,
+{
+    fn predict_q(&self, s: &S, a: &<Greedy<Q> as Policy<S>>::Action) -> f64 {
+        self.fa_q.evaluate(s, a)
+    }
+}
+
+impl<S, Q, W, PB, R> Controller<S, usize> for MirrorProxGQ<Q, W, PB, R>
+where
+    Q: EnumerableStateActionFunction<S>,
+    PB: EnumerablePolicy<S>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> usize {
+        self.target_policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> usize {
+        self.behaviour_policy.sample(rng, s)
+    }
+}
@@ -1 +1,259 @@
+use crate::{
+    fa::{Parameterised, WeightsViewMut, linear::LFAGradient},
+    linalg::MatrixLike,
+};
+use ndarray::Array2;
+
 import_all!(greedy_gq);
+import_all!(mirror_prox_gq);
+import_all!(proximal_gq);
+import_all!(gtd2_mp);
+
+/// Apply the proximal operator of the L1 norm, shrinking `w` towards zero by
+/// `threshold`.
+fn shrink(w: f64, threshold: f64) -> f64 { w.signum() * (w.abs() - threshold).max(0.0) }
+
+/// Cumulative L1 shrinkage of a weight matrix, applied lazily.
+///
+/// Soft thresholds compose, shrinking by `a` and then by `b` being the same as
+/// shrinking by `a + b`, so the shrinkage owed by a weight that is neither read
+/// nor updated can be deferred until it is. This keeps the cost of the
+/// proximal step proportional to the number of active features.
+struct LazyShrinkage {
+    total: f64,
+    applied: Array2<f64>,
+}
+
+impl LazyShrinkage {
+    fn new(dim: [usize; 2]) -> Self {
+        LazyShrinkage {
+            total: 0.0,
+            applied: Array2::zeros((dim[0], dim[1])),
+        }
+    }
+
+    /// Shrink the weights at the entries of `grad` by the threshold accrued
+    /// since they were last caught up.
+    fn catch_up<G: MatrixLike>(&mut self, mut weights: WeightsViewMut, grad: &G) {
+        let total = self.total;
+        let applied = &mut self.applied;
+
+        grad.for_each(|e| {
+            let owed = total - applied[e.index];
+
+            if owed > 0.0 {
+                weights[e.index] = shrink(weights[e.index], owed);
+                applied[e.index] = total;
+            }
+        });
+    }
+
+    /// Shrink every weight by the threshold accrued since it was last caught up.
+    fn flush(&mut self, mut weights: WeightsViewMut) {
+        let total = self.total;
+
+        ndarray::Zip::from(&mut weights).and(&mut self.applied).apply(|w, applied| {
+            *w = shrink(*w, total - *applied);
+            *applied = total;
+        });
+    }
+}
+
+/// Apply a single mirror-prox update to the action-value weights, `fa_q`, and
+/// auxiliary weights, `fa_w`.
+///
+/// The update directions returned by `directions` at the current weights,
+/// scaled by `alpha` and `alpha * beta` respectively, define an intermediate
+/// point; the directions at that point are then applied to the current
+/// weights, followed by an L1 proximal step of `alpha * l1` on `fa_q`. Only the
+/// entries of the directions found at the current weights are saved, shrunk
+/// and restored, so these must cover every weight read by `directions`, and
+/// must have been caught up by `shrinkage` beforehand.
+fn mirror_prox<Q, W>(
+    fa_q: &mut Q,
+    fa_w: &mut W,
+    shrinkage: &mut LazyShrinkage,
+    alpha: f64,
+    beta: f64,
+    l1: f64,
+    directions: impl Fn(&Q, &W) -> (LFAGradient, LFAGradient),
+) where
+    Q: Parameterised,
+    W: Parameterised,
+{
+    // Extragradient step to the intermediate point:
+    let (d_q, d_w) = directions(fa_q, fa_w);
+
+    let mut q_saved = vec![];
+    let mut w_saved = vec![];
+
+    {
+        let q = fa_q.weights_view();
+        let w = fa_w.weights_view();
+
+        d_q.for_each(|e| q_saved.push((e.index, q[e.index], shrinkage.applied[e.index])));
+        d_w.for_each(|e| w_saved.push((e.index, w[e.index])));
+    }
+
+    d_q.scaled_addto(alpha, &mut fa_q.weights_view_mut());
+    d_w.scaled_addto(alpha * beta, &mut fa_w.weights_view_mut());
+
+    shrinkage.total += alpha * l1;
+    shrinkage.catch_up(fa_q.weights_view_mut(), &d_q);
+
+    // Update from the current point using directions at the intermediate point:
+    let (d_q_mid, d_w_mid) = directions(fa_q, fa_w);
+
+    {
+        let mut q = fa_q.weights_view_mut();
+        let mut w = fa_w.weights_view_mut();
+
+        for (i, x, applied) in q_saved {
+            q[i] = x;
+            shrinkage.applied[i] = applied;
+        }
+
+        w_saved.into_iter().for_each(|(i, x)| w[i] = x);
+    }
+
+    d_q_mid.scaled_addto(alpha, &mut fa_q.weights_view_mut());
+    d_w_mid.scaled_addto(alpha * beta, &mut fa_w.weights_view_mut());
+
+    shrinkage.catch_up(fa_q.weights_view_mut(), &d_q);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        control::Controller,
+        domains::{Observation, Transition},
+        fa::{
+            Parameterised,
+            linear::{LFA, LFAGradient, Features, basis::UniformGrid, optim::SGD},
+        },
+        policies::Random,
+        prediction::ActionValuePredictor,
+        spaces::{Equipartition, ProductSpace},
+    };
+    use ndarray::{arr1, arr2};
+    use rand::{rngs::StdRng, SeedableRng};
+    use super::{GTD2MP, LazyShrinkage, ProximalGQ};
+
+    const N_STATES: usize = 5;
+
+    /// One-hot features over the states of the chain.
+    fn grid() -> UniformGrid {
+        UniformGrid::new(ProductSpace::new(vec![
+            Equipartition::new(0.0, N_STATES as f64, N_STATES)
+        ]))
+    }
+
+    /// Step along a chain on which action 1 moves right, action 0 moves left,
+    /// and reaching the right end terminates with a reward of one.
+    fn step(s: usize, a: usize) -> Transition<Vec<f64>, usize> {
+        let ns = if a == 1 { s + 1 } else { s.saturating_sub(1) };
+        let to = if ns == N_STATES - 1 {
+            Observation::Terminal(vec![ns as f64])
+        } else {
+            Observation::Full(vec![ns as f64])
+        };
+
+        Transition {
+            from: Observation::Full(vec![s as f64]),
+            action: a,
+            reward: if ns == N_STATES - 1 { 1.0 } else { 0.0 },
+            to,
+        }
+    }
+
+    /// Learn off-policy from a uniformly random behaviour policy, and return
+    /// whether the learnt values rank moving right above moving left.
+    fn learns_chain<C>(agent: &mut C) -> bool
+    where
+        C: OnlineLearner<Vec<f64>, usize> + Controller<Vec<f64>, usize>
+            + ActionValuePredictor<Vec<f64>, usize>,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..500 {
+            let mut s = 0;
+
+            for _ in 0..100 {
+                let t = step(s, agent.sample_behaviour(&mut rng, &vec![s as f64]));
+
+                agent.handle_transition(&t);
+
+                if t.terminated() { break; }
+
+                s = t.to.state()[0] as usize;
+            }
+        }
+
+        (0..N_STATES - 1).all(|s| {
+            let s = vec![s as f64];
+
+            agent.predict_q(&s, &1) > agent.predict_q(&s, &0)
+        })
+    }
+
+    #[test]
+    fn test_lazy_shrinkage() {
+        let mut weights = arr2(&[[1.0, -0.05], [-2.0, 0.1]]);
+        let mut shrinkage = LazyShrinkage::new([2, 2]);
+
+        let column = LFAGradient::from_features([2, 2], 0, Features::Dense(arr1(&[1.0, 1.0])));
+
+        shrinkage.total = 0.05;
+        shrinkage.catch_up(weights.view_mut(), &column);
+
+        assert_eq!(weights, arr2(&[[0.95, -0.05], [-1.95, 0.1]]));
+
+        // Deferred shrinkage composes with that already applied:
+        shrinkage.total = 0.1;
+        shrinkage.flush(weights.view_mut());
+
+        assert!((&weights - &arr2(&[[0.9, 0.0], [-1.9, 0.0]])).iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_proximal_gq() {
+        let mut agent = ProximalGQ::new(
+            LFA::vector(grid(), SGD(1.0), 2), LFA::scalar(grid(), SGD(1.0)),
+            Random::new(2), 0.1, 1.0, 0.9,
+        );
+
+        assert!(learns_chain(&mut agent));
+    }
+
+    #[test]
+    fn test_gtd2_mp() {
+        let mut agent = GTD2MP::new(
+            LFA::vector(grid(), SGD(1.0), 2), LFA::scalar(grid(), SGD(1.0)),
+            Random::new(2), 0.1, 1.0, 0.9,
+        );
+
+        assert!(learns_chain(&mut agent));
+    }
+
+    #[test]
+    fn test_proximal_gq_l1() {
+        let new = || ProximalGQ::new(
+            LFA::vector(grid(), SGD(1.0), 2), LFA::scalar(grid(), SGD(1.0)),
+            Random::new(2), 0.1, 1.0, 0.9,
+        );
+        let l1_norm = |w: ndarray::Array2<f64>| w.iter().map(|x| x.abs()).sum::<f64>();
+
+        let mut plain = new();
+        let mut sparse = new();
+
+        sparse.l1 = 0.01;
+
+        assert!(learns_chain(&mut plain));
+        assert!(learns_chain(&mut sparse));
+
+        sparse.shrink_all();
+
+        assert!(l1_norm(sparse.weights()) < l1_norm(plain.weights()));
+    }
+}
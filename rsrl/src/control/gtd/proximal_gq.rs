@@ -0,0 +1,38 @@
+use crate::{fa::linear::LFAGradient, linalg::MatrixLike};
+use super::{MirrorProxGQ, MirrorProxRule};
+
+/// GQ update direction, `delta grad q(s, a) - gamma w^T phi(s) grad q(s', a')`.
+pub struct GQRule;
+
+impl MirrorProxRule for GQRule {
+    fn direction(
+        residual: f64,
+        estimate: f64,
+        gamma: f64,
+        grad_s: &LFAGradient,
+        grad_ns: Option<&LFAGradient>,
+    ) -> LFAGradient {
+        match grad_ns {
+            Some(grad_ns) => {
+                let c = gamma * estimate;
+
+                grad_s.clone().combine(grad_ns, move |x, y| residual * x - c * y)
+            },
+            None => grad_s.clone() * residual,
+        }
+    }
+}
+
+/// Proximal Greedy GQ control algorithm.
+///
+/// Extends `GreedyGQ` with mirror-prox updates and optional L1 regularisation
+/// of the action-value weights; see `MirrorProxGQ`.
+///
+/// # References
+/// - Liu, B., Mahadevan, S., Liu, J. (2012). Regularized off-policy
+///   TD-learning. In Advances in Neural Information Processing Systems 25,
+///   pp. 836–844.
+/// - Mahadevan, S., Liu, B., Thomas, P., Dabney, W., Giguere, S., Jacek, N.,
+///   Gemp, I., Liu, J. (2014). Proximal reinforcement learning: A new theory
+///   of sequential decision making in primal-dual spaces. arXiv:1405.6757.
+pub type ProximalGQ<Q, W, PB> = MirrorProxGQ<Q, W, PB, GQRule>;
@@ -0,0 +1,116 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised},
+    policies::{Policy, DifferentiablePolicy},
+};
+use ndarray::Array2;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Behaviour cloning.
+///
+/// Fits the policy to recorded state-action pairs by maximum likelihood,
+/// ascending the mean of `grad log pi(a | s)` over shuffled mini-batches of
+/// size `mini_batch_size` for `n_epochs` passes of the data. Applies to any
+/// `DifferentiablePolicy`, e.g. `Softmax` for discrete actions or `Gaussian`
+/// and `Beta` for continuous actions. As a `BatchLearner`, only the source
+/// states and actions of each transition are used. The mini-batches are drawn
+/// using an internal generator, which may be seeded with `with_seed`.
+///
+/// # References
+/// - Pomerleau, D. A. (1991). Efficient training of artificial neural networks
+///   for autonomous navigation. Neural Computation, 3(1), 88–97.
+#[derive(Parameterised)]
+pub struct BehaviourCloning<P> {
+    #[weights] pub policy: P,
+
+    pub alpha: f64,
+    pub n_epochs: usize,
+    pub mini_batch_size: usize,
+
+    rng: StdRng,
+}
+
+impl<P> BehaviourCloning<P> {
+    pub fn new(policy: P, alpha: f64) -> Self {
+        BehaviourCloning {
+            policy,
+
+            alpha,
+            n_epochs: 1,
+            mini_batch_size: 32,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the generator used to shuffle the mini-batches.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn fit_with<'a, S: 'a>(
+        &mut self,
+        n_samples: usize,
+        sample: impl Fn(usize) -> (&'a S, &'a P::Action),
+    )
+    where
+        P: DifferentiablePolicy<S>,
+        P::Action: 'a,
+    {
+        let mut indices: Vec<usize> = (0..n_samples).collect();
+
+        for _ in 0..self.n_epochs {
+            indices.shuffle(&mut self.rng);
+
+            for mb in indices.chunks(self.mini_batch_size.max(1)) {
+                let grad = mb.iter().fold(Array2::zeros(self.policy.weights_dim()), |acc, &i| {
+                    let (s, a) = sample(i);
+
+                    acc + self.policy.grad_log(s, a)
+                });
+
+                self.policy.update_grad_scaled(&grad.view(), self.alpha / mb.len() as f64);
+            }
+        }
+    }
+
+    /// Fit the policy to a set of `(state, action)` pairs.
+    pub fn fit<S>(&mut self, samples: &[(S, P::Action)])
+    where
+        P: DifferentiablePolicy<S>,
+    {
+        self.fit_with(samples.len(), |i| (&samples[i].0, &samples[i].1));
+    }
+
+    /// Return the mean log-likelihood of a set of `(state, action)` pairs
+    /// under the policy.
+    pub fn log_likelihood<S>(&self, samples: &[(S, P::Action)]) -> f64
+    where
+        P: Policy<S>,
+    {
+        samples.iter().map(|(s, a)| self.policy.probability(s, a).ln()).sum::<f64>()
+            / samples.len() as f64
+    }
+}
+
+impl<S, P> BatchLearner<S, P::Action> for BehaviourCloning<P>
+where
+    P: DifferentiablePolicy<S>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        self.fit_with(batch.len(), |i| (batch[i].from.state(), &batch[i].action));
+    }
+}
+
+impl<S, P: Policy<S>> Controller<S, P::Action> for BehaviourCloning<P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    control::Controller,
+    domains::{Domain, Observation},
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised},
+    policies::{Policy, DifferentiablePolicy},
+    spaces::Space,
+};
+use rand::Rng;
+use super::BehaviourCloning;
+
+/// Dataset aggregation.
+///
+/// Iteratively trains a policy to imitate an `expert` on the distribution of
+/// states that the learner itself visits. At each iteration, `n_episodes` are
+/// rolled out under a mixture that follows the expert with probability `beta`
+/// and the learner otherwise; every visited state is labelled with the action
+/// of the expert and appended to the aggregate dataset, to which the learner
+/// is then refitted by behaviour cloning. After each iteration `beta` is
+/// multiplied by `beta_decay`, so the first iteration, with the default `beta`
+/// of one, collects pure expert demonstrations.
+///
+/// # References
+/// - Ross, S., Gordon, G. J., Bagnell, J. A. (2011). A reduction of imitation
+///   learning and structured prediction to no-regret online learning. In
+///   Proceedings of the 14th International Conference on Artificial
+///   Intelligence and Statistics, pp. 627–635.
+#[derive(Parameterised)]
+pub struct DAgger<P, E, S, A> {
+    #[weights] pub learner: BehaviourCloning<P>,
+    pub expert: E,
+
+    pub beta: f64,
+    pub beta_decay: f64,
+
+    pub n_episodes: usize,
+    pub step_limit: u64,
+
+    dataset: Vec<(S, A)>,
+}
+
+impl<P, E, S, A> DAgger<P, E, S, A> {
+    pub fn new(policy: P, expert: E, alpha: f64) -> Self {
+        DAgger {
+            learner: BehaviourCloning::new(policy, alpha),
+            expert,
+
+            beta: 1.0,
+            beta_decay: 0.5,
+
+            n_episodes: 1,
+            step_limit: 1000,
+
+            dataset: vec![],
+        }
+    }
+
+    /// Return the aggregate dataset of expert-labelled states.
+    pub fn dataset(&self) -> &[(S, A)] { &self.dataset }
+}
+
+impl<P, E, S, A> DAgger<P, E, S, A>
+where
+    S: Clone,
+    A: Clone,
+    P: DifferentiablePolicy<S, Action = A>,
+    E: Policy<S, Action = A>,
+{
+    /// Run a single iteration of data collection and refitting, and return the
+    /// mean return of the episodes collected.
+    pub fn step<D, F>(&mut self, rng: &mut impl Rng, domain_factory: &F) -> f64
+    where
+        D: Domain,
+        D::StateSpace: Space<Value = S>,
+        D::ActionSpace: Space<Value = A>,
+        F: Fn() -> D,
+    {
+        let n_episodes = self.n_episodes.max(1);
+        let beta = self.beta.clamp(0.0, 1.0);
+        let mut total = 0.0;
+
+        for _ in 0..n_episodes {
+            let mut domain = domain_factory();
+            let mut obs = domain.emit();
+
+            for _ in 0..self.step_limit {
+                let s = match obs {
+                    Observation::Terminal(_) => break,
+                    Observation::Full(ref s) | Observation::Partial(ref s) => s,
+                };
+
+                let label = self.expert.sample(rng, s);
+                let action = if rng.gen_bool(beta) {
+                    label.clone()
+                } else {
+                    self.learner.policy.sample(rng, s)
+                };
+
+                self.dataset.push((s.clone(), label));
+
+                let t = domain.step(action);
+
+                total += t.reward;
+                obs = t.to;
+            }
+        }
+
+        self.learner.fit(&self.dataset);
+        self.beta *= self.beta_decay;
+
+        total / n_episodes as f64
+    }
+}
+
+impl<S, P: Policy<S>, E, A> Controller<S, P::Action> for DAgger<P, E, S, A> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.learner.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.learner.policy.sample(rng, s)
+    }
+}
@@ -0,0 +1,186 @@
+//! Imitation learning agents.
+//!
+//! These agents fit a `DifferentiablePolicy` to the actions of an expert by
+//! maximum likelihood, either from a fixed set of demonstrations or, in the
+//! case of `DAgger`, by querying the expert on the states visited by the
+//! learner.
+import_all!(bc);
+import_all!(dagger);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BatchLearner,
+        domains::{Domain, Observation, Transition},
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+        },
+        policies::{EnumerablePolicy, Gibbs, Policy, gaussian::{self, Gaussian}},
+        spaces::{ProductSpace, real::Interval, discrete::Ordinal},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::cell::Cell;
+    use super::{BehaviourCloning, DAgger};
+
+    /// Deterministic expert that steers towards the origin.
+    struct Expert;
+
+    impl Policy<Vec<f64>> for Expert {
+        type Action = usize;
+
+        fn mpa(&self, s: &Vec<f64>) -> usize { if s[0] < 0.0 { 1 } else { 0 } }
+
+        fn probability(&self, s: &Vec<f64>, a: &usize) -> f64 {
+            if *a == self.mpa(s) { 1.0 } else { 0.0 }
+        }
+    }
+
+    /// Corridor of ten steps in which the agent is penalised for its distance
+    /// from the origin.
+    struct Corridor(f64, usize);
+
+    impl Domain for Corridor {
+        type StateSpace = ProductSpace<Interval>;
+        type ActionSpace = Ordinal;
+
+        fn emit(&self) -> Observation<Vec<f64>> {
+            if self.1 >= 10 {
+                Observation::Terminal(vec![self.0])
+            } else {
+                Observation::Full(vec![self.0])
+            }
+        }
+
+        fn step(&mut self, action: usize) -> Transition<Vec<f64>, usize> {
+            let from = self.emit();
+
+            self.0 += if action == 1 { 1.0 } else { -1.0 };
+            self.1 += 1;
+
+            Transition {
+                from,
+                action,
+                reward: -self.0.abs(),
+                to: self.emit(),
+            }
+        }
+
+        fn state_space(&self) -> Self::StateSpace {
+            ProductSpace::empty() + Interval::bounded(-15.0, 15.0)
+        }
+
+        fn action_space(&self) -> Ordinal { Ordinal::new(2) }
+    }
+
+    #[test]
+    fn test_behaviour_cloning_softmax() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+        let policy = Gibbs::standard(LFA::vector(basis, SGD(1.0), 2));
+
+        let mut agent = BehaviourCloning::new(policy, 0.5);
+
+        agent.n_epochs = 50;
+
+        let demonstrations: Vec<(Vec<f64>, usize)> = (0..200).map(|_| {
+            let s = vec![rng.gen_range(-3.0, 3.0)];
+            let a = Expert.mpa(&s);
+
+            (s, a)
+        }).collect();
+
+        let ll_before = agent.log_likelihood(&demonstrations);
+
+        agent.fit(&demonstrations);
+
+        assert!(agent.log_likelihood(&demonstrations) > ll_before);
+        assert!(agent.policy.probabilities(&vec![-2.0])[1] > 0.9);
+        assert!(agent.policy.probabilities(&vec![2.0])[0] > 0.9);
+    }
+
+    #[test]
+    fn test_behaviour_cloning_seeded() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let demonstrations: Vec<(Vec<f64>, usize)> = (0..20).map(|i| {
+            let s = vec![i as f64 / 5.0 - 2.0];
+            let a = Expert.mpa(&s);
+
+            (s, a)
+        }).collect();
+        let weights = |seed| {
+            let policy = Gibbs::standard(LFA::vector(basis.clone(), SGD(1.0), 2));
+            let mut agent = BehaviourCloning::new(policy, 0.5).with_seed(seed);
+
+            agent.mini_batch_size = 4;
+            agent.fit(&demonstrations);
+            agent.weights()
+        };
+
+        assert_eq!(weights(0), weights(0));
+    }
+
+    #[test]
+    fn test_behaviour_cloning_gaussian() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+        let policy = Gaussian::new(
+            gaussian::mean::Scalar(LFA::scalar(basis, SGD(1.0))),
+            gaussian::stddev::Constant(0.5),
+        );
+
+        let mut agent = BehaviourCloning::new(policy, 0.05);
+
+        agent.n_epochs = 100;
+
+        // Demonstrations recorded as transitions, with a linear expert:
+        let batch: Vec<Transition<Vec<f64>, f64>> = (0..100).map(|_| {
+            let x = rng.gen_range(0.0, 1.0);
+
+            Transition {
+                from: Observation::Full(vec![x]),
+                action: 2.0 * x - 1.0,
+                reward: 0.0,
+                to: Observation::Terminal(vec![x]),
+            }
+        }).collect();
+
+        agent.handle_batch(&batch);
+
+        assert!((agent.policy.compute_mean(&vec![0.0]) + 1.0).abs() < 0.1);
+        assert!((agent.policy.compute_mean(&vec![1.0]) - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_dagger() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+        let policy = Gibbs::standard(LFA::vector(basis, SGD(1.0), 2));
+
+        let mut agent = DAgger::new(policy, Expert, 0.5);
+
+        agent.n_episodes = 5;
+        agent.learner.n_epochs = 20;
+
+        let left = Cell::new(false);
+        let factory = || {
+            left.set(!left.get());
+
+            Corridor(if left.get() { -3.0 } else { 3.0 }, 0)
+        };
+        let mut ret = 0.0;
+
+        for _ in 0..10 {
+            ret = agent.step(&mut rng, &factory);
+        }
+
+        assert_eq!(agent.dataset().len(), 10 * 5 * 10);
+        assert!(ret > -15.0);
+
+        for &x in [-3.0, -1.0, 1.0, 3.0].iter() {
+            let s = vec![x];
+
+            assert!(agent.learner.policy.probabilities(&s)[Expert.mpa(&s)] > 0.8);
+        }
+    }
+}
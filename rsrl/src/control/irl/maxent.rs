@@ -0,0 +1,121 @@
+use crate::{
+    domains::{FiniteMDP, State, Transition},
+    fa::tabular::Tabular,
+    policies::{EnumerablePolicy, Softmax},
+};
+use ndarray::{Array1, Array2};
+use super::{Demonstrations, IRLSolution, backup, q_table};
+
+/// Maximum-entropy inverse reinforcement learning.
+///
+/// Models the demonstrator as acting according to the maximum-entropy policy
+/// for the reward `w^T phi(s')`, and fits the weights, `w`, by gradient ascent
+/// on the log-likelihood of the demonstrations with step size `alpha`. The
+/// gradient is the difference between the discounted feature expectations of
+/// the demonstrations and those of the soft-optimal policy, which is computed
+/// at each iteration by soft value iteration, `V(s) = log sum_a exp Q(s, a)`,
+/// performing at most `max_sweeps` sweeps or until the largest change falls
+/// below `tol`. Learning stops after `n_iterations`, or once the norm of the
+/// gradient falls below `tol`.
+///
+/// # References
+/// - Ziebart, B. D., Maas, A., Bagnell, J. A., Dey, A. K. (2008). Maximum
+///   entropy inverse reinforcement learning. In Proceedings of the 23rd AAAI
+///   Conference on Artificial Intelligence, pp. 1433–1438.
+/// - Ziebart, B. D., Bagnell, J. A., Dey, A. K. (2010). Modeling interaction
+///   via the principle of maximum causal entropy. In Proceedings of the 27th
+///   International Conference on Machine Learning, pp. 1255–1262.
+#[derive(Clone, Copy, Debug)]
+pub struct MaxEntIRL {
+    pub gamma: f64,
+    pub alpha: f64,
+    pub tol: f64,
+
+    pub n_iterations: usize,
+    pub max_sweeps: usize,
+}
+
+impl MaxEntIRL {
+    pub fn new(gamma: f64, alpha: f64, n_iterations: usize) -> Self {
+        MaxEntIRL {
+            gamma,
+            alpha,
+            tol: 1e-6,
+
+            n_iterations,
+            max_sweeps: 1000,
+        }
+    }
+
+    /// Return the soft action values for rewards `r`.
+    fn soft_q<M: FiniteMDP>(&self, mdp: &M, r: &[f64]) -> Tabular {
+        let mut v = vec![0.0; mdp.n_states()];
+
+        for _ in 0..self.max_sweeps {
+            let mut delta: f64 = 0.0;
+
+            for s in (0..mdp.n_states()).filter(|&s| !mdp.is_terminal(s)) {
+                let qs: Vec<f64> = (0..mdp.n_actions())
+                    .map(|a| backup(mdp, r, &v, self.gamma, s, a))
+                    .collect();
+                let q_max = qs.iter().fold(f64::NEG_INFINITY, |acc, &q| acc.max(q));
+                let new_v = q_max + qs.into_iter().map(|q| (q - q_max).exp()).sum::<f64>().ln();
+
+                delta = delta.max((new_v - v[s]).abs());
+                v[s] = new_v;
+            }
+
+            if delta < self.tol { break; }
+        }
+
+        q_table(mdp, r, &v, self.gamma)
+    }
+
+    /// Recover reward weights for the feature map `phi` from `demonstrations`.
+    pub fn solve<M, A, F>(
+        &self,
+        mdp: &M,
+        phi: F,
+        demonstrations: &[Vec<Transition<State<M>, A>>],
+    ) -> IRLSolution<Softmax<Tabular>>
+    where
+        M: FiniteMDP,
+        F: Fn(&State<M>) -> Array1<f64>,
+    {
+        let demos = Demonstrations::new(mdp, phi, demonstrations, self.gamma);
+
+        let mut w = Array1::zeros(demos.features.cols());
+        let mut n_iterations = 0;
+
+        let policy = loop {
+            let rewards = demos.rewards(&w);
+            let policy = Softmax::standard(self.soft_q(mdp, &rewards));
+
+            if n_iterations >= self.n_iterations { break policy; }
+
+            let mut probabilities = Array2::zeros((mdp.n_states(), mdp.n_actions()));
+
+            for s in 0..mdp.n_states() {
+                for (a, p) in policy.probabilities(&s).into_iter().enumerate() {
+                    probabilities[(s, a)] = p;
+                }
+            }
+
+            let grad = &demos.feature_expectations
+                - &demos.expected_features(mdp, &probabilities, self.gamma);
+
+            n_iterations += 1;
+
+            if grad.dot(&grad).sqrt() < self.tol { break policy; }
+
+            w.scaled_add(self.alpha, &grad);
+        };
+
+        IRLSolution {
+            rewards: demos.rewards(&w),
+            weights: w,
+            policy,
+            n_iterations,
+        }
+    }
+}
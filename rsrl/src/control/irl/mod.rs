@@ -0,0 +1,208 @@
+//! Inverse reinforcement learning on finite MDPs with known dynamics.
+//!
+//! These methods recover a reward function, linear in a user-specified map of
+//! state features, from demonstration trajectories. The reward for a
+//! transition is that of the state it enters, `r(s') = w^T phi(s')`, so that
+//! the features of terminal goal states are accounted for. The reward defined
+//! by `FiniteMDP::expected_reward` is ignored, and the distribution of initial
+//! states is estimated from the first state of each demonstration.
+use crate::{
+    domains::{FiniteMDP, State, Transition},
+    fa::tabular::Tabular,
+};
+use ndarray::{Array1, Array2};
+
+import_all!(maxent);
+import_all!(projection);
+
+/// Reward weights and policy recovered by inverse reinforcement learning.
+#[derive(Clone, Debug)]
+pub struct IRLSolution<P> {
+    /// Weights of the linear reward function.
+    pub weights: Array1<f64>,
+
+    /// Rewards for entering each state, indexed by state.
+    pub rewards: Vec<f64>,
+
+    /// Policy that is (soft-)optimal with respect to the learnt rewards.
+    pub policy: P,
+
+    /// Number of iterations performed before termination.
+    pub n_iterations: usize,
+}
+
+/// Summary statistics of a set of demonstrations.
+struct Demonstrations {
+    features: Array2<f64>,
+    feature_expectations: Array1<f64>,
+    initial_distribution: Vec<f64>,
+    horizon: usize,
+}
+
+impl Demonstrations {
+    /// Return the feature matrix, with one row per state, and the discounted
+    /// feature expectations and initial state distribution of
+    /// `demonstrations`.
+    fn new<M, A, F>(
+        mdp: &M,
+        phi: F,
+        demonstrations: &[Vec<Transition<State<M>, A>>],
+        gamma: f64,
+    ) -> Self
+    where
+        M: FiniteMDP,
+        F: Fn(&State<M>) -> Array1<f64>,
+    {
+        let n_states = mdp.n_states();
+        let rows: Vec<Array1<f64>> = (0..n_states).map(|s| phi(&mdp.index_state(s))).collect();
+        let n_features = rows.first().map_or(0, |r| r.len());
+
+        let mut features = Array2::zeros((n_states, n_features));
+
+        for (s, r) in rows.into_iter().enumerate() {
+            features.row_mut(s).assign(&r);
+        }
+
+        let mut feature_expectations = Array1::zeros(n_features);
+        let mut initial_distribution = vec![0.0; n_states];
+        let mut horizon = 0;
+
+        let demonstrations: Vec<_> = demonstrations.iter().filter(|d| !d.is_empty()).collect();
+        let n = demonstrations.len().max(1) as f64;
+
+        for d in demonstrations {
+            initial_distribution[mdp.state_index(d[0].from.state())] += 1.0 / n;
+            horizon = horizon.max(d.len());
+
+            for (i, t) in d.iter().enumerate() {
+                let ns = mdp.state_index(t.to.state());
+
+                feature_expectations.scaled_add(gamma.powi(i as i32) / n, &features.row(ns));
+            }
+        }
+
+        Demonstrations { features, feature_expectations, initial_distribution, horizon, }
+    }
+
+    /// Return the rewards for entering each state under weights `w`.
+    fn rewards(&self, w: &Array1<f64>) -> Vec<f64> { self.features.dot(w).to_vec() }
+
+    /// Return the discounted feature expectations of the stochastic `policy`,
+    /// given as a matrix of action probabilities, over the demonstration
+    /// horizon.
+    fn expected_features<M: FiniteMDP>(
+        &self,
+        mdp: &M,
+        policy: &Array2<f64>,
+        gamma: f64,
+    ) -> Array1<f64> {
+        let mut mu = Array1::zeros(self.features.cols());
+        let mut d = self.initial_distribution.clone();
+        let mut discount = 1.0;
+
+        for _ in 0..self.horizon {
+            let mut nd = vec![0.0; d.len()];
+
+            for s in (0..mdp.n_states()).filter(|&s| d[s] > 0.0 && !mdp.is_terminal(s)) {
+                for a in 0..mdp.n_actions() {
+                    let p_sa = d[s] * policy[(s, a)];
+
+                    if p_sa <= 0.0 { continue; }
+
+                    for (ns, p) in mdp.transition_probabilities(s, a) {
+                        mu.scaled_add(discount * p_sa * p, &self.features.row(ns));
+                        nd[ns] += p_sa * p;
+                    }
+                }
+            }
+
+            d = nd;
+            discount *= gamma;
+        }
+
+        mu
+    }
+}
+
+/// Expected return of taking action `a` in state `s`, with rewards `r` for
+/// entering each state, and following on with values `v`.
+fn backup<M: FiniteMDP>(mdp: &M, r: &[f64], v: &[f64], gamma: f64, s: usize, a: usize) -> f64 {
+    mdp.transition_probabilities(s, a).into_iter()
+        .fold(0.0, |acc, (ns, p)| acc + p * (r[ns] + gamma * v[ns]))
+}
+
+/// Return the action values, as a `Tabular` function, for values `v`.
+fn q_table<M: FiniteMDP>(mdp: &M, r: &[f64], v: &[f64], gamma: f64) -> Tabular {
+    Tabular::new((0..mdp.n_actions()).map(|a| {
+        (0..mdp.n_states()).map(|s| if mdp.is_terminal(s) {
+            0.0
+        } else {
+            backup(mdp, r, v, gamma, s, a)
+        }).collect()
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        control::dp::ValueIteration,
+        domains::{CliffWalk, Domain, FiniteMDP, Transition},
+        policies::Policy,
+    };
+    use ndarray::Array1;
+    use super::{MaxEntIRL, ProjectionIRL};
+
+    /// Return the one-hot features of a state of a 4 x 6 cliff walk.
+    fn one_hot(s: &[usize; 2]) -> Array1<f64> {
+        let mut phi = Array1::zeros(24);
+
+        phi[s[1] * 6 + s[0]] = 1.0;
+
+        phi
+    }
+
+    /// Return a demonstration by the optimal policy on a 4 x 6 cliff walk.
+    fn demonstrations() -> Vec<Vec<Transition<[usize; 2], usize>>> {
+        let cw = CliffWalk::new(4, 6);
+        let expert = ValueIteration::new(0.9, 1e-10, 1000).solve(&cw).policy();
+
+        vec![CliffWalk::new(4, 6).rollout(|s| expert.mpa(&cw.state_index(s)))]
+    }
+
+    /// Return the final reward obtained by following `actor` from the start.
+    fn final_reward(actor: impl Fn(usize) -> usize) -> f64 {
+        let mut domain = CliffWalk::new(4, 6);
+
+        for _ in 0..50 {
+            let t = domain.step(actor(domain.state_index(domain.emit().state())));
+
+            if t.terminated() { return t.reward; }
+        }
+
+        0.0
+    }
+
+    #[test]
+    fn test_maxent_irl() {
+        let cw = CliffWalk::new(4, 6);
+        let demos = demonstrations();
+
+        let solution = MaxEntIRL::new(0.9, 0.5, 200).solve(&cw, one_hot, &demos);
+
+        let goal = cw.state_index(&[5, 0]);
+        let cliff = cw.state_index(&[2, 0]);
+
+        assert!(solution.rewards[goal] > solution.rewards[cliff]);
+        assert_eq!(final_reward(|s| solution.policy.mpa(&s)), 50.0);
+    }
+
+    #[test]
+    fn test_projection_irl() {
+        let cw = CliffWalk::new(4, 6);
+        let demos = demonstrations();
+
+        let solution = ProjectionIRL::new(0.9, 1e-3, 50).solve(&cw, one_hot, &demos);
+
+        assert_eq!(final_reward(|s| solution.policy.mpa(&s)), 50.0);
+    }
+}
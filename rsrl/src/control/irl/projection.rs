@@ -0,0 +1,140 @@
+use crate::{
+    domains::{FiniteMDP, State, Transition},
+    fa::tabular::Tabular,
+    policies::{Greedy, Policy},
+};
+use ndarray::{Array1, Array2};
+use super::{Demonstrations, IRLSolution, backup, q_table};
+
+/// Apprenticeship learning via the projection method.
+///
+/// Alternates between computing an optimal policy, by value iteration, for the
+/// current reward weights, and setting the weights to the difference between
+/// the discounted feature expectations of the demonstrations and the
+/// projection of the demonstrations onto the convex hull of the feature
+/// expectations of the policies found so far. Learning stops once this margin
+/// falls below `epsilon`, or after `n_iterations`. The returned policy is the
+/// one whose feature expectations lie closest to those of the demonstrations.
+///
+/// # References
+/// - Abbeel, P., Ng, A. Y. (2004). Apprenticeship learning via inverse
+///   reinforcement learning. In Proceedings of the 21st International
+///   Conference on Machine Learning, pp. 1–8.
+#[derive(Clone, Copy, Debug)]
+pub struct ProjectionIRL {
+    pub gamma: f64,
+    pub epsilon: f64,
+    pub tol: f64,
+
+    pub n_iterations: usize,
+    pub max_sweeps: usize,
+}
+
+impl ProjectionIRL {
+    pub fn new(gamma: f64, epsilon: f64, n_iterations: usize) -> Self {
+        ProjectionIRL {
+            gamma,
+            epsilon,
+            tol: 1e-8,
+
+            n_iterations,
+            max_sweeps: 1000,
+        }
+    }
+
+    /// Return the optimal action values for rewards `r`.
+    fn optimal_q<M: FiniteMDP>(&self, mdp: &M, r: &[f64]) -> Tabular {
+        let mut v = vec![0.0; mdp.n_states()];
+
+        for _ in 0..self.max_sweeps {
+            let mut delta: f64 = 0.0;
+
+            for s in (0..mdp.n_states()).filter(|&s| !mdp.is_terminal(s)) {
+                let new_v = (0..mdp.n_actions())
+                    .map(|a| backup(mdp, r, &v, self.gamma, s, a))
+                    .fold(f64::NEG_INFINITY, f64::max);
+
+                delta = delta.max((new_v - v[s]).abs());
+                v[s] = new_v;
+            }
+
+            if delta < self.tol { break; }
+        }
+
+        q_table(mdp, r, &v, self.gamma)
+    }
+
+    /// Return the discounted feature expectations of a greedy `policy`.
+    fn expected_features<M: FiniteMDP>(
+        &self,
+        mdp: &M,
+        demos: &Demonstrations,
+        policy: &Greedy<Tabular>,
+    ) -> Array1<f64> {
+        let mut probabilities = Array2::zeros((mdp.n_states(), mdp.n_actions()));
+
+        for s in 0..mdp.n_states() {
+            probabilities[(s, policy.mpa(&s))] = 1.0;
+        }
+
+        demos.expected_features(mdp, &probabilities, self.gamma)
+    }
+
+    /// Recover reward weights for the feature map `phi` from `demonstrations`.
+    pub fn solve<M, A, F>(
+        &self,
+        mdp: &M,
+        phi: F,
+        demonstrations: &[Vec<Transition<State<M>, A>>],
+    ) -> IRLSolution<Greedy<Tabular>>
+    where
+        M: FiniteMDP,
+        F: Fn(&State<M>) -> Array1<f64>,
+    {
+        let demos = Demonstrations::new(mdp, phi, demonstrations, self.gamma);
+        let mu_expert = &demos.feature_expectations;
+
+        // Start from the policy that is optimal for zero reward:
+        let mut w = Array1::zeros(demos.features.cols());
+        let mut best = Greedy::new(self.optimal_q(mdp, &demos.rewards(&w)));
+        let mut mu_bar = self.expected_features(mdp, &demos, &best);
+        let mut best_distance = (mu_expert - &mu_bar).dot(&(mu_expert - &mu_bar)).sqrt();
+        let mut n_iterations = 0;
+
+        while n_iterations < self.n_iterations {
+            w = mu_expert - &mu_bar;
+
+            let margin = w.dot(&w).sqrt();
+
+            n_iterations += 1;
+
+            if margin < self.epsilon { break; }
+
+            let policy = Greedy::new(self.optimal_q(mdp, &demos.rewards(&w)));
+            let mu = self.expected_features(mdp, &demos, &policy);
+
+            let distance = (mu_expert - &mu).dot(&(mu_expert - &mu)).sqrt();
+
+            if distance < best_distance {
+                best_distance = distance;
+                best = policy;
+            }
+
+            // Orthogonal projection of the expert onto the line through mu_bar
+            // and mu:
+            let step = &mu - &mu_bar;
+            let norm_sq = step.dot(&step);
+
+            if norm_sq <= 0.0 { break; }
+
+            mu_bar.scaled_add(step.dot(&w) / norm_sq, &step);
+        }
+
+        IRLSolution {
+            rewards: demos.rewards(&w),
+            weights: w,
+            policy: best,
+            n_iterations,
+        }
+    }
+}
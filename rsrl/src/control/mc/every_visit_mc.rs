@@ -0,0 +1,91 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateActionFunction},
+    policies::Policy,
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::{Rng, thread_rng};
+
+/// On-policy every-visit Monte Carlo control.
+///
+/// Action values are moved towards the return observed after every occurrence
+/// of each state-action pair in an episode. The policy is expected to be
+/// ε-soft with respect to `q_func` (e.g. `EpsilonGreedy` over a shared
+/// instance).
+///
+/// # References
+/// - Singh, S. P., Sutton, R. S. (1996). Reinforcement learning with replacing
+///   eligibility traces. Machine Learning 22:123–158.
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 5.4.
+#[derive(Parameterised)]
+pub struct EveryVisitMC<Q, P> {
+    #[weights] pub q_func: Q,
+    pub policy: P,
+
+    pub alpha: f64,
+    pub gamma: f64,
+}
+
+impl<Q, P> EveryVisitMC<Q, P> {
+    pub fn new(q_func: Q, policy: P, alpha: f64, gamma: f64) -> Self {
+        EveryVisitMC {
+            q_func,
+            policy,
+
+            alpha,
+            gamma,
+        }
+    }
+}
+
+impl<S, Q, P> BatchLearner<S, P::Action> for EveryVisitMC<Q, P>
+where
+    Q: StateActionFunction<S, P::Action, Output = f64>,
+    P: Policy<S>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        let mut ret = 0.0;
+
+        for t in batch.iter().rev() {
+            ret = t.reward + self.gamma * ret;
+
+            let s = t.from.state();
+            let qsa = self.q_func.evaluate(s, &t.action);
+
+            self.q_func.update(s, &t.action, self.alpha * (ret - qsa));
+        }
+    }
+}
+
+impl<S, Q, P: Policy<S>> Controller<S, P::Action> for EveryVisitMC<Q, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<S, Q, P> ValuePredictor<S> for EveryVisitMC<Q, P>
+where
+    Q: StateActionFunction<S, P::Action, Output = f64>,
+    P: Policy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.q_func.evaluate(s, &self.sample_behaviour(&mut thread_rng(), s))
+    }
+}
+
+impl<S, Q, P> ActionValuePredictor<S, P::Action> for EveryVisitMC<Q, P>
+where
+    Q: StateActionFunction<S, P::Action, Output = f64>,
+    P: Policy<S>,
+{
+    fn predict_q(&self, s: &S, a: &P::Action) -> f64 {
+        self.q_func.evaluate(s, a)
+    }
+}
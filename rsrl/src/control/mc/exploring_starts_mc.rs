@@ -0,0 +1,77 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        EnumerableStateActionFunction,
+    },
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::Rng;
+use super::first_visit_mc::first_visit_returns;
+
+/// Monte Carlo control with exploring starts.
+///
+/// First-visit updates are combined with a policy that is greedy with respect
+/// to `q_func`. Exploration is achieved entirely through the choice of initial
+/// state-action pair, so episodes must begin from a state and action sampled
+/// such that every pair has non-zero probability of being selected.
+///
+/// # References
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 5.3.
+#[derive(Parameterised)]
+pub struct ExploringStartsMC<Q> {
+    #[weights] pub q_func: Q,
+
+    pub alpha: f64,
+    pub gamma: f64,
+}
+
+impl<Q> ExploringStartsMC<Q> {
+    pub fn new(q_func: Q, alpha: f64, gamma: f64) -> Self {
+        ExploringStartsMC {
+            q_func,
+
+            alpha,
+            gamma,
+        }
+    }
+}
+
+impl<S, Q> BatchLearner<S, usize> for ExploringStartsMC<Q>
+where
+    S: PartialEq,
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, usize>]) {
+        let returns = first_visit_returns(batch, self.gamma);
+
+        for (t, ret) in batch.iter().zip(returns) {
+            if let Some(ret) = ret {
+                let s = t.from.state();
+                let qsa = self.q_func.evaluate(s, &t.action);
+
+                self.q_func.update(s, &t.action, self.alpha * (ret - qsa));
+            }
+        }
+    }
+}
+
+impl<S, Q: EnumerableStateActionFunction<S>> Controller<S, usize> for ExploringStartsMC<Q> {
+    fn sample_target(&self, _: &mut impl Rng, s: &S) -> usize { self.q_func.find_max(s).0 }
+
+    fn sample_behaviour(&self, _: &mut impl Rng, s: &S) -> usize { self.q_func.find_max(s).0 }
+}
+
+impl<S, Q: EnumerableStateActionFunction<S>> ValuePredictor<S> for ExploringStartsMC<Q> {
+    fn predict_v(&self, s: &S) -> f64 { self.q_func.find_max(s).1 }
+}
+
+impl<S, Q> ActionValuePredictor<S, usize> for ExploringStartsMC<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 { self.q_func.evaluate(s, a) }
+}
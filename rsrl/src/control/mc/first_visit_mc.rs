@@ -0,0 +1,119 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateActionFunction},
+    policies::Policy,
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::{Rng, thread_rng};
+
+/// Returns, for each transition in an episode, the discounted return from that
+/// point onwards if it is the first occurrence of its state-action pair.
+pub(super) fn first_visit_returns<S: PartialEq, A: PartialEq>(
+    batch: &[Transition<S, A>],
+    gamma: f64,
+) -> Vec<Option<f64>> {
+    let mut ret = 0.0;
+    let mut returns: Vec<Option<f64>> = batch.iter().rev().map(|t| {
+        ret = t.reward + gamma * ret;
+
+        Some(ret)
+    }).collect();
+
+    returns.reverse();
+
+    for (i, t) in batch.iter().enumerate() {
+        let s = t.from.state();
+
+        if batch[..i].iter().any(|p| p.action == t.action && p.from.state() == s) {
+            returns[i] = None;
+        }
+    }
+
+    returns
+}
+
+/// On-policy first-visit Monte Carlo control.
+///
+/// Action values are moved towards the return observed after the first
+/// occurrence of each state-action pair in an episode. The policy is expected
+/// to be ε-soft with respect to `q_func` (e.g. `EpsilonGreedy` over a shared
+/// instance).
+///
+/// # References
+/// - Singh, S. P., Sutton, R. S. (1996). Reinforcement learning with replacing
+///   eligibility traces. Machine Learning 22:123–158.
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 5.4.
+#[derive(Parameterised)]
+pub struct FirstVisitMC<Q, P> {
+    #[weights] pub q_func: Q,
+    pub policy: P,
+
+    pub alpha: f64,
+    pub gamma: f64,
+}
+
+impl<Q, P> FirstVisitMC<Q, P> {
+    pub fn new(q_func: Q, policy: P, alpha: f64, gamma: f64) -> Self {
+        FirstVisitMC {
+            q_func,
+            policy,
+
+            alpha,
+            gamma,
+        }
+    }
+}
+
+impl<S, Q, P> BatchLearner<S, P::Action> for FirstVisitMC<Q, P>
+where
+    S: PartialEq,
+    Q: StateActionFunction<S, P::Action, Output = f64>,
+    P: Policy<S>,
+    P::Action: PartialEq,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, P::Action>]) {
+        let returns = first_visit_returns(batch, self.gamma);
+
+        for (t, ret) in batch.iter().zip(returns) {
+            if let Some(ret) = ret {
+                let s = t.from.state();
+                let qsa = self.q_func.evaluate(s, &t.action);
+
+                self.q_func.update(s, &t.action, self.alpha * (ret - qsa));
+            }
+        }
+    }
+}
+
+impl<S, Q, P: Policy<S>> Controller<S, P::Action> for FirstVisitMC<Q, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<S, Q, P> ValuePredictor<S> for FirstVisitMC<Q, P>
+where
+    Q: StateActionFunction<S, P::Action, Output = f64>,
+    P: Policy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.q_func.evaluate(s, &self.sample_behaviour(&mut thread_rng(), s))
+    }
+}
+
+impl<S, Q, P> ActionValuePredictor<S, P::Action> for FirstVisitMC<Q, P>
+where
+    Q: StateActionFunction<S, P::Action, Output = f64>,
+    P: Policy<S>,
+{
+    fn predict_q(&self, s: &S, a: &P::Action) -> f64 {
+        self.q_func.evaluate(s, a)
+    }
+}
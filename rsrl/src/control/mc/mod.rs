@@ -1,2 +1,112 @@
 import_all!(reinforce);
 import_all!(baseline_reinforce);
+
+import_all!(every_visit_mc);
+import_all!(first_visit_mc);
+import_all!(exploring_starts_mc);
+import_all!(off_policy_mc);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BatchLearner,
+        control::Controller,
+        domains::{Observation, Transition},
+        fa::{StateActionFunction, tabular::Tabular},
+        policies::{Greedy, Random},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use super::{
+        EveryVisitMC, ExploringStartsMC, FirstVisitMC, OffPolicyMC,
+        first_visit_mc::first_visit_returns,
+    };
+
+    /// Return a transition from `s`, terminating unless `ns` is given.
+    fn transition(s: usize, a: usize, reward: f64, ns: Option<usize>) -> Transition<usize, usize> {
+        Transition {
+            from: Observation::Full(s),
+            action: a,
+            reward,
+            to: ns.map_or(Observation::Terminal(s), Observation::Full),
+        }
+    }
+
+    /// Episode that takes action 0 in state 0 twice, earning one each time.
+    fn revisiting_episode() -> Vec<Transition<usize, usize>> {
+        vec![transition(0, 0, 1.0, Some(0)), transition(0, 0, 1.0, None)]
+    }
+
+    #[test]
+    fn test_first_visit_returns() {
+        let returns = first_visit_returns(&revisiting_episode(), 1.0);
+
+        assert_eq!(returns, vec![Some(2.0), None]);
+    }
+
+    #[test]
+    fn test_first_vs_every_visit() {
+        let mut first = FirstVisitMC::new(Tabular::zeros([1, 2]), Random::new(2), 0.5, 1.0);
+        let mut every = EveryVisitMC::new(Tabular::zeros([1, 2]), Random::new(2), 0.5, 1.0);
+
+        first.handle_batch(&revisiting_episode());
+        every.handle_batch(&revisiting_episode());
+
+        // Only the return of 2 from the first visit is counted:
+        assert_eq!(first.q_func.evaluate(&0, &0), 1.0);
+
+        // The return of 1 from the second visit is counted first, then that of 2:
+        assert_eq!(every.q_func.evaluate(&0, &0), 1.25);
+    }
+
+    #[test]
+    fn test_exploring_starts() {
+        // Action 1 moves from state 0 to state 1, where action 1 earns one;
+        // every other action terminates with nothing.
+        let step = |s: usize, a: usize| match (s, a) {
+            (0, 1) => transition(0, 1, 0.0, Some(1)),
+            (1, 1) => transition(1, 1, 1.0, None),
+            (s, a) => transition(s, a, 0.0, None),
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = ExploringStartsMC::new(Tabular::zeros([2, 2]), 0.5, 1.0);
+
+        for _ in 0..100 {
+            let mut episode = vec![step(rng.gen_range(0, 2), rng.gen_range(0, 2))];
+
+            while let Observation::Full(s) = episode.last().unwrap().to {
+                episode.push(step(s, agent.sample_behaviour(&mut rng, &s)));
+            }
+
+            agent.handle_batch(&episode);
+        }
+
+        assert_eq!(agent.sample_target(&mut rng, &0), 1);
+        assert_eq!(agent.sample_target(&mut rng, &1), 1);
+        assert!((agent.q_func.evaluate(&0, &1) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_off_policy_cutoff() {
+        // Target policy that always takes action 1:
+        let target = Greedy::new(Tabular::new(vec![vec![0.0, 0.0], vec![1.0, 1.0]]));
+        let mut agent = OffPolicyMC::new(
+            Tabular::zeros([2, 2]), Tabular::zeros([2, 2]), target, Random::new(2), 1.0,
+        );
+
+        // The final action is not that of the target policy, so the weight drops
+        // to zero and the first transition is ignored:
+        agent.handle_batch(&[transition(0, 1, 0.0, Some(1)), transition(1, 0, 1.0, None)]);
+
+        assert_eq!(agent.q_func.evaluate(&1, &0), 1.0);
+        assert_eq!(agent.c_func.evaluate(&1, &0), 1.0);
+        assert_eq!(agent.q_func.evaluate(&0, &1), 0.0);
+        assert_eq!(agent.c_func.evaluate(&0, &1), 0.0);
+
+        // Here it is, so the first transition is weighted by 1 / 0.5:
+        agent.handle_batch(&[transition(0, 1, 0.0, Some(1)), transition(1, 1, 1.0, None)]);
+
+        assert_eq!(agent.q_func.evaluate(&0, &1), 1.0);
+        assert_eq!(agent.c_func.evaluate(&0, &1), 2.0);
+    }
+}
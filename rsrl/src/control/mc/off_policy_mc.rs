@@ -0,0 +1,115 @@
+use crate::{
+    BatchLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateActionFunction},
+    policies::Policy,
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::{Rng, thread_rng};
+
+/// Off-policy Monte Carlo control with weighted importance sampling.
+///
+/// Episodes generated by `behaviour_policy` are processed backwards, with each
+/// return weighted by the product of importance sampling ratios between the
+/// target and behaviour policies over the remainder of the episode. The
+/// cumulative weight of each state-action pair is tracked by `c_func` (e.g.
+/// a zero-initialised `Tabular`), giving each update a step size of `W / C`.
+/// The target policy is typically greedy with respect to a shared `q_func`.
+///
+/// # References
+/// - Precup, D., Sutton, R. S., Singh, S. (2000). Eligibility traces for
+///   off-policy policy evaluation. In Proceedings of the 17th International
+///   Conference on Machine Learning, pp. 759–766.
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 5.7.
+#[derive(Parameterised)]
+pub struct OffPolicyMC<Q, C, T, B> {
+    #[weights] pub q_func: Q,
+    pub c_func: C,
+
+    pub target_policy: T,
+    pub behaviour_policy: B,
+
+    pub gamma: f64,
+}
+
+impl<Q, C, T, B> OffPolicyMC<Q, C, T, B> {
+    pub fn new(q_func: Q, c_func: C, target_policy: T, behaviour_policy: B, gamma: f64) -> Self {
+        OffPolicyMC {
+            q_func,
+            c_func,
+
+            target_policy,
+            behaviour_policy,
+
+            gamma,
+        }
+    }
+}
+
+impl<S, Q, C, T, B> BatchLearner<S, T::Action> for OffPolicyMC<Q, C, T, B>
+where
+    Q: StateActionFunction<S, T::Action, Output = f64>,
+    C: StateActionFunction<S, T::Action, Output = f64>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn handle_batch(&mut self, batch: &[Transition<S, T::Action>]) {
+        let mut ret = 0.0;
+        let mut weight = 1.0;
+
+        for t in batch.iter().rev() {
+            ret = t.reward + self.gamma * ret;
+
+            let s = t.from.state();
+
+            self.c_func.update(s, &t.action, weight);
+
+            let csa = self.c_func.evaluate(s, &t.action);
+            let qsa = self.q_func.evaluate(s, &t.action);
+
+            self.q_func.update(s, &t.action, weight / csa * (ret - qsa));
+
+            weight *= self.target_policy.probability(s, &t.action)
+                / self.behaviour_policy.probability(s, &t.action);
+
+            if weight.abs() < 1e-10 { break; }
+        }
+    }
+}
+
+impl<S, Q, C, T, B> Controller<S, T::Action> for OffPolicyMC<Q, C, T, B>
+where
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> T::Action {
+        self.target_policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> T::Action {
+        self.behaviour_policy.sample(rng, s)
+    }
+}
+
+impl<S, Q, C, T, B> ValuePredictor<S> for OffPolicyMC<Q, C, T, B>
+where
+    Q: StateActionFunction<S, T::Action, Output = f64>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.q_func.evaluate(s, &self.sample_target(&mut thread_rng(), s))
+    }
+}
+
+impl<S, Q, C, T, B> ActionValuePredictor<S, T::Action> for OffPolicyMC<Q, C, T, B>
+where
+    Q: StateActionFunction<S, T::Action, Output = f64>,
+    T: Policy<S>,
+{
+    fn predict_q(&self, s: &S, a: &T::Action) -> f64 {
+        self.q_func.evaluate(s, a)
+    }
+}
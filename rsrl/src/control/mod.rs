@@ -19,16 +19,17 @@ impl<S, A, T: Controller<S, A>> Controller<S, A> for Shared<T> {
 }
 
 pub mod ac;
+pub mod distributional;
+pub mod dp;
 pub mod gtd;
+pub mod imitation;
+pub mod irl;
 pub mod mc;
+pub mod planning;
+pub mod ps;
 pub mod td;
 pub mod totd;
 
-// TODO
-// Proximal gradient-descent methods:
-// https://arxiv.org/pdf/1210.4893.pdf
-// https://arxiv.org/pdf/1405.6757.pdf
-
 // TODO
 // Hamid Maei Thesis (reference)
 // https://era.library.ualberta.ca/files/8s45q967t/Hamid_Maei_PhDThesis.pdf
@@ -0,0 +1,147 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::{Observation, Transition},
+    fa::{
+        Parameterised, Weights, WeightsView, WeightsViewMut,
+        EnumerableStateActionFunction,
+    },
+    models::TabularModel,
+    policies::Policy,
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Dyna-Q and Dyna-Q+.
+///
+/// Each real transition is used to perform a Q-learning update and to train
+/// the model, after which `n_planning` simulated transitions are drawn from
+/// the model and backed up in the same way. A non-zero `kappa` yields Dyna-Q+,
+/// in which simulated rewards are augmented by an exploration bonus of
+/// `kappa * sqrt(tau)`, where `tau` is the number of real steps since the
+/// state-action pair was last visited. The simulated transitions are drawn
+/// using an internal generator, which may be seeded with `with_seed`.
+///
+/// # References
+/// - Sutton, R. S. (1990). Integrated architectures for learning, planning,
+///   and reacting based on approximating dynamic programming. In Proceedings
+///   of the 7th International Conference on Machine Learning, pp. 216–224.
+/// - Sutton, R. S., Barto, A. G. (2018). Reinforcement Learning: An
+///   Introduction (2nd ed.). MIT Press, Section 8.3.
+#[derive(Parameterised)]
+pub struct DynaQ<Q, P, M> {
+    #[weights] pub q_func: Q,
+
+    pub policy: P,
+    pub model: M,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub kappa: f64,
+
+    pub n_planning: usize,
+
+    rng: StdRng,
+}
+
+impl<Q, P, M> DynaQ<Q, P, M> {
+    pub fn new(
+        q_func: Q,
+        policy: P,
+        model: M,
+        alpha: f64,
+        gamma: f64,
+        kappa: f64,
+        n_planning: usize,
+    ) -> Self {
+        DynaQ {
+            q_func,
+
+            policy,
+            model,
+
+            alpha,
+            gamma,
+            kappa,
+
+            n_planning,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the generator used to draw simulated transitions.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl<Q: EnumerableStateActionFunction<usize>, P, M> DynaQ<Q, P, M> {
+    fn backup(&mut self, s: usize, a: usize, reward: f64, ns: &Observation<usize>) {
+        let qsa = self.q_func.evaluate(&s, &a);
+        let residual = if ns.is_terminal() {
+            reward - qsa
+        } else {
+            let (_, nqsna) = self.q_func.find_max(ns.state());
+
+            reward + self.gamma * nqsna - qsa
+        };
+
+        self.q_func.update(&s, &a, self.alpha * residual);
+    }
+}
+
+impl<Q, P, M> OnlineLearner<usize, usize> for DynaQ<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+    M: TabularModel,
+{
+    fn handle_transition(&mut self, t: &Transition<usize, usize>) {
+        self.backup(*t.from.state(), t.action, t.reward, &t.to);
+        self.model.handle_transition(t);
+
+        for _ in 0..self.n_planning {
+            let (s, a) = match self.model.sample_pair(&mut self.rng) {
+                Some(pair) => pair,
+                None => break,
+            };
+            let (reward, ns) = self.model.sample(&mut self.rng, s, a).unwrap();
+            let bonus = if self.kappa > 0.0 {
+                self.model.n_steps_since(s, a).map_or(0.0, |tau| self.kappa * (tau as f64).sqrt())
+            } else {
+                0.0
+            };
+
+            self.backup(s, a, reward + bonus, &ns);
+        }
+    }
+
+    fn handle_terminal(&mut self) { self.model.handle_terminal(); }
+}
+
+impl<Q, P, M> Controller<usize, usize> for DynaQ<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+    P: Policy<usize, Action = usize>,
+{
+    fn sample_target(&self, _: &mut impl Rng, s: &usize) -> usize { self.q_func.find_max(s).0 }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &usize) -> usize {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<Q, P, M> ValuePredictor<usize> for DynaQ<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+{
+    fn predict_v(&self, s: &usize) -> f64 { self.q_func.find_max(s).1 }
+}
+
+impl<Q, P, M> ActionValuePredictor<usize, usize> for DynaQ<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+{
+    fn predict_q(&self, s: &usize, a: &usize) -> f64 { self.q_func.evaluate(s, a) }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Parameterised, Weights, WeightsView, WeightsViewMut,
+        linear::{Features, LinearStateFunction},
+    },
+    models::LinearModel,
+    policies::Policy,
+    prediction::{ValuePredictor, ActionValuePredictor},
+    utils::argmax_choose_rng,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Linear Dyna with a learned per-action feature-space model.
+///
+/// Each real transition is used to perform a TD(0) update of the linear value
+/// function and to train the model. Planning then applies `n_planning`
+/// model-based backups, `V(e_i) <- max_a [b_a^T e_i + gamma V(F_a^T e_i)]`, to
+/// unit basis vectors `e_i` drawn uniformly at random, using an internal
+/// generator which may be seeded with `with_seed`. The target policy is greedy
+/// with respect to the same one-step model lookahead.
+///
+/// # References
+/// - Sutton, R. S., Szepesvári, Cs., Geramifard, A., Bowling, M. (2008).
+///   Dyna-style planning with linear function approximation and prioritized
+///   sweeping. In Proceedings of the 24th Conference on Uncertainty in
+///   Artificial Intelligence, pp. 528–536.
+#[derive(Parameterised)]
+pub struct LinearDyna<V, P> {
+    #[weights] pub v_func: V,
+
+    pub policy: P,
+    pub model: LinearModel,
+
+    pub alpha: f64,
+    pub gamma: f64,
+
+    pub n_planning: usize,
+
+    rng: StdRng,
+}
+
+impl<V, P> LinearDyna<V, P> {
+    pub fn new(
+        v_func: V,
+        policy: P,
+        model: LinearModel,
+        alpha: f64,
+        gamma: f64,
+        n_planning: usize,
+    ) -> Self {
+        LinearDyna {
+            v_func,
+
+            policy,
+            model,
+
+            alpha,
+            gamma,
+
+            n_planning,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the generator used to draw the basis vectors for planning.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn lookahead_action<S>(&self, phi: &Features, a: usize) -> f64
+    where
+        V: LinearStateFunction<S>,
+    {
+        let nv = self.v_func.evaluate_features(&self.model.predict_features(phi, a));
+
+        self.model.predict_reward(phi, a) + self.gamma * nv
+    }
+
+    fn lookahead<S>(&self, phi: &Features) -> Vec<f64> where V: LinearStateFunction<S> {
+        (0..self.model.n_actions()).map(|a| self.lookahead_action(phi, a)).collect()
+    }
+}
+
+impl<S, V, P> OnlineLearner<S, usize> for LinearDyna<V, P>
+where
+    V: LinearStateFunction<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let n_features = self.v_func.n_features();
+        let phi_s = self.v_func.features(t.from.state());
+        let phi_ns = if t.terminated() {
+            Features::Sparse(n_features, Default::default())
+        } else {
+            self.v_func.features(t.to.state())
+        };
+
+        let residual = t.reward
+            + self.gamma * self.v_func.evaluate_features(&phi_ns)
+            - self.v_func.evaluate_features(&phi_s);
+
+        self.v_func.update_features(&phi_s, self.alpha * residual);
+        self.model.update(&phi_s, t.action, t.reward, &phi_ns);
+
+        for _ in 0..self.n_planning {
+            let i = self.rng.gen_range(0, n_features);
+            let phi: Features = Features::Sparse(n_features, vec![(i, 1.0)].into_iter().collect());
+
+            let target = self.lookahead(&phi).into_iter().fold(f64::MIN, f64::max);
+            let residual = target - self.v_func.evaluate_features(&phi);
+
+            self.v_func.update_features(&phi, self.alpha * residual);
+        }
+    }
+}
+
+impl<S, V, P> Controller<S, usize> for LinearDyna<V, P>
+where
+    V: LinearStateFunction<S>,
+    P: Policy<S, Action = usize>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> usize {
+        let phi = self.v_func.features(s);
+
+        argmax_choose_rng(rng, &self.lookahead(&phi)).1
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> usize {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<S, V, P> ValuePredictor<S> for LinearDyna<V, P>
+where
+    V: LinearStateFunction<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.v_func.evaluate_features(&self.v_func.features(s))
+    }
+}
+
+impl<S, V, P> ActionValuePredictor<S, usize> for LinearDyna<V, P>
+where
+    V: LinearStateFunction<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 {
+        self.lookahead_action(&self.v_func.features(s), *a)
+    }
+}
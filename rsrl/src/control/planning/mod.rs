@@ -0,0 +1,143 @@
+import_all!(dyna_q);
+import_all!(prioritized_sweeping);
+import_all!(linear_dyna);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner, make_shared,
+        control::{Controller, td::QLearning},
+        domains::{Domain, GridMaze, Observation, Transition},
+        fa::{
+            linear::{LFA, LinearStateFunction, basis::UniformGrid, optim::SGD},
+            tabular::Tabular,
+        },
+        models::{DeterministicModel, LinearModel},
+        policies::{EpsilonGreedy, Random},
+        prediction::ValuePredictor,
+        spaces::{Equipartition, ProductSpace},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use super::{DynaQ, LinearDyna, PrioritizedSweeping};
+
+    /// The Dyna maze of Sutton & Barto (figure 8.2), in which the shortest
+    /// path from start to goal takes 14 steps.
+    const MAZE: &str = "\
+        . . . . . . . # G\n\
+        . . # . . . . # .\n\
+        S . # . . . . # .\n\
+        . . # . . . . . .\n\
+        . . . . . # . . .\n\
+        . . . . . . . . .";
+
+    /// Return the number of real steps taken by `agent` over `n_episodes`
+    /// episodes of the maze.
+    fn real_steps<A>(agent: &mut A, n_episodes: usize) -> usize
+    where
+        A: OnlineLearner<usize, usize> + Controller<usize, usize>,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut n_steps = 0;
+
+        for _ in 0..n_episodes {
+            let mut domain = GridMaze::new(MAZE, 0.0);
+
+            while !domain.emit().is_terminal() {
+                let s = domain.emit().map_into(|l| l[0] + 9 * l[1]);
+                let t = domain.step(agent.sample_behaviour(&mut rng, &s));
+
+                agent.handle_transition(&t.map_states(|l| l[0] + 9 * l[1]));
+                n_steps += 1;
+            }
+
+            agent.handle_terminal();
+        }
+
+        n_steps
+    }
+
+    /// Return the number of real steps taken by Q-learning, with the same
+    /// behaviour policy and step sizes as the planning agents below.
+    fn q_learning_steps(n_episodes: usize) -> usize {
+        let q_func = make_shared(Tabular::zeros([54, 4]));
+        let policy = EpsilonGreedy::from_Q(q_func.clone(), 0.1);
+
+        real_steps(&mut QLearning::new(q_func, policy, 0.1, 0.95), n_episodes)
+    }
+
+    #[test]
+    fn test_dyna_q() {
+        let q_func = make_shared(Tabular::zeros([54, 4]));
+        let policy = EpsilonGreedy::from_Q(q_func.clone(), 0.1);
+        let mut agent = DynaQ::new(q_func, policy, DeterministicModel::new(), 0.1, 0.95, 0.0, 50)
+            .with_seed(0);
+
+        assert!(real_steps(&mut agent, 20) < q_learning_steps(20) / 2);
+    }
+
+    #[test]
+    fn test_prioritized_sweeping() {
+        let q_func = make_shared(Tabular::zeros([54, 4]));
+        let policy = EpsilonGreedy::from_Q(q_func.clone(), 0.1);
+        let mut agent = PrioritizedSweeping::new(
+            q_func, policy, DeterministicModel::new(), 0.1, 0.95, 1e-4, 50,
+        );
+
+        assert!(real_steps(&mut agent, 20) < q_learning_steps(20) / 2);
+    }
+
+    /// Return a Linear Dyna agent with one-hot features over a five-state
+    /// chain.
+    fn linear_dyna(n_planning: usize) -> LinearDyna<impl LinearStateFunction<Vec<f64>>, Random> {
+        let basis = UniformGrid::new(ProductSpace::new(vec![Equipartition::new(0.0, 5.0, 5)]));
+
+        LinearDyna::new(
+            LFA::scalar(basis, SGD(1.0)),
+            Random::new(2),
+            LinearModel::new(5, 2, 1.0),
+            0.5,
+            0.9,
+            n_planning,
+        ).with_seed(0)
+    }
+
+    #[test]
+    fn test_linear_dyna() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = linear_dyna(100);
+        let mut no_planning = linear_dyna(0);
+
+        // Visit each pair once, from the left, on a chain in which action 1
+        // moves right and reaching the right end pays one:
+        for s in 0..4 {
+            for a in 0..2 {
+                let ns = if a == 1 { s + 1 } else { s.max(1) - 1 };
+                let t = Transition {
+                    from: Observation::Full(vec![s as f64]),
+                    action: a,
+                    reward: if ns == 4 { 1.0 } else { 0.0 },
+                    to: if ns == 4 {
+                        Observation::Terminal(vec![ns as f64])
+                    } else {
+                        Observation::Full(vec![ns as f64])
+                    },
+                };
+
+                agent.handle_transition(&t);
+                no_planning.handle_transition(&t);
+            }
+        }
+
+        // A single pass of TD(0) only reaches the state next to the goal...
+        assert_eq!(no_planning.predict_v(&vec![0.0]), 0.0);
+
+        // ...while planning with the model propagates the reward back along
+        // the chain.
+        for s in 0..4 {
+            let v = agent.predict_v(&vec![s as f64]);
+
+            assert!((v - 0.9f64.powi(3 - s)).abs() < 0.05);
+            assert_eq!(agent.sample_target(&mut rng, &vec![s as f64]), 1);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Parameterised, Weights, WeightsView, WeightsViewMut,
+        EnumerableStateActionFunction,
+    },
+    models::TabularModel,
+    policies::Policy,
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::Rng;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    priority: f64,
+    pair: (usize, usize),
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        self.priority.partial_cmp(&other.priority).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Max-priority queue over state-action pairs in which each pair appears at
+/// most once, retaining its highest priority.
+#[derive(Clone, Debug, Default)]
+struct PriorityQueue {
+    heap: BinaryHeap<Entry>,
+    priorities: HashMap<(usize, usize), f64>,
+}
+
+impl PriorityQueue {
+    fn push(&mut self, pair: (usize, usize), priority: f64) {
+        let current = self.priorities.entry(pair).or_insert(f64::NEG_INFINITY);
+
+        if priority > *current {
+            *current = priority;
+
+            self.heap.push(Entry { priority, pair, });
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        while let Some(Entry { priority, pair }) = self.heap.pop() {
+            // Skip stale entries that have since been superseded.
+            if self.priorities.get(&pair) == Some(&priority) {
+                self.priorities.remove(&pair);
+
+                return Some(pair);
+            }
+        }
+
+        None
+    }
+}
+
+/// Prioritized sweeping.
+///
+/// Real transitions are used only to train the model. Planning backups are
+/// applied to state-action pairs in order of the magnitude of their expected
+/// update, with the predecessors of each updated state re-prioritised in
+/// turn; pairs whose priority does not exceed `theta` are never queued.
+///
+/// # References
+/// - Moore, A. W., Atkeson, C. G. (1993). Prioritized sweeping: Reinforcement
+///   learning with less data and less time. Machine Learning, 13:103–130.
+/// - Peng, J., Williams, R. J. (1993). Efficient learning and planning within
+///   the Dyna framework. Adaptive Behavior, 1(4):437–454.
+#[derive(Parameterised)]
+pub struct PrioritizedSweeping<Q, P, M> {
+    #[weights] pub q_func: Q,
+
+    pub policy: P,
+    pub model: M,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub theta: f64,
+
+    pub n_planning: usize,
+
+    queue: PriorityQueue,
+}
+
+impl<Q, P, M> PrioritizedSweeping<Q, P, M> {
+    pub fn new(
+        q_func: Q,
+        policy: P,
+        model: M,
+        alpha: f64,
+        gamma: f64,
+        theta: f64,
+        n_planning: usize,
+    ) -> Self {
+        PrioritizedSweeping {
+            q_func,
+
+            policy,
+            model,
+
+            alpha,
+            gamma,
+            theta,
+
+            n_planning,
+
+            queue: PriorityQueue::default(),
+        }
+    }
+}
+
+impl<Q, P, M> PrioritizedSweeping<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+    M: TabularModel,
+{
+    fn expected_residual(&self, s: usize, a: usize) -> f64 {
+        let target = self.model.outcomes(s, a).into_iter().fold(0.0, |acc, (p, r, ns)| {
+            let nv = if ns.is_terminal() { 0.0 } else { self.q_func.find_max(ns.state()).1 };
+
+            acc + p * (r + self.gamma * nv)
+        });
+
+        target - self.q_func.evaluate(&s, &a)
+    }
+
+    fn enqueue(&mut self, s: usize, a: usize) {
+        let priority = self.expected_residual(s, a).abs();
+
+        if priority > self.theta {
+            self.queue.push((s, a), priority);
+        }
+    }
+}
+
+impl<Q, P, M> OnlineLearner<usize, usize> for PrioritizedSweeping<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+    M: TabularModel,
+{
+    fn handle_transition(&mut self, t: &Transition<usize, usize>) {
+        self.model.handle_transition(t);
+        self.enqueue(*t.from.state(), t.action);
+
+        for _ in 0..self.n_planning {
+            let (s, a) = match self.queue.pop() {
+                Some(pair) => pair,
+                None => break,
+            };
+            let residual = self.expected_residual(s, a);
+
+            self.q_func.update(&s, &a, self.alpha * residual);
+
+            for (ps, pa) in self.model.predecessors(s) {
+                self.enqueue(ps, pa);
+            }
+        }
+    }
+
+    fn handle_terminal(&mut self) { self.model.handle_terminal(); }
+}
+
+impl<Q, P, M> Controller<usize, usize> for PrioritizedSweeping<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+    P: Policy<usize, Action = usize>,
+{
+    fn sample_target(&self, _: &mut impl Rng, s: &usize) -> usize { self.q_func.find_max(s).0 }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &usize) -> usize {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<Q, P, M> ValuePredictor<usize> for PrioritizedSweeping<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+{
+    fn predict_v(&self, s: &usize) -> f64 { self.q_func.find_max(s).1 }
+}
+
+impl<Q, P, M> ActionValuePredictor<usize, usize> for PrioritizedSweeping<Q, P, M>
+where
+    Q: EnumerableStateActionFunction<usize>,
+{
+    fn predict_q(&self, s: &usize, a: &usize) -> f64 { self.q_func.evaluate(s, a) }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    control::Controller,
+    domains::{Action, Domain, State},
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised},
+    policies::{DifferentiablePolicy, Policy},
+};
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::StandardNormal;
+use super::{Rollouts, assign, flatten, rank};
+
+/// Cross-entropy method.
+///
+/// Maintains a diagonal Gaussian search distribution over the policy weights,
+/// centred on the current weights. At each generation, `population_size`
+/// candidates are sampled and evaluated, and the distribution is refitted to
+/// the best `elite_fraction` of them. A constant `extra_noise` is added to the
+/// refitted variances to delay premature convergence.
+///
+/// # References
+/// - Rubinstein, R. Y., Kroese, D. P. (2004). The cross-entropy method: A
+///   unified approach to combinatorial optimization, Monte-Carlo simulation
+///   and machine learning. Springer.
+/// - Szita, I., Lőrincz, A. (2006). Learning Tetris using the noisy
+///   cross-entropy method. Neural Computation, 18(12), 2936–2941.
+#[derive(Parameterised)]
+pub struct CEM<P> {
+    #[weights] pub policy: P,
+    pub rollouts: Rollouts,
+
+    pub population_size: usize,
+    pub elite_fraction: f64,
+    pub extra_noise: f64,
+
+    stddev: Array1<f64>,
+}
+
+impl<P: Parameterised> CEM<P> {
+    pub fn new(
+        policy: P,
+        population_size: usize,
+        elite_fraction: f64,
+        initial_stddev: f64,
+    ) -> Self {
+        let n = flatten(&policy).len();
+
+        CEM {
+            policy,
+            rollouts: Rollouts::default(),
+
+            population_size,
+            elite_fraction,
+            extra_noise: 0.0,
+
+            stddev: Array1::from_elem(n, initial_stddev),
+        }
+    }
+
+    /// Return the standard deviations of the search distribution.
+    pub fn stddev(&self) -> &Array1<f64> { &self.stddev }
+
+    /// Run a single generation, updating the policy weights to the mean of the
+    /// refitted search distribution, and return the mean return of the
+    /// population.
+    pub fn step<D, F>(&mut self, rng: &mut impl Rng, domain_factory: &F) -> f64
+    where
+        D: Domain,
+        P: DifferentiablePolicy<State<D>, Action = Action<D>> + Clone + Send,
+        F: Fn() -> D + Sync,
+    {
+        let mean = flatten(&self.policy);
+        let candidates: Vec<Array1<f64>> = (0..self.population_size.max(1)).map(|_| {
+            let noise: Array1<f64> = self.stddev.iter()
+                .map(|sd| sd * rng.sample::<f64, _>(StandardNormal))
+                .collect();
+
+            &mean + &noise
+        }).collect();
+
+        let returns = self.rollouts.evaluate(rng, &self.policy, &candidates, domain_factory);
+
+        let ranking = rank(&returns);
+
+        let n_elite = ((candidates.len() as f64 * self.elite_fraction).round() as usize)
+            .max(1)
+            .min(candidates.len());
+        let elite = &ranking[..n_elite];
+
+        let new_mean = elite.iter().fold(Array1::zeros(mean.len()), |acc, &i| {
+            acc + &candidates[i]
+        }) / n_elite as f64;
+        let variance = elite.iter().fold(Array1::zeros(mean.len()), |acc, &i| {
+            let d = &candidates[i] - &new_mean;
+
+            acc + &(&d * &d)
+        }) / n_elite as f64;
+
+        let extra_noise = self.extra_noise;
+
+        self.stddev = variance.mapv(|v| (v + extra_noise).sqrt());
+        assign(&mut self.policy, &new_mean);
+
+        returns.iter().sum::<f64>() / returns.len() as f64
+    }
+}
+
+impl<S, P: Policy<S>> Controller<S, P::Action> for CEM<P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
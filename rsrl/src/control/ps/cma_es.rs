@@ -0,0 +1,161 @@
+use crate::{
+    control::Controller,
+    domains::{Action, Domain, State},
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised},
+    policies::{DifferentiablePolicy, Policy},
+};
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::StandardNormal;
+use super::{Rollouts, assign, flatten, rank};
+
+/// Covariance matrix adaptation evolution strategy with diagonal covariance.
+///
+/// Samples `population_size` candidates from `N(m, sigma^2 C)`, where the mean,
+/// `m`, is the current policy weights and `C` is diagonal, and moves the mean
+/// to a rank-weighted average of the best half. The global step size, `sigma`,
+/// is adapted by cumulative step-size adaptation, and `C` by the rank-one and
+/// rank-mu updates restricted to the diagonal, with the learning rates scaled
+/// up accordingly. Memory and time per generation are linear in the number of
+/// weights, which makes this variant suitable for large policies.
+///
+/// # References
+/// - Hansen, N., Ostermeier, A. (2001). Completely derandomized
+///   self-adaptation in evolution strategies. Evolutionary Computation, 9(2),
+///   159–195.
+/// - Ros, R., Hansen, N. (2008). A simple modification in CMA-ES achieving
+///   linear time and space complexity. In Parallel Problem Solving from
+///   Nature, pp. 296–305.
+#[derive(Parameterised)]
+pub struct CMAES<P> {
+    #[weights] pub policy: P,
+    pub rollouts: Rollouts,
+
+    pub population_size: usize,
+
+    sigma: f64,
+    c_diag: Array1<f64>,
+    p_sigma: Array1<f64>,
+    p_c: Array1<f64>,
+    generation: usize,
+}
+
+impl<P: Parameterised> CMAES<P> {
+    pub fn new(policy: P, initial_sigma: f64) -> Self {
+        let n = flatten(&policy).len();
+
+        CMAES {
+            policy,
+            rollouts: Rollouts::default(),
+
+            population_size: 4 + (3.0 * (n as f64).ln()).floor() as usize,
+
+            sigma: initial_sigma,
+            c_diag: Array1::ones(n),
+            p_sigma: Array1::zeros(n),
+            p_c: Array1::zeros(n),
+            generation: 0,
+        }
+    }
+
+    /// Return the global step size.
+    pub fn sigma(&self) -> f64 { self.sigma }
+
+    /// Return the diagonal of the covariance matrix.
+    pub fn covariance(&self) -> &Array1<f64> { &self.c_diag }
+
+    /// Run a single generation, updating the policy weights to the new mean of
+    /// the search distribution, and return the mean return of the population.
+    pub fn step<D, F>(&mut self, rng: &mut impl Rng, domain_factory: &F) -> f64
+    where
+        D: Domain,
+        P: DifferentiablePolicy<State<D>, Action = Action<D>> + Clone + Send,
+        F: Fn() -> D + Sync,
+    {
+        let mean = flatten(&self.policy);
+        let n = mean.len() as f64;
+        let lambda = self.population_size.max(2);
+        let mu = lambda / 2;
+
+        // Recombination weights and strategy parameters:
+        let w: Vec<f64> = (0..mu).map(|i| (mu as f64 + 0.5).ln() - (i as f64 + 1.0).ln()).collect();
+        let w_sum: f64 = w.iter().sum();
+        let w: Vec<f64> = w.into_iter().map(|wi| wi / w_sum).collect();
+        let mu_eff = 1.0 / w.iter().map(|wi| wi * wi).sum::<f64>();
+
+        let c_sigma = (mu_eff + 2.0) / (n + mu_eff + 5.0);
+        let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+        let c_c = (4.0 + mu_eff / n) / (n + 4.0 + 2.0 * mu_eff / n);
+        let c_1 = 2.0 / ((n + 1.3).powi(2) + mu_eff);
+        let c_mu = 2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((n + 2.0).powi(2) + mu_eff);
+
+        // Learning rates of the separable variant:
+        let scale = (n + 2.0) / 3.0;
+        let c_1 = (c_1 * scale).min(1.0);
+        let c_mu = (c_mu * scale).min(1.0 - c_1);
+
+        let chi_n = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+
+        // Sample the population, x = m + sigma sqrt(C) z:
+        let sqrt_c = self.c_diag.mapv(f64::sqrt);
+        let zs: Vec<Array1<f64>> = (0..lambda).map(|_| {
+            mean.iter().map(|_| rng.sample::<f64, _>(StandardNormal)).collect()
+        }).collect();
+        let candidates: Vec<Array1<f64>> = zs.iter()
+            .map(|z| &mean + &(&sqrt_c * z * self.sigma))
+            .collect();
+
+        let returns = self.rollouts.evaluate(rng, &self.policy, &candidates, domain_factory);
+
+        let ranking = rank(&returns);
+
+        // Weighted recombination of the best mu:
+        let elite = ranking[..mu].iter().zip(w.iter());
+        let z_w = elite.clone().fold(Array1::zeros(mean.len()), |acc, (&i, wi)| {
+            acc + &(&zs[i] * *wi)
+        });
+        let y_w = &sqrt_c * &z_w;
+
+        assign(&mut self.policy, &(&mean + &(&y_w * self.sigma)));
+
+        // Evolution paths:
+        self.generation += 1;
+        self.p_sigma = &self.p_sigma * (1.0 - c_sigma)
+            + &(&z_w * (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt());
+
+        let ps_norm = self.p_sigma.dot(&self.p_sigma).sqrt();
+        let h_sigma = ps_norm / (1.0 - (1.0 - c_sigma).powi(2 * self.generation as i32)).sqrt()
+            < (1.4 + 2.0 / (n + 1.0)) * chi_n;
+        let h_sigma = if h_sigma { 1.0 } else { 0.0 };
+
+        self.p_c = &self.p_c * (1.0 - c_c)
+            + &(&y_w * (h_sigma * (c_c * (2.0 - c_c) * mu_eff).sqrt()));
+
+        // Rank-one and rank-mu updates of the diagonal covariance:
+        let rank_mu = elite.fold(Array1::zeros(mean.len()), |acc, (&i, wi)| {
+            let y = &sqrt_c * &zs[i];
+
+            acc + &(&y * &y * *wi)
+        });
+        let decay = 1.0 - c_1 - c_mu + c_1 * (1.0 - h_sigma) * c_c * (2.0 - c_c);
+
+        self.c_diag = &self.c_diag * decay
+            + &(&self.p_c * &self.p_c * c_1)
+            + &(rank_mu * c_mu);
+
+        // Cumulative step-size adaptation:
+        self.sigma *= ((c_sigma / d_sigma) * (ps_norm / chi_n - 1.0)).exp();
+
+        returns.iter().sum::<f64>() / returns.len() as f64
+    }
+}
+
+impl<S, P: Policy<S>> Controller<S, P::Action> for CMAES<P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    control::Controller,
+    domains::{Action, Domain, State},
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised},
+    policies::{DifferentiablePolicy, Policy},
+};
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::StandardNormal;
+use super::{Rollouts, assign, flatten, rank};
+
+/// Return the centred ranks of `xs`, in `[-0.5, 0.5]`, with NaN ranked lowest.
+fn centred_ranks(xs: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+
+    if n < 2 { return vec![0.0; n]; }
+
+    let mut ranks = vec![0.0; n];
+
+    for (r, i) in rank(xs).into_iter().rev().enumerate() {
+        ranks[i] = r as f64 / (n - 1) as f64 - 0.5;
+    }
+
+    ranks
+}
+
+/// Natural evolution strategy with isotropic Gaussian perturbations.
+///
+/// Estimates the gradient of the expected return under `N(theta, sigma^2 I)`
+/// from `population_size` perturbations of the policy weights, `theta`, and
+/// takes a step of size `alpha` along it. With `antithetic` sampling, each
+/// perturbation is evaluated alongside its mirror image, which cancels the
+/// even-order terms of the estimator. With `rank_shaping`, the returns are
+/// replaced by their centred ranks, making the update invariant to monotone
+/// transformations of the reward.
+///
+/// # References
+/// - Salimans, T., Ho, J., Chen, X., Sidor, S., Sutskever, I. (2017).
+///   Evolution strategies as a scalable alternative to reinforcement
+///   learning. arXiv:1703.03864.
+/// - Wierstra, D., Schaul, T., Glasmachers, T., Sun, Y., Peters, J.,
+///   Schmidhuber, J. (2014). Natural evolution strategies. Journal of Machine
+///   Learning Research, 15, 949–980.
+#[derive(Parameterised)]
+pub struct ES<P> {
+    #[weights] pub policy: P,
+    pub rollouts: Rollouts,
+
+    pub alpha: f64,
+    pub sigma: f64,
+    pub population_size: usize,
+
+    pub antithetic: bool,
+    pub rank_shaping: bool,
+}
+
+impl<P> ES<P> {
+    pub fn new(policy: P, alpha: f64, sigma: f64, population_size: usize) -> Self {
+        ES {
+            policy,
+            rollouts: Rollouts::default(),
+
+            alpha,
+            sigma,
+            population_size,
+
+            antithetic: true,
+            rank_shaping: true,
+        }
+    }
+}
+
+impl<P: Parameterised> ES<P> {
+    /// Run a single generation, taking one gradient step on the policy weights,
+    /// and return the mean return of the population.
+    pub fn step<D, F>(&mut self, rng: &mut impl Rng, domain_factory: &F) -> f64
+    where
+        D: Domain,
+        P: DifferentiablePolicy<State<D>, Action = Action<D>> + Clone + Send,
+        F: Fn() -> D + Sync,
+    {
+        let theta = flatten(&self.policy);
+        let n_noise = if self.antithetic {
+            (self.population_size / 2).max(1)
+        } else {
+            self.population_size.max(2)
+        };
+
+        let eps: Vec<Array1<f64>> = (0..n_noise).map(|_| {
+            theta.iter().map(|_| rng.sample::<f64, _>(StandardNormal)).collect()
+        }).collect();
+
+        let mut candidates: Vec<Array1<f64>> = eps.iter()
+            .map(|e| &theta + &(e * self.sigma))
+            .collect();
+
+        if self.antithetic {
+            candidates.extend(eps.iter().map(|e| &theta - &(e * self.sigma)));
+        }
+
+        let returns = self.rollouts.evaluate(rng, &self.policy, &candidates, domain_factory);
+        let fitness = if self.rank_shaping {
+            centred_ranks(&returns)
+        } else {
+            let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+
+            returns.iter().map(|r| r - mean).collect()
+        };
+
+        let grad = eps.iter().enumerate().fold(Array1::zeros(theta.len()), |acc, (i, e)| {
+            let f = if self.antithetic { fitness[i] - fitness[i + n_noise] } else { fitness[i] };
+
+            acc + &(e * f)
+        }) / (candidates.len() as f64 * self.sigma);
+
+        assign(&mut self.policy, &(theta + &(grad * self.alpha)));
+
+        returns.iter().sum::<f64>() / returns.len() as f64
+    }
+}
+
+impl<S, P: Policy<S>> Controller<S, P::Action> for ES<P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
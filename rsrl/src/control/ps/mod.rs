@@ -0,0 +1,333 @@
+//! Derivative-free policy search agents.
+//!
+//! These agents treat the episodic return as a black-box function of the
+//! weights returned by `Parameterised::weights`. New weights are written back
+//! through `DifferentiablePolicy::update_grad`, which shares the same layout,
+//! so policies that split their weights across several approximators, such as
+//! `Gaussian`, are supported alongside those with a single weight matrix.
+use crate::{
+    domains::{Action, Domain, Observation, State},
+    fa::Parameterised,
+    policies::{DifferentiablePolicy, Policy},
+};
+use ndarray::Array1;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::thread;
+
+/// Configuration of the episodes used to evaluate candidate weights.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Rollouts {
+    /// Number of episodes averaged to estimate the return of each candidate.
+    pub n_episodes: usize,
+
+    /// Maximum number of steps per episode.
+    pub step_limit: u64,
+
+    /// Number of threads over which the candidates are split.
+    pub n_threads: usize,
+}
+
+impl Rollouts {
+    pub fn new(n_episodes: usize, step_limit: u64) -> Self {
+        Rollouts {
+            n_episodes,
+            step_limit,
+            n_threads: 1,
+        }
+    }
+
+    /// Return the undiscounted return of a single episode under `policy`.
+    fn episode<D, P>(&self, rng: &mut impl Rng, policy: &P, mut domain: D) -> f64
+    where
+        D: Domain,
+        P: Policy<State<D>, Action = Action<D>>,
+    {
+        let mut ret = 0.0;
+        let mut obs = domain.emit();
+
+        for _ in 0..self.step_limit {
+            let s = match obs {
+                Observation::Terminal(_) => break,
+                Observation::Full(ref s) | Observation::Partial(ref s) => s,
+            };
+
+            let t = domain.step(policy.sample(rng, s));
+
+            ret += t.reward;
+            obs = t.to;
+        }
+
+        ret
+    }
+
+    /// Return the mean return of each candidate, flattened as in `weights`,
+    /// with `policy` as a template. Each thread samples from its own generator,
+    /// seeded from `rng`.
+    pub fn evaluate<D, P, F>(
+        &self,
+        rng: &mut impl Rng,
+        policy: &P,
+        candidates: &[Array1<f64>],
+        domain_factory: &F,
+    ) -> Vec<f64>
+    where
+        D: Domain,
+        P: DifferentiablePolicy<State<D>, Action = Action<D>> + Clone + Send,
+        F: Fn() -> D + Sync,
+    {
+        let n_episodes = self.n_episodes.max(1);
+
+        let run = |mut policy: P, mut rng: StdRng, chunk: &[Array1<f64>]| -> Vec<f64> {
+            chunk.iter().map(|w| {
+                assign(&mut policy, w);
+
+                (0..n_episodes).fold(0.0, |acc, _| {
+                    acc + self.episode(&mut rng, &policy, domain_factory())
+                }) / n_episodes as f64
+            }).collect()
+        };
+
+        if self.n_threads <= 1 || candidates.len() <= 1 {
+            return run(policy.clone(), StdRng::seed_from_u64(rng.gen()), candidates);
+        }
+
+        let run = &run;
+        let chunk_size = candidates.len().div_ceil(self.n_threads);
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = candidates.chunks(chunk_size).map(|chunk| {
+                let policy = policy.clone();
+                let rng = StdRng::seed_from_u64(rng.gen());
+
+                scope.spawn(move || run(policy, rng, chunk))
+            }).collect();
+
+            handles.into_iter().flat_map(|h| h.join().expect("Rollout thread panicked.")).collect()
+        })
+    }
+}
+
+impl Default for Rollouts {
+    fn default() -> Rollouts { Rollouts::new(1, 1000) }
+}
+
+/// Return the weights of `policy` flattened into a vector.
+fn flatten<P: Parameterised>(policy: &P) -> Array1<f64> {
+    policy.weights().iter().cloned().collect()
+}
+
+/// Overwrite the weights of `policy` with the flattened vector `w`, by applying
+/// the difference to the current weights as a gradient step.
+fn assign<S, P: DifferentiablePolicy<S>>(policy: &mut P, w: &Array1<f64>) {
+    let weights = policy.weights();
+    let delta = &w.view().into_shape(weights.dim()).unwrap() - &weights;
+
+    policy.update_grad(&delta.view());
+}
+
+/// Return the indices of `returns` in order of decreasing return. NaN returns,
+/// such as those of diverged episodes, are ranked last.
+fn rank(returns: &[f64]) -> Vec<usize> {
+    let key = |x: f64| if x.is_nan() { f64::NEG_INFINITY } else { x };
+    let mut ranking: Vec<usize> = (0..returns.len()).collect();
+
+    ranking.sort_by(|&i, &j| key(returns[j]).total_cmp(&key(returns[i])));
+    ranking
+}
+
+import_all!(cem);
+import_all!(cma_es);
+import_all!(es);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domains::{Domain, Observation, Transition},
+        fa::linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+        policies::{
+            Beta, DifferentiablePolicy, EnumerablePolicy, Gamma, Gibbs, IPP,
+            gaussian::{self, Gaussian},
+        },
+        spaces::{ProductSpace, real::Interval, discrete::Ordinal},
+    };
+    use ndarray::Array1;
+    use rand::{rngs::StdRng, SeedableRng};
+    use super::{CEM, CMAES, ES, Rollouts, assign, flatten, rank};
+
+    /// One-step bandit in which only the second arm pays out.
+    struct Bandit(bool);
+
+    impl Domain for Bandit {
+        type StateSpace = ProductSpace<Interval>;
+        type ActionSpace = Ordinal;
+
+        fn emit(&self) -> Observation<Vec<f64>> {
+            if self.0 { Observation::Terminal(vec![0.0]) } else { Observation::Full(vec![0.0]) }
+        }
+
+        fn step(&mut self, action: usize) -> Transition<Vec<f64>, usize> {
+            let from = self.emit();
+
+            self.0 = true;
+
+            Transition {
+                from,
+                action,
+                reward: if action == 1 { 1.0 } else { 0.0 },
+                to: self.emit(),
+            }
+        }
+
+        fn state_space(&self) -> Self::StateSpace {
+            ProductSpace::empty() + Interval::bounded(0.0, 1.0)
+        }
+
+        fn action_space(&self) -> Ordinal { Ordinal::new(2) }
+    }
+
+    /// One-step bandit with a continuous action, paying `-(a - 1)^2`.
+    struct ContinuousBandit(bool);
+
+    impl Domain for ContinuousBandit {
+        type StateSpace = ProductSpace<Interval>;
+        type ActionSpace = Interval;
+
+        fn emit(&self) -> Observation<Vec<f64>> {
+            if self.0 { Observation::Terminal(vec![0.0]) } else { Observation::Full(vec![0.0]) }
+        }
+
+        fn step(&mut self, action: f64) -> Transition<Vec<f64>, f64> {
+            let from = self.emit();
+
+            self.0 = true;
+
+            Transition {
+                from,
+                action,
+                reward: -(action - 1.0).powi(2),
+                to: self.emit(),
+            }
+        }
+
+        fn state_space(&self) -> Self::StateSpace {
+            ProductSpace::empty() + Interval::bounded(0.0, 1.0)
+        }
+
+        fn action_space(&self) -> Interval { Interval::unbounded() }
+    }
+
+    fn policy() -> impl EnumerablePolicy<Vec<f64>> + DifferentiablePolicy<Vec<f64>> + Clone + Send {
+        Gibbs::standard(LFA::vector(Polynomial::new(1, 1).with_constant(), SGD(1.0), 2))
+    }
+
+    /// Check that `assign` writes the flattened weights of a composite policy.
+    fn check_assign<P: DifferentiablePolicy<Vec<f64>>>(mut policy: P) {
+        let w: Array1<f64> = (0..flatten(&policy).len()).map(|i| i as f64).collect();
+
+        assign(&mut policy, &w);
+
+        assert!(flatten(&policy).iter().zip(w.iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_assign_composite() {
+        let small = || LFA::scalar(Polynomial::new(1, 1).with_constant(), SGD(1.0));
+        let large = || LFA::scalar(Polynomial::new(2, 1).with_constant(), SGD(1.0));
+
+        check_assign(Beta::new(small(), large()));
+        check_assign(Gamma::new(large(), small()));
+        check_assign(IPP::new(policy(), policy()));
+        check_assign(Gaussian::new(
+            gaussian::mean::Scalar(small()),
+            gaussian::stddev::Scalar(small()),
+        ));
+    }
+
+    #[test]
+    fn test_rank_nan() {
+        let ranking = rank(&[1.0, f64::NAN, 3.0, -f64::NAN, 2.0]);
+
+        assert_eq!(&ranking[..3], &[2, 4, 0]);
+    }
+
+    #[test]
+    fn test_evaluate_seeded() {
+        let mut rollouts = Rollouts::new(5, 1);
+
+        rollouts.n_threads = 2;
+
+        let p = policy();
+        let candidates: Vec<Array1<f64>> = (0..4).map(|i| flatten(&p) + i as f64).collect();
+        let evaluate = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            rollouts.evaluate(&mut rng, &p, &candidates, &|| Bandit(false))
+        };
+
+        assert_eq!(evaluate(0), evaluate(0));
+    }
+
+    #[test]
+    fn test_cem_gaussian() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let basis = Polynomial::new(1, 1).with_constant();
+        let policy = Gaussian::new(
+            gaussian::mean::Scalar(LFA::scalar(basis, SGD(1.0))),
+            gaussian::stddev::Constant(0.1),
+        );
+        let mut agent = CEM::new(policy, 20, 0.2, 1.0);
+
+        agent.rollouts = Rollouts::new(10, 1);
+        agent.rollouts.n_threads = 2;
+
+        for _ in 0..30 {
+            agent.step(&mut rng, &|| ContinuousBandit(false));
+        }
+
+        assert!((agent.policy.compute_mean(&vec![0.0]) - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_cem() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = CEM::new(policy(), 20, 0.2, 1.0);
+
+        agent.rollouts = Rollouts::new(10, 1);
+        agent.rollouts.n_threads = 2;
+
+        for _ in 0..20 {
+            agent.step(&mut rng, &|| Bandit(false));
+        }
+
+        assert!(agent.policy.probabilities(&vec![0.0])[1] > 0.9);
+    }
+
+    #[test]
+    fn test_cma_es() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = CMAES::new(policy(), 1.0);
+
+        agent.rollouts = Rollouts::new(10, 1);
+
+        for _ in 0..30 {
+            agent.step(&mut rng, &|| Bandit(false));
+        }
+
+        assert!(agent.policy.probabilities(&vec![0.0])[1] > 0.9);
+    }
+
+    #[test]
+    fn test_es() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = ES::new(policy(), 0.5, 0.5, 20);
+
+        agent.rollouts = Rollouts::new(10, 1);
+        agent.rollouts.n_threads = 4;
+
+        for _ in 0..50 {
+            agent.step(&mut rng, &|| Bandit(false));
+        }
+
+        assert!(agent.policy.probabilities(&vec![0.0])[1] > 0.9);
+    }
+}
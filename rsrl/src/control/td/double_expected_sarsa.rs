@@ -0,0 +1,121 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Parameterised, Weights, WeightsView, WeightsViewMut,
+        EnumerableStateActionFunction,
+    },
+    policies::{Policy, EnumerablePolicy},
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Double variant of Expected SARSA.
+///
+/// The policy is expected to act on the combined estimate of both
+/// action-value functions (see `fa::Averaged`). At each step one estimate is
+/// chosen uniformly at random for the update, with the expectation over the
+/// policy at the next state taken with respect to the other. The choice is
+/// drawn from an internal generator, which may be seeded with `with_seed`.
+///
+/// # References
+/// - Ganger, M., Duryea, E., Hu, W. (2016). Double Sarsa and Double Expected
+///   Sarsa with Shallow and Deep Learning. Journal of Data Analysis and
+///   Information Processing, 4:159–176.
+#[derive(Parameterised)]
+pub struct DoubleExpectedSARSA<Q, P> {
+    #[weights] pub q_func_a: Q,
+    pub q_func_b: Q,
+
+    pub policy: P,
+
+    pub alpha: f64,
+    pub gamma: f64,
+
+    rng: StdRng,
+}
+
+impl<Q, P> DoubleExpectedSARSA<Q, P> {
+    pub fn new(q_func_a: Q, q_func_b: Q, policy: P, alpha: f64, gamma: f64) -> Self {
+        DoubleExpectedSARSA {
+            q_func_a,
+            q_func_b,
+
+            policy,
+
+            alpha,
+            gamma,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the generator used to choose which estimate to update.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl<S, Q, P> OnlineLearner<S, P::Action> for DoubleExpectedSARSA<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: EnumerablePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state();
+        let (q_update, q_target) = if self.rng.gen_bool(0.5) {
+            (&mut self.q_func_a, &self.q_func_b)
+        } else {
+            (&mut self.q_func_b, &self.q_func_a)
+        };
+
+        let qsa = q_update.evaluate(s, &t.action);
+        let residual = if t.terminated() {
+            t.reward - qsa
+        } else {
+            let ns = t.to.state();
+            let exp_nv = q_target.evaluate_all(ns).into_iter()
+                .zip(self.policy.probabilities(ns))
+                .fold(0.0, |acc, (q, p)| acc + q * p);
+
+            t.reward + self.gamma * exp_nv - qsa
+        };
+
+        q_update.update(s, &t.action, self.alpha * residual);
+    }
+}
+
+impl<S, Q, P: Policy<S>> Controller<S, P::Action> for DoubleExpectedSARSA<Q, P> {
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<S, Q, P> ValuePredictor<S> for DoubleExpectedSARSA<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: EnumerablePolicy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.q_func_a.evaluate_all(s).into_iter()
+            .zip(self.q_func_b.evaluate_all(s))
+            .zip(self.policy.probabilities(s))
+            .fold(0.0, |acc, ((qa, qb), p)| acc + (qa + qb) / 2.0 * p)
+    }
+}
+
+impl<S, Q, P> ActionValuePredictor<S, P::Action> for DoubleExpectedSARSA<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: EnumerablePolicy<S>,
+{
+    fn predict_q(&self, s: &S, a: &P::Action) -> f64 {
+        (self.q_func_a.evaluate(s, a) + self.q_func_b.evaluate(s, a)) / 2.0
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    OnlineLearner,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        Parameterised, Weights, WeightsView, WeightsViewMut,
+        EnumerableStateActionFunction,
+    },
+    policies::{Greedy, Policy, EnumerablePolicy},
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Double Q-learning.
+///
+/// Two independent estimates are maintained; at each step one of them is
+/// chosen uniformly at random for the update, with the other used to evaluate
+/// the greedy action selected by the first. The choice is drawn from an
+/// internal generator, which may be seeded with `with_seed`.
+///
+/// # References
+/// - Hasselt, H. V. (2010). Double Q-learning. In Advances in Neural
+///   Information Processing Systems, pp. 2613–2621.
+#[derive(Parameterised)]
+pub struct DoubleQLearning<Q, P> {
+    #[weights] pub q_func_a: Q,
+    pub q_func_b: Q,
+
+    pub policy: P,
+
+    pub alpha: f64,
+    pub gamma: f64,
+
+    rng: StdRng,
+}
+
+impl<Q, P> DoubleQLearning<Q, P> {
+    pub fn new(q_func_a: Q, q_func_b: Q, policy: P, alpha: f64, gamma: f64) -> Self {
+        DoubleQLearning {
+            q_func_a,
+            q_func_b,
+
+            policy,
+
+            alpha,
+            gamma,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the generator used to choose which estimate to update.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn evaluate_all<S>(&self, s: &S) -> Vec<f64> where Q: EnumerableStateActionFunction<S> {
+        self.q_func_a.evaluate_all(s).into_iter()
+            .zip(self.q_func_b.evaluate_all(s))
+            .map(|(qa, qb)| (qa + qb) / 2.0)
+            .collect()
+    }
+}
+
+impl<S, Q, P> OnlineLearner<S, P::Action> for DoubleQLearning<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: EnumerablePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, P::Action>) {
+        let s = t.from.state();
+        let (q_update, q_target) = if self.rng.gen_bool(0.5) {
+            (&mut self.q_func_a, &self.q_func_b)
+        } else {
+            (&mut self.q_func_b, &self.q_func_a)
+        };
+
+        let qsa = q_update.evaluate(s, &t.action);
+        let residual = if t.terminated() {
+            t.reward - qsa
+        } else {
+            let ns = t.to.state();
+            let (na, _) = q_update.find_max(ns);
+
+            t.reward + self.gamma * q_target.evaluate(ns, &na) - qsa
+        };
+
+        q_update.update(s, &t.action, self.alpha * residual);
+    }
+}
+
+impl<S, Q, P> Controller<S, P::Action> for DoubleQLearning<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: EnumerablePolicy<S>,
+{
+    fn sample_target(&self, _: &mut impl Rng, s: &S) -> P::Action {
+        Greedy::<Q>::argmax_qs(&self.evaluate_all(s))
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> P::Action {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<S, Q, P> ValuePredictor<S> for DoubleQLearning<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: Policy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.evaluate_all(s).into_iter().fold(f64::MIN, f64::max)
+    }
+}
+
+impl<S, Q, P> ActionValuePredictor<S, <Greedy<Q> as Policy<S>>::Action> for DoubleQLearning<Q, P>
+where
+    Q: EnumerableStateActionFunction<S>,
+    P: Policy<S>,
+{
+    fn predict_q(&self, s: &S, a: &<Greedy<Q> as Policy<S>>::Action) -> f64 {
+        (self.q_func_a.evaluate(s, a) + self.q_func_b.evaluate(s, a)) / 2.0
+    }
+}
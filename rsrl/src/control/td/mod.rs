@@ -1,13 +1,176 @@
 // Off-policy:
 import_all!(q_learning);
+import_all!(double_q_learning);
 import_all!(q_lambda);
 import_all!(q_sigma);
 import_all!(pal);
+import_all!(soft_q_learning);
 
 // On-policy:
 import_all!(sarsa);
 import_all!(sarsa_lambda);
 import_all!(expected_sarsa);
+import_all!(double_expected_sarsa);
+import_all!(soft_expected_sarsa);
 
 // TODO:
 // PQ(lambda) - http://proceedings.mlr.press/v32/sutton14.pdf
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner, make_shared,
+        control::Controller,
+        domains::{Observation, Transition},
+        fa::{Averaged, tabular::Tabular},
+        policies::{EnumerablePolicy, EpsilonGreedy, Policy, Random},
+        prediction::{ActionValuePredictor, ValuePredictor},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+    use super::{DoubleExpectedSARSA, DoubleQLearning, QLearning, SoftExpectedSARSA, SoftQLearning};
+
+    /// Return a one-step transition from the single state of a bandit.
+    fn pull(action: usize, reward: f64) -> Transition<usize, usize> {
+        Transition {
+            from: Observation::Full(0),
+            action,
+            reward,
+            to: Observation::Terminal(0),
+        }
+    }
+
+    #[test]
+    fn test_soft_values() {
+        let q_func = Tabular::new(vec![vec![1.0], vec![3.0]]);
+        let agent = SoftQLearning::new(q_func.clone(), 0.1, 0.9, 2.0);
+
+        // tau log(e^{1/2} + e^{3/2}):
+        let expected = 2.0 * (0.5f64.exp() + 1.5f64.exp()).ln();
+
+        assert!((agent.predict_v(&0) - expected).abs() < 1e-10);
+
+        // Uniform policy: mean value plus tau ln 2.
+        let agent = SoftExpectedSARSA::new(q_func, Random::new(2), 0.1, 0.9, 2.0);
+
+        assert!((agent.predict_v(&0) - 2.0 - 2.0 * 2.0f64.ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_soft_q_learning() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = SoftQLearning::new(Tabular::zeros([1, 2]), 0.1, 0.9, 0.5);
+
+        for _ in 0..1000 {
+            let action = agent.sample_behaviour(&mut rng, &0);
+
+            agent.handle_transition(&pull(action, if action == 1 { 1.0 } else { 0.0 }));
+        }
+
+        // pi(1) = 1 / (1 + e^{-1 / 0.5}) once Q has converged:
+        let p = agent.policy.probabilities(&0)[1];
+
+        assert!((p - 1.0 / (1.0 + (-2.0f64).exp())).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_soft_q_learning_temperature() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = SoftQLearning::new(Tabular::zeros([1, 2]), 0.1, 0.9, 1.0);
+
+        agent.target_entropy = Some(0.1);
+        agent.temperature_lr = 0.01;
+
+        for _ in 0..5000 {
+            let action = agent.sample_behaviour(&mut rng, &0);
+
+            agent.handle_transition(&pull(action, if action == 1 { 1.0 } else { 0.0 }));
+        }
+
+        assert!(agent.temperature() < 0.5);
+        assert!((agent.policy.entropy(&0) - 0.1).abs() < 0.05);
+    }
+
+    /// Return the mean estimate of going left from A over `n_runs`
+    /// independent agents, each created from the index of its run and trained
+    /// on 1000 episodes of uniformly random behaviour in the maximisation-bias
+    /// MDP of Sutton & Barto (example 6.7).
+    ///
+    /// From A (state 0), action 0 leads to B (state 1) and the remaining
+    /// actions terminate with reward 0; every action in B terminates with a
+    /// reward drawn from N(-0.1, 1), so the true value of going left is -0.1.
+    fn left_value<L>(n_runs: usize, mut new_agent: impl FnMut(u64) -> L) -> f64
+    where
+        L: OnlineLearner<usize, usize> + ActionValuePredictor<usize, usize>,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut total = 0.0;
+
+        for run in 0..n_runs {
+            let mut agent = new_agent(run as u64);
+
+            for _ in 0..1000 {
+                if rng.gen_bool(0.5) {
+                    let noise: f64 = rng.sample(StandardNormal);
+
+                    agent.handle_transition(&Transition {
+                        from: Observation::Full(0),
+                        action: 0,
+                        reward: 0.0,
+                        to: Observation::Full(1),
+                    });
+                    agent.handle_transition(&Transition {
+                        from: Observation::Full(1),
+                        action: rng.gen_range(0, 10),
+                        reward: noise - 0.1,
+                        to: Observation::Terminal(1),
+                    });
+                } else {
+                    agent.handle_transition(&Transition {
+                        from: Observation::Full(0),
+                        action: rng.gen_range(1, 10),
+                        reward: 0.0,
+                        to: Observation::Terminal(0),
+                    });
+                }
+            }
+
+            total += agent.predict_q(&0, &0);
+        }
+
+        total / n_runs as f64
+    }
+
+    #[test]
+    fn test_maximisation_bias() {
+        let q_learning = left_value(200, |_| {
+            QLearning::new(Tabular::zeros([2, 10]), Random::new(10), 0.1, 1.0)
+        });
+        let double_q_learning = left_value(200, |seed| {
+            DoubleQLearning::new(
+                Tabular::zeros([2, 10]),
+                Tabular::zeros([2, 10]),
+                Random::new(10),
+                0.1,
+                1.0,
+            ).with_seed(seed)
+        });
+        let double_expected_sarsa = left_value(200, |seed| {
+            let q_a = make_shared(Tabular::zeros([2, 10]));
+            let q_b = make_shared(Tabular::zeros([2, 10]));
+            let policy = EpsilonGreedy::from_Q(Averaged::new(q_a.clone(), q_b.clone()), 0.1);
+
+            DoubleExpectedSARSA::new(q_a, q_b, policy, 0.1, 1.0).with_seed(seed)
+        });
+
+        // Maximising over the noisy estimates in B makes going left look
+        // better than going right:
+        assert!(q_learning > 0.1);
+
+        // Decoupling selection from evaluation removes the bias...
+        assert!((double_q_learning + 0.1).abs() < 0.05);
+
+        // ...or, when the policy still acts on both estimates, reduces it.
+        assert!(double_expected_sarsa < q_learning - 0.07);
+    }
+}
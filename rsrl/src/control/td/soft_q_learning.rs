@@ -0,0 +1,137 @@
+use crate::{
+    OnlineLearner, Shared, make_shared,
+    control::Controller,
+    domains::Transition,
+    fa::{
+        EnumerableStateActionFunction,
+        Parameterised, Weights, WeightsView, WeightsViewMut,
+    },
+    policies::{Softmax, Policy},
+    prediction::{ValuePredictor, ActionValuePredictor},
+};
+use rand::Rng;
+
+const TAU_MIN: f64 = 1e-6;
+
+/// Return the temperature after a single step of automatic tuning towards the
+/// `target` entropy.
+///
+/// The temperature is adapted in log-space by descending `tau (H - target)`,
+/// such that it grows while the policy entropy, `H`, is below the target, and
+/// shrinks while it is above.
+fn tune_temperature(tau: f64, entropy: f64, target: f64, lr: f64) -> f64 {
+    (tau * (-lr * (entropy - target)).exp()).max(TAU_MIN)
+}
+
+/// Soft Q-learning.
+///
+/// Learns the soft action-value function of the maximum-entropy objective with
+/// temperature `tau`, backing up the soft value, `V(s') = tau log sum_a'
+/// exp(Q(s', a') / tau)`, in place of the max used by Q-learning. The target
+/// and behaviour policies are the Boltzmann distribution over `Q / tau`, which
+/// is the optimal policy under the entropy-regularised objective.
+///
+/// When `target_entropy` is set, the temperature is tuned automatically with
+/// step size `temperature_lr` so that the entropy of the policy in visited
+/// states tracks the target.
+///
+/// # References
+/// - Haarnoja, T., Tang, H., Abbeel, P., Levine, S. (2017). Reinforcement
+///   learning with deep energy-based policies. In Proceedings of the 34th
+///   International Conference on Machine Learning, pp. 1352–1361.
+/// - Haarnoja, T., Zhou, A., Hartikainen, K., Tucker, G., Ha, S., Tan, J.,
+///   Kumar, V., Zhu, H., Gupta, A., Abbeel, P., Levine, S. (2018). Soft
+///   actor-critic algorithms and applications. arXiv:1812.05905.
+#[derive(Parameterised)]
+pub struct SoftQLearning<Q> {
+    #[weights] pub q_func: Q,
+    pub policy: Softmax<Q>,
+
+    pub alpha: f64,
+    pub gamma: f64,
+
+    pub target_entropy: Option<f64>,
+    pub temperature_lr: f64,
+}
+
+impl<Q> SoftQLearning<Shared<Q>> {
+    pub fn new(q_func: Q, alpha: f64, gamma: f64, tau: f64) -> Self {
+        let q_func = make_shared(q_func);
+
+        SoftQLearning {
+            q_func: q_func.clone(),
+            policy: Softmax::new(q_func, tau),
+
+            alpha,
+            gamma,
+
+            target_entropy: None,
+            temperature_lr: 1e-3,
+        }
+    }
+}
+
+impl<Q> SoftQLearning<Q> {
+    /// Return the current temperature.
+    pub fn temperature(&self) -> f64 { self.policy.tau }
+}
+
+impl<S, Q> OnlineLearner<S, usize> for SoftQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+        let qsa = self.q_func.evaluate(s, &t.action);
+
+        let residual = if t.terminated() {
+            t.reward - qsa
+        } else {
+            t.reward + self.gamma * self.predict_v(t.to.state()) - qsa
+        };
+
+        if let Some(target) = self.target_entropy {
+            let entropy = self.policy.entropy(s);
+
+            self.policy.tau =
+                tune_temperature(self.policy.tau, entropy, target, self.temperature_lr);
+        }
+
+        self.q_func.update(s, &t.action, self.alpha * residual);
+    }
+}
+
+impl<S, Q> Controller<S, usize> for SoftQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn sample_target(&self, rng: &mut impl Rng, s: &S) -> usize {
+        self.policy.sample(rng, s)
+    }
+
+    fn sample_behaviour(&self, rng: &mut impl Rng, s: &S) -> usize {
+        self.policy.sample(rng, s)
+    }
+}
+
+impl<S, Q> ValuePredictor<S> for SoftQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        let tau = self.policy.tau;
+        let qs = self.q_func.evaluate_all(s);
+        let max_q = qs.iter().fold(f64::NEG_INFINITY, |acc, &q| acc.max(q));
+
+        max_q + tau * qs.into_iter().fold(0.0, |acc, q| acc + ((q - max_q) / tau).exp()).ln()
+    }
+}
+
+impl<S, Q> ActionValuePredictor<S, usize> for SoftQLearning<Q>
+where
+    Q: EnumerableStateActionFunction<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 {
+        self.q_func.evaluate(s, a)
+    }
+}
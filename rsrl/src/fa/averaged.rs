@@ -0,0 +1,102 @@
+use crate::fa::{StateFunction, StateActionFunction, EnumerableStateActionFunction};
+
+/// Point-wise average of a pair of function approximators.
+///
+/// Updates are applied in full to both members such that the average moves by
+/// the given error.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct Averaged<F1, F2 = F1>(pub F1, pub F2);
+
+impl<F1, F2> Averaged<F1, F2> {
+    pub fn new(f1: F1, f2: F2) -> Self { Averaged(f1, f2) }
+}
+
+// V(s):
+impl<X: ?Sized, F1, F2> StateFunction<X> for Averaged<F1, F2>
+where
+    F1: StateFunction<X, Output = f64>,
+    F2: StateFunction<X, Output = f64>,
+{
+    type Output = f64;
+
+    fn evaluate(&self, state: &X) -> f64 {
+        (self.0.evaluate(state) + self.1.evaluate(state)) / 2.0
+    }
+
+    fn update(&mut self, state: &X, error: f64) {
+        self.0.update(state, error);
+        self.1.update(state, error);
+    }
+}
+
+// Q(s, a):
+impl<X: ?Sized, U: ?Sized, F1, F2> StateActionFunction<X, U> for Averaged<F1, F2>
+where
+    F1: StateActionFunction<X, U, Output = f64>,
+    F2: StateActionFunction<X, U, Output = f64>,
+{
+    type Output = f64;
+
+    fn evaluate(&self, state: &X, action: &U) -> f64 {
+        (self.0.evaluate(state, action) + self.1.evaluate(state, action)) / 2.0
+    }
+
+    fn update(&mut self, state: &X, action: &U, error: f64) {
+        self.0.update(state, action, error);
+        self.1.update(state, action, error);
+    }
+}
+
+impl<X: ?Sized, F1, F2> EnumerableStateActionFunction<X> for Averaged<F1, F2>
+where
+    F1: EnumerableStateActionFunction<X>,
+    F2: EnumerableStateActionFunction<X>,
+{
+    fn n_actions(&self) -> usize { self.0.n_actions() }
+
+    fn evaluate_all(&self, state: &X) -> Vec<f64> {
+        self.0.evaluate_all(state).into_iter()
+            .zip(self.1.evaluate_all(state))
+            .map(|(q1, q2)| (q1 + q2) / 2.0)
+            .collect()
+    }
+
+    fn update_all(&mut self, state: &X, errors: Vec<f64>) {
+        self.0.update_all(state, errors.clone());
+        self.1.update_all(state, errors);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fa::{tabular::Tabular, EnumerableStateActionFunction, StateActionFunction};
+    use super::Averaged;
+
+    #[test]
+    fn test_evaluate() {
+        let q = Averaged::new(
+            Tabular::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]),
+            Tabular::new(vec![vec![3.0, 0.0], vec![1.0, 0.0]]),
+        );
+
+        assert_eq!(q.evaluate(&0, &0), 2.0);
+        assert_eq!(q.evaluate(&1, &1), 2.0);
+        assert_eq!(q.evaluate_all(&0), vec![2.0, 2.0]);
+        assert_eq!(q.evaluate_all(&1), vec![1.0, 2.0]);
+        assert_eq!(q.find_max(&1), (1, 2.0));
+    }
+
+    #[test]
+    fn test_update() {
+        let mut q = Averaged::new(Tabular::zeros([2, 2]), Tabular::zeros([2, 2]));
+
+        q.update(&0, &1, 1.0);
+        q.update_all(&1, vec![-1.0, 2.0]);
+
+        assert_eq!(q.evaluate(&0, &1), 1.0);
+        assert_eq!(q.0.evaluate(&0, &1), 1.0);
+        assert_eq!(q.1.evaluate(&0, &1), 1.0);
+        assert_eq!(q.evaluate_all(&1), vec![-1.0, 2.0]);
+    }
+}
@@ -7,6 +7,7 @@ pub mod tabular;
 
 pub mod transforms;
 import_all!(transformed);
+import_all!(averaged);
 
 import_all!(shared);
 
@@ -36,6 +36,7 @@ pub extern crate spaces;
 #[macro_use]
 pub mod fa;
 pub mod control;
+pub mod models;
 pub mod policies;
 pub mod prediction;
 pub mod traces;
@@ -0,0 +1,145 @@
+use crate::fa::linear::{Features, dot_features};
+use std::mem::replace;
+
+/// Return the non-zero (index, activation) pairs of a feature vector.
+fn active(phi: &Features) -> Vec<(usize, f64)> {
+    match phi {
+        Features::Dense(activations) => activations.iter().copied().enumerate()
+            .filter(|&(_, act)| act != 0.0)
+            .collect(),
+        Features::Sparse(_, activations) => activations.iter().map(|(&i, &act)| (i, act)).collect(),
+    }
+}
+
+/// Add `alpha * other` to `f` in place, preserving sparsity where possible.
+fn scaled_add(f: &mut Features, alpha: f64, other: &Features) {
+    let current = replace(f, Features::Sparse(0, Default::default()));
+
+    *f = current.combine(other, |x, y| x + alpha * y);
+}
+
+/// Linear, feature-space model with one transition matrix and reward vector
+/// per action.
+///
+/// For each action `a`, the expected next feature vector is predicted as
+/// `F_a^T phi` and the expected reward as `b_a^T phi`; both are fitted online
+/// by least-mean-squares updates with step size `alpha`.
+///
+/// Each `F_a` is stored as one feature vector per row, and each `b_a` as a
+/// single feature vector, all of which start out sparse and empty. Row `i`
+/// only gains entries once feature `i` has been active under action `a`, so
+/// with sparse bases (e.g. tile coding) memory grows with the transitions
+/// observed rather than with the square of the number of features.
+///
+/// # References
+/// - Sutton, R. S., Szepesvári, Cs., Geramifard, A., Bowling, M. (2008).
+///   Dyna-style planning with linear function approximation and prioritized
+///   sweeping. In Proceedings of the 24th Conference on Uncertainty in
+///   Artificial Intelligence, pp. 528–536.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct LinearModel {
+    pub transitions: Vec<Vec<Features>>,
+    pub rewards: Vec<Features>,
+
+    pub alpha: f64,
+}
+
+impl LinearModel {
+    pub fn new(n_features: usize, n_actions: usize, alpha: f64) -> Self {
+        let empty = Features::Sparse(n_features, Default::default());
+
+        LinearModel {
+            transitions: vec![vec![empty.clone(); n_features]; n_actions],
+            rewards: vec![empty; n_actions],
+
+            alpha,
+        }
+    }
+
+    pub fn n_features(&self) -> usize { self.rewards.first().map_or(0, |b| b.n_features()) }
+
+    pub fn n_actions(&self) -> usize { self.rewards.len() }
+
+    /// Return the expected next feature vector after taking action `a`.
+    pub fn predict_features(&self, phi: &Features, a: usize) -> Features {
+        let mut prediction = Features::Sparse(self.n_features(), Default::default());
+
+        for (i, act) in active(phi) {
+            scaled_add(&mut prediction, act, &self.transitions[a][i]);
+        }
+
+        prediction
+    }
+
+    /// Return the expected reward for taking action `a`.
+    pub fn predict_reward(&self, phi: &Features, a: usize) -> f64 {
+        dot_features(phi, &self.rewards[a])
+    }
+
+    /// Update the model for action `a` towards an observed reward and
+    /// successor feature vector.
+    pub fn update(&mut self, phi: &Features, a: usize, reward: f64, phi_next: &Features) {
+        let reward_error = reward - self.predict_reward(phi, a);
+        let features_error = phi_next.clone().combine(&self.predict_features(phi, a), |x, y| x - y);
+
+        scaled_add(&mut self.rewards[a], self.alpha * reward_error, phi);
+
+        for (i, act) in active(phi) {
+            scaled_add(&mut self.transitions[a][i], self.alpha * act, &features_error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fa::linear::Features;
+    use super::LinearModel;
+
+    #[test]
+    fn test_sparse() {
+        let mut m = LinearModel::new(3, 2, 0.5);
+
+        let phi: Features = vec![0].into();
+        let phi_next = Features::Sparse(3, vec![(2, 1.0)].into_iter().collect());
+
+        for _ in 0..100 {
+            m.update(&phi, 1, 2.0, &phi_next);
+        }
+
+        assert!((m.predict_reward(&phi, 1) - 2.0).abs() < 1e-7);
+        assert_eq!(m.predict_reward(&phi, 0), 0.0);
+
+        let pred = m.predict_features(&phi, 1).expanded();
+
+        assert!((pred[0] - 0.0).abs() < 1e-7);
+        assert!((pred[1] - 0.0).abs() < 1e-7);
+        assert!((pred[2] - 1.0).abs() < 1e-7);
+
+        // Only the row of the active feature is populated, and sparsely:
+        assert_eq!(m.transitions[1][0].n_active(), 1);
+        assert_eq!(m.transitions[1][1].n_active(), 0);
+        assert_eq!(m.transitions[0][0].n_active(), 0);
+        assert!(m.predict_features(&phi, 1).is_sparse());
+    }
+
+    #[test]
+    fn test_dense() {
+        let mut m = LinearModel::new(2, 1, 0.1);
+
+        let phis: Vec<Features> = vec![vec![1.0, 0.0].into(), vec![0.0, 1.0].into()];
+        let phis_next: Vec<Features> = vec![vec![0.5, 0.5].into(), vec![1.0, -1.0].into()];
+
+        for _ in 0..500 {
+            m.update(&phis[0], 0, 1.0, &phis_next[0]);
+            m.update(&phis[1], 0, -1.0, &phis_next[1]);
+        }
+
+        let phi: Features = vec![1.0, 1.0].into();
+        let pred = m.predict_features(&phi, 0).expanded();
+
+        assert!((m.predict_reward(&phi, 0) - 0.0).abs() < 1e-5);
+        assert!((pred[0] - 1.5).abs() < 1e-5);
+        assert!((pred[1] + 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,42 @@
+//! Learned environment models module.
+//!
+//! This module contains models of the problem environment that are learned
+//! from experience and used by model-based agents to generate simulated
+//! transitions for planning.
+use crate::{OnlineLearner, domains::Observation};
+use rand::Rng;
+
+import_all!(tabular);
+import_all!(linear);
+
+/// Trait for models defined over enumerable state and action spaces.
+///
+/// Models are updated from real experience via the `OnlineLearner`
+/// interface, and are keyed on `(usize, usize)` state-action pairs.
+pub trait TabularModel: OnlineLearner<usize, usize> {
+    /// Return the number of state-action pairs observed so far.
+    fn n_pairs(&self) -> usize;
+
+    /// Sample a previously observed state-action pair uniformly at random.
+    fn sample_pair<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(usize, usize)>;
+
+    /// Sample a reward and successor observation for a given state-action
+    /// pair, if it has been observed.
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        s: usize,
+        a: usize,
+    ) -> Option<(f64, Observation<usize>)>;
+
+    /// Return the possible outcomes of a state-action pair as a list of
+    /// `(probability, expected reward, successor)` triples.
+    fn outcomes(&self, s: usize, a: usize) -> Vec<(f64, f64, Observation<usize>)>;
+
+    /// Return all observed state-action pairs predicted to lead to state `s`.
+    fn predecessors(&self, s: usize) -> Vec<(usize, usize)>;
+
+    /// Return the number of real steps elapsed since the pair was last
+    /// observed, if ever.
+    fn n_steps_since(&self, s: usize, a: usize) -> Option<u64>;
+}
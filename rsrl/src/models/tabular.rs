@@ -0,0 +1,272 @@
+use crate::{
+    OnlineLearner,
+    domains::{Observation, Transition},
+    models::TabularModel,
+};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet};
+
+fn same_outcome(o1: &Observation<usize>, o2: &Observation<usize>) -> bool {
+    o1.state() == o2.state() && o1.is_terminal() == o2.is_terminal()
+}
+
+/// Tabular model of a deterministic environment.
+///
+/// Only the most recent outcome of each state-action pair is retained.
+#[derive(Clone, Debug, Default)]
+pub struct DeterministicModel {
+    transitions: HashMap<(usize, usize), (f64, Observation<usize>)>,
+    pairs: Vec<(usize, usize)>,
+    predecessors: HashMap<usize, HashSet<(usize, usize)>>,
+
+    last_visit: HashMap<(usize, usize), u64>,
+    n_steps: u64,
+}
+
+impl DeterministicModel {
+    pub fn new() -> Self { Self::default() }
+}
+
+impl OnlineLearner<usize, usize> for DeterministicModel {
+    fn handle_transition(&mut self, t: &Transition<usize, usize>) {
+        let key = (*t.from.state(), t.action);
+
+        self.n_steps += 1;
+        self.last_visit.insert(key, self.n_steps);
+
+        match self.transitions.insert(key, (t.reward, t.to)) {
+            Some((_, old)) => if old.state() != t.to.state() {
+                if let Some(preds) = self.predecessors.get_mut(old.state()) {
+                    preds.remove(&key);
+                }
+            },
+            None => self.pairs.push(key),
+        }
+
+        self.predecessors.entry(*t.to.state()).or_default().insert(key);
+    }
+}
+
+impl TabularModel for DeterministicModel {
+    fn n_pairs(&self) -> usize { self.pairs.len() }
+
+    fn sample_pair<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        self.pairs.choose(rng).copied()
+    }
+
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        _: &mut R,
+        s: usize,
+        a: usize,
+    ) -> Option<(f64, Observation<usize>)> {
+        self.transitions.get(&(s, a)).copied()
+    }
+
+    fn outcomes(&self, s: usize, a: usize) -> Vec<(f64, f64, Observation<usize>)> {
+        self.transitions.get(&(s, a)).map_or(vec![], |&(r, ns)| vec![(1.0, r, ns)])
+    }
+
+    fn predecessors(&self, s: usize) -> Vec<(usize, usize)> {
+        self.predecessors.get(&s).map_or(vec![], |preds| preds.iter().copied().collect())
+    }
+
+    fn n_steps_since(&self, s: usize, a: usize) -> Option<u64> {
+        self.last_visit.get(&(s, a)).map(|&k| self.n_steps - k)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Outcome {
+    next: Observation<usize>,
+    count: usize,
+    reward_sum: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct PairStatistics {
+    count: usize,
+    outcomes: Vec<Outcome>,
+}
+
+/// Sample-based tabular model of a stochastic environment.
+///
+/// Successor observations are sampled in proportion to their empirical
+/// frequency, with rewards given by the empirical mean for each outcome.
+#[derive(Clone, Debug, Default)]
+pub struct StochasticModel {
+    statistics: HashMap<(usize, usize), PairStatistics>,
+    pairs: Vec<(usize, usize)>,
+    predecessors: HashMap<usize, HashSet<(usize, usize)>>,
+
+    last_visit: HashMap<(usize, usize), u64>,
+    n_steps: u64,
+}
+
+impl StochasticModel {
+    pub fn new() -> Self { Self::default() }
+}
+
+impl OnlineLearner<usize, usize> for StochasticModel {
+    fn handle_transition(&mut self, t: &Transition<usize, usize>) {
+        let key = (*t.from.state(), t.action);
+
+        self.n_steps += 1;
+        self.last_visit.insert(key, self.n_steps);
+
+        let pairs = &mut self.pairs;
+        let stats = self.statistics.entry(key).or_insert_with(|| {
+            pairs.push(key);
+
+            PairStatistics::default()
+        });
+
+        stats.count += 1;
+
+        match stats.outcomes.iter_mut().find(|o| same_outcome(&o.next, &t.to)) {
+            Some(o) => {
+                o.count += 1;
+                o.reward_sum += t.reward;
+            },
+            None => stats.outcomes.push(Outcome {
+                next: t.to,
+                count: 1,
+                reward_sum: t.reward,
+            }),
+        }
+
+        self.predecessors.entry(*t.to.state()).or_default().insert(key);
+    }
+}
+
+impl TabularModel for StochasticModel {
+    fn n_pairs(&self) -> usize { self.pairs.len() }
+
+    fn sample_pair<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        self.pairs.choose(rng).copied()
+    }
+
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        s: usize,
+        a: usize,
+    ) -> Option<(f64, Observation<usize>)> {
+        self.statistics.get(&(s, a)).and_then(|stats| {
+            let mut r = rng.gen_range(0, stats.count);
+
+            stats.outcomes.iter().find(|o| if r < o.count {
+                true
+            } else {
+                r -= o.count;
+
+                false
+            }).map(|o| (o.reward_sum / o.count as f64, o.next))
+        })
+    }
+
+    fn outcomes(&self, s: usize, a: usize) -> Vec<(f64, f64, Observation<usize>)> {
+        self.statistics.get(&(s, a)).map_or(vec![], |stats| {
+            let z = stats.count as f64;
+
+            stats.outcomes.iter().map(|o| {
+                let n = o.count as f64;
+
+                (n / z, o.reward_sum / n, o.next)
+            }).collect()
+        })
+    }
+
+    fn predecessors(&self, s: usize) -> Vec<(usize, usize)> {
+        self.predecessors.get(&s).map_or(vec![], |preds| preds.iter().copied().collect())
+    }
+
+    fn n_steps_since(&self, s: usize, a: usize) -> Option<u64> {
+        self.last_visit.get(&(s, a)).map(|&k| self.n_steps - k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        domains::{Observation, Transition},
+        models::TabularModel,
+    };
+    use rand::thread_rng;
+    use super::{DeterministicModel, StochasticModel};
+
+    fn transition(s: usize, a: usize, r: f64, ns: Observation<usize>) -> Transition<usize, usize> {
+        Transition { from: Observation::Full(s), action: a, reward: r, to: ns, }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let mut rng = thread_rng();
+        let mut m = DeterministicModel::new();
+
+        assert_eq!(m.n_pairs(), 0);
+        assert!(m.sample_pair(&mut rng).is_none());
+        assert!(m.sample(&mut rng, 0, 0).is_none());
+
+        m.handle_transition(&transition(0, 1, 1.0, Observation::Full(2)));
+        m.handle_transition(&transition(2, 0, -1.0, Observation::Terminal(3)));
+
+        assert_eq!(m.n_pairs(), 2);
+        assert_eq!(m.n_steps_since(0, 1), Some(1));
+        assert_eq!(m.n_steps_since(2, 0), Some(0));
+        assert_eq!(m.n_steps_since(1, 1), None);
+
+        let (r, ns) = m.sample(&mut rng, 2, 0).unwrap();
+
+        assert_eq!(r, -1.0);
+        assert!(ns.is_terminal());
+        assert_eq!(*ns.state(), 3);
+        assert_eq!(m.predecessors(2), vec![(0, 1)]);
+
+        m.handle_transition(&transition(0, 1, 0.5, Observation::Full(4)));
+
+        assert_eq!(m.n_pairs(), 2);
+        assert!(m.predecessors(2).is_empty());
+        assert_eq!(m.predecessors(4), vec![(0, 1)]);
+        assert_eq!(m.outcomes(0, 1).len(), 1);
+        assert_eq!(m.outcomes(0, 1)[0].1, 0.5);
+    }
+
+    #[test]
+    fn test_stochastic() {
+        let mut rng = thread_rng();
+        let mut m = StochasticModel::new();
+
+        m.handle_transition(&transition(0, 0, 1.0, Observation::Full(1)));
+        m.handle_transition(&transition(0, 0, 3.0, Observation::Full(1)));
+        m.handle_transition(&transition(0, 0, 0.0, Observation::Full(2)));
+        m.handle_transition(&transition(0, 0, 0.0, Observation::Terminal(2)));
+
+        assert_eq!(m.n_pairs(), 1);
+        assert_eq!(m.sample_pair(&mut rng), Some((0, 0)));
+
+        let outcomes = m.outcomes(0, 0);
+
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0].0, 0.5);
+        assert_eq!(outcomes[0].1, 2.0);
+        assert_eq!(outcomes[1].0, 0.25);
+        assert!(!outcomes[1].2.is_terminal());
+        assert!(outcomes[2].2.is_terminal());
+
+        let mut preds = m.predecessors(2);
+        preds.dedup();
+
+        assert_eq!(preds, vec![(0, 0)]);
+
+        let mut n1 = 0.0f64;
+        for _ in 0..10000 {
+            let (_, ns) = m.sample(&mut rng, 0, 0).unwrap();
+
+            if *ns.state() == 1 { n1 += 1.0; }
+        }
+
+        assert!((0.5 - n1 / 10000.0).abs() < 0.05);
+    }
+}
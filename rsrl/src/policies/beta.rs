@@ -5,13 +5,13 @@ use crate::{
         StateFunction, DifferentiableStateFunction,
         Parameterised, Weights, WeightsView, WeightsViewMut,
     },
-    policies::{DifferentiablePolicy, Policy},
+    policies::{DifferentiablePolicy, Policy, trigamma},
 };
 use ndarray::{Array2, ArrayView2, Axis};
 use rand::Rng;
 use rstat::{
     Distribution, ContinuousDistribution,
-    core::Modes,
+    core::{Entropy, Modes},
     univariate::{UnivariateMoments, continuous::Beta as BetaDist},
 };
 use std::ops::AddAssign;
@@ -89,6 +89,10 @@ where
     fn probability(&self, input: &S, a: &f64) -> f64 {
         self.dist(input).pdf(*a)
     }
+
+    fn entropy(&self, input: &S) -> f64 {
+        self.dist(input).entropy()
+    }
 }
 
 impl<A: Parameterised, B: Parameterised> Parameterised for Beta<A, B> {
@@ -138,7 +142,7 @@ where
 
         match self.beta.weights_dim() {
             [r, _] if r > 0 => {
-                let grad_beta = grad.slice(s![self.beta.weights_dim()[0].., ..]);
+                let grad_beta = grad.slice(s![self.alpha.weights_dim()[0].., ..]);
                 self.beta.weights_view_mut().add_assign(&grad_beta);
             },
             _ => {},
@@ -156,7 +160,7 @@ where
 
         match self.beta.weights_dim() {
             [r, _] if r > 0 => {
-                let grad_beta = grad.slice(s![self.beta.weights_dim()[0].., ..]);
+                let grad_beta = grad.slice(s![self.alpha.weights_dim()[0].., ..]);
                 self.beta.weights_view_mut().scaled_add(factor, &grad_beta);
             },
             _ => {},
@@ -174,4 +178,71 @@ where
 
         stack![Axis(0), gl_alpha * grad_alpha, gl_beta * grad_beta]
     }
+
+    fn grad_entropy(&self, state: &S) -> Array2<f64> {
+        let alpha = self.compute_alpha(state);
+        let beta = self.compute_beta(state);
+        let tg_apb = (alpha + beta - 2.0) * trigamma(alpha + beta);
+
+        let ge_alpha = tg_apb - (alpha - 1.0) * trigamma(alpha);
+        let ge_beta = tg_apb - (beta - 1.0) * trigamma(beta);
+
+        let grad_alpha: Array2<f64> = self.alpha.grad(state).into();
+        let grad_beta: Array2<f64> = self.beta.grad(state).into();
+
+        stack![Axis(0), ge_alpha * grad_alpha, ge_beta * grad_beta]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+        },
+        policies::{DifferentiablePolicy, Policy},
+    };
+    use super::Beta;
+
+    fn perturb<A: Parameterised, B: Parameterised>(p: &mut Beta<A, B>, i: usize, eps: f64) {
+        let n_alpha = p.alpha.weights_dim()[0];
+
+        if i < n_alpha {
+            p.alpha.weights_view_mut()[(i, 0)] += eps;
+        } else {
+            p.beta.weights_view_mut()[(i - n_alpha, 0)] += eps;
+        }
+    }
+
+    #[test]
+    fn test_grad_entropy() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let mut p = Beta::new(
+            LFA::scalar(basis.clone(), SGD(1.0)),
+            LFA::scalar(basis, SGD(1.0)),
+        );
+        let s = vec![0.5];
+
+        p.alpha.weights_view_mut().assign(&array![[0.5], [1.0]]);
+        p.beta.weights_view_mut().assign(&array![[2.0], [-0.5]]);
+
+        let grad = p.grad_entropy(&s);
+
+        assert_eq!(grad.shape(), &[4, 1]);
+
+        for i in 0..4 {
+            perturb(&mut p, i, 1e-6);
+            let h_plus = p.entropy(&s);
+
+            perturb(&mut p, i, -2e-6);
+            let h_minus = p.entropy(&s);
+
+            perturb(&mut p, i, 1e-6);
+
+            let fd = (h_plus - h_minus) / 2e-6;
+
+            assert!((grad[(i, 0)] - fd).abs() < 1e-5);
+        }
+    }
 }
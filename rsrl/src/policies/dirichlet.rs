@@ -5,7 +5,7 @@ use crate::{
         Weights, WeightsView, WeightsViewMut, Parameterised,
         StateFunction, DifferentiableStateFunction,
     },
-    policies::{DifferentiablePolicy, Policy},
+    policies::{DifferentiablePolicy, Policy, trigamma},
 };
 use ndarray::{Array2, ArrayView2};
 use rand::Rng;
@@ -71,6 +71,17 @@ where
     fn probability(&self, input: &S, a: &Vec<f64>) -> f64 {
         self.dist(input).pdf(a.clone())
     }
+
+    fn entropy(&self, input: &S) -> f64 {
+        let alphas = self.compute_alphas(input);
+        let n = alphas.len() as f64;
+        let sum_alphas: f64 = alphas.iter().sum();
+
+        let ln_beta = alphas.iter().map(|a| a.loggamma()).sum::<f64>() - sum_alphas.loggamma();
+        let digammas: f64 = alphas.iter().map(|a| (a - 1.0) * a.digamma()).sum();
+
+        ln_beta + (sum_alphas - n) * sum_alphas.digamma() - digammas
+    }
 }
 
 impl<S, F> DifferentiablePolicy<S> for Dirichlet<F>
@@ -110,4 +121,94 @@ where
 
         grad_alphas
     }
+
+    fn grad_entropy(&self, state: &S) -> Array2<f64> {
+        let raw = self.alphas.evaluate(state);
+        let alphas = self.compute_alphas(state);
+        let n = alphas.len() as f64;
+        let sum_alphas: f64 = alphas.iter().sum();
+        let tg_sum = (sum_alphas - n) * trigamma(sum_alphas);
+
+        // The entropy is constant in any concentration clamped at MIN_TOL:
+        let ge = raw.into_iter().zip(alphas).map(|(r, a)| if r > 0.0 {
+            tg_sum - (a - 1.0) * trigamma(a)
+        } else {
+            0.0
+        });
+
+        let mut grad_alphas: Array2<f64> = self.alphas.grad(state).into();
+
+        for (mut c, ge) in grad_alphas.gencolumns_mut().into_iter().zip(ge) {
+            c.mul_assign(ge);
+        }
+
+        grad_alphas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fa::{
+            DifferentiableStateFunction, Parameterised, StateFunction,
+            WeightsView, WeightsViewMut,
+        },
+        policies::{DifferentiablePolicy, Policy},
+    };
+    use ndarray::{Array2, Axis};
+    use super::Dirichlet;
+
+    /// Linear map from a state vector to the raw concentrations.
+    struct Linear(Array2<f64>);
+
+    impl StateFunction<Vec<f64>> for Linear {
+        type Output = Vec<f64>;
+
+        fn evaluate(&self, s: &Vec<f64>) -> Vec<f64> {
+            self.0.t().dot(&ndarray::arr1(s)).into_raw_vec()
+        }
+
+        fn update(&mut self, _: &Vec<f64>, _: Vec<f64>) {}
+    }
+
+    impl Parameterised for Linear {
+        fn weights_view(&self) -> WeightsView { self.0.view() }
+
+        fn weights_view_mut(&mut self) -> WeightsViewMut { self.0.view_mut() }
+    }
+
+    impl DifferentiableStateFunction<Vec<f64>> for Linear {
+        type Gradient = Array2<f64>;
+
+        fn grad(&self, s: &Vec<f64>) -> Array2<f64> {
+            let mut grad = Array2::zeros(self.0.dim());
+
+            grad.axis_iter_mut(Axis(1)).for_each(|mut c| c.assign(&ndarray::arr1(s)));
+
+            grad
+        }
+    }
+
+    #[test]
+    fn test_grad_entropy() {
+        let mut p = Dirichlet::new(Linear(array![[0.5, 2.0, -1.0], [1.0, -0.5, 0.5]]));
+        let s = vec![1.0, 0.5];
+
+        let grad = p.grad_entropy(&s);
+        let [n_rows, n_cols] = p.weights_dim();
+
+        for i in 0..n_rows {
+            for j in 0..n_cols {
+                p.weights_view_mut()[(i, j)] += 1e-6;
+                let h_plus = p.entropy(&s);
+
+                p.weights_view_mut()[(i, j)] -= 2e-6;
+                let h_minus = p.entropy(&s);
+
+                p.weights_view_mut()[(i, j)] += 1e-6;
+
+                assert!((grad[(i, j)] - (h_plus - h_minus) / 2e-6).abs() < 1e-5);
+            }
+        }
+    }
 }
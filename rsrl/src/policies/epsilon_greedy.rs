@@ -1,6 +1,6 @@
 use crate::{
     fa::EnumerableStateActionFunction,
-    policies::{EnumerablePolicy, Greedy, Policy, Random}
+    policies::{entropy_of, EnumerablePolicy, Greedy, Policy, Random}
 };
 use rand::Rng;
 
@@ -44,6 +44,8 @@ impl<S, Q: EnumerableStateActionFunction<S>> Policy<S> for EpsilonGreedy<Q> {
     fn mpa(&self, s: &S) -> usize { self.greedy.mpa(s) }
 
     fn probability(&self, s: &S, a: &usize) -> f64 { self.probabilities(s)[*a] }
+
+    fn entropy(&self, s: &S) -> f64 { entropy_of(&self.probabilities(s)) }
 }
 
 impl<S, Q: EnumerableStateActionFunction<S>> EnumerablePolicy<S> for EpsilonGreedy<Q> {
@@ -5,12 +5,13 @@ use crate::{
         StateFunction, DifferentiableStateFunction,
         Parameterised, Weights, WeightsView, WeightsViewMut,
     },
-    policies::{DifferentiablePolicy, Policy},
+    policies::{DifferentiablePolicy, Policy, trigamma},
 };
 use ndarray::{Array2, ArrayView2, Axis};
 use rand::Rng;
 use rstat::{
     Distribution, ContinuousDistribution,
+    core::Entropy,
     univariate::{UnivariateMoments, continuous::Gamma as GammaDist},
 };
 use std::ops::AddAssign;
@@ -77,6 +78,10 @@ where
     fn probability(&self, input: &S, a: &f64) -> f64 {
         self.dist(input).pdf(*a)
     }
+
+    fn entropy(&self, input: &S) -> f64 {
+        self.dist(input).entropy()
+    }
 }
 
 impl<S, A, T> DifferentiablePolicy<S> for Gamma<A, T>
@@ -105,7 +110,7 @@ where
 
         match self.theta.weights_dim() {
             [r, _] if r > 0 => {
-                let grad_theta = grad.slice(s![self.theta.weights_dim()[0].., ..]);
+                let grad_theta = grad.slice(s![self.alpha.weights_dim()[0].., ..]);
                 self.theta.weights_view_mut().add_assign(&grad_theta);
             },
             _ => {},
@@ -123,7 +128,7 @@ where
 
         match self.theta.weights_dim() {
             [r, _] if r > 0 => {
-                let grad_theta = grad.slice(s![self.theta.weights_dim()[0].., ..]);
+                let grad_theta = grad.slice(s![self.alpha.weights_dim()[0].., ..]);
                 self.theta.weights_view_mut().scaled_add(factor, &grad_theta);
             },
             _ => {},
@@ -141,6 +146,25 @@ where
 
         stack![Axis(0), gl_alpha * grad_alpha, gl_theta * grad_theta]
     }
+
+    fn grad_entropy(&self, state: &S) -> Array2<f64> {
+        let val_alpha = self.alpha.evaluate(state);
+        let val_theta = self.theta.evaluate(state);
+
+        // The entropy is constant wherever a parameter is clamped at MIN_TOL,
+        // and theta enters the distribution as a rate:
+        let ge_alpha = if val_alpha > MIN_TOL {
+            1.0 + (1.0 - val_alpha) * trigamma(val_alpha)
+        } else {
+            0.0
+        };
+        let ge_theta = if val_theta > MIN_TOL { -1.0 / val_theta } else { 0.0 };
+
+        let grad_alpha: Array2<f64> = self.alpha.grad(state).into();
+        let grad_theta: Array2<f64> = self.theta.grad(state).into();
+
+        stack![Axis(0), ge_alpha * grad_alpha, ge_theta * grad_theta]
+    }
 }
 
 impl<A: Parameterised, T: Parameterised> Parameterised for Gamma<A, T> {
@@ -159,3 +183,54 @@ impl<A: Parameterised, T: Parameterised> Parameterised for Gamma<A, T> {
         [ra + rb, 1]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+        },
+        policies::{DifferentiablePolicy, Policy},
+    };
+    use super::Gamma;
+
+    fn perturb<A: Parameterised, T: Parameterised>(p: &mut Gamma<A, T>, i: usize, eps: f64) {
+        let n_alpha = p.alpha.weights_dim()[0];
+
+        if i < n_alpha {
+            p.alpha.weights_view_mut()[(i, 0)] += eps;
+        } else {
+            p.theta.weights_view_mut()[(i - n_alpha, 0)] += eps;
+        }
+    }
+
+    #[test]
+    fn test_grad_entropy() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let mut p = Gamma::new(
+            LFA::scalar(basis.clone(), SGD(1.0)),
+            LFA::scalar(basis, SGD(1.0)),
+        );
+        let s = vec![0.5];
+
+        p.alpha.weights_view_mut().assign(&array![[1.5], [1.0]]);
+        p.theta.weights_view_mut().assign(&array![[2.0], [-0.5]]);
+
+        let grad = p.grad_entropy(&s);
+
+        assert_eq!(grad.shape(), &[4, 1]);
+
+        for i in 0..4 {
+            perturb(&mut p, i, 1e-6);
+            let h_plus = p.entropy(&s);
+
+            perturb(&mut p, i, -2e-6);
+            let h_minus = p.entropy(&s);
+
+            perturb(&mut p, i, 1e-6);
+
+            assert!((grad[(i, 0)] - (h_plus - h_minus) / 2e-6).abs() < 1e-5);
+        }
+    }
+}
@@ -1,15 +1,25 @@
 use ndarray::{Array1, Array2};
+use ndarray_linalg::Determinant;
 use rstat::{
     Distribution, ContinuousDistribution,
     univariate::continuous::Normal,
     multivariate::continuous::{BivariateNormal, MultivariateNormal},
 };
-use std::fmt::Debug;
+use std::{f64::consts::{E, PI}, fmt::Debug};
+
+/// Return the differential entropy of a Gaussian in `n` dimensions with
+/// covariance log-determinant `ln_det`.
+fn entropy(n: usize, ln_det: f64) -> f64 {
+    0.5 * (n as f64 * (2.0 * PI * E).ln() + ln_det)
+}
 
 pub trait DistBuilder<M: Debug + Clone, S: Debug + Clone> {
     type Distribution: ContinuousDistribution;
 
     fn build(mean: M, stddev: S) -> Self::Distribution;
+
+    /// Return the differential entropy of the distribution that would be built.
+    fn entropy(mean: &M, stddev: &S) -> f64;
 }
 
 pub struct GB;
@@ -24,6 +34,8 @@ impl DistBuilder<f64, f64> for GB {
     fn build(mean: f64, stddev: f64) -> Normal {
         Normal::new(mean, stddev)
     }
+
+    fn entropy(_: &f64, stddev: &f64) -> f64 { entropy(1, 2.0 * stddev.ln()) }
 }
 
 impl DistBuilder<[f64; 2], f64> for GB {
@@ -32,6 +44,8 @@ impl DistBuilder<[f64; 2], f64> for GB {
     fn build(mean: [f64; 2], stddev: f64) -> BivariateNormal {
         BivariateNormal::isotropic(mean, stddev)
     }
+
+    fn entropy(_: &[f64; 2], stddev: &f64) -> f64 { entropy(2, 4.0 * stddev.ln()) }
 }
 
 impl DistBuilder<[f64; 2], [f64; 2]> for GB {
@@ -40,6 +54,10 @@ impl DistBuilder<[f64; 2], [f64; 2]> for GB {
     fn build(mean: [f64; 2], stddev: [f64; 2]) -> BivariateNormal {
         BivariateNormal::independent(mean, stddev)
     }
+
+    fn entropy(_: &[f64; 2], stddev: &[f64; 2]) -> f64 {
+        entropy(2, 2.0 * (stddev[0].ln() + stddev[1].ln()))
+    }
 }
 
 impl DistBuilder<Array1<f64>, f64> for GB {
@@ -48,6 +66,10 @@ impl DistBuilder<Array1<f64>, f64> for GB {
     fn build(mean: Array1<f64>, stddev: f64) -> MultivariateNormal {
         MultivariateNormal::isotropic(mean, stddev)
     }
+
+    fn entropy(mean: &Array1<f64>, stddev: &f64) -> f64 {
+        entropy(mean.len(), 2.0 * mean.len() as f64 * stddev.ln())
+    }
 }
 
 impl DistBuilder<Array1<f64>, Array1<f64>> for GB {
@@ -59,6 +81,11 @@ impl DistBuilder<Array1<f64>, Array1<f64>> for GB {
 
         MultivariateNormal::new(mean, sigma)
     }
+
+    fn entropy(mean: &Array1<f64>, stddev: &Array1<f64>) -> f64 {
+        // The diagonal is taken as the covariance, as in `build`:
+        entropy(mean.len(), stddev.fold(0.0, |acc, s| acc + s.ln()))
+    }
 }
 
 impl DistBuilder<Array1<f64>, Array2<f64>> for GB {
@@ -67,4 +94,10 @@ impl DistBuilder<Array1<f64>, Array2<f64>> for GB {
     fn build(mean: Array1<f64>, sigma: Array2<f64>) -> MultivariateNormal {
         MultivariateNormal::new(mean, sigma)
     }
+
+    fn entropy(mean: &Array1<f64>, sigma: &Array2<f64>) -> f64 {
+        let (_, ln_det) = sigma.sln_det().expect("Covariance matrix must be non-singular.");
+
+        entropy(mean.len(), ln_det)
+    }
 }
@@ -73,6 +73,10 @@ where
     where Self::Action: Clone {
         GB::build(self.compute_mean(input), self.compute_stddev(input)).pdf(a.clone())
     }
+
+    fn entropy(&self, input: &I) -> f64 {
+        GB::entropy(&self.compute_mean(input), &self.compute_stddev(input))
+    }
 }
 
 impl<I, M, S> DifferentiablePolicy<I> for Gaussian<M, S>
@@ -153,6 +157,18 @@ where
             stack![Axis(0), gl_mean, gl_stddev]
         }
     }
+
+    fn grad_entropy(&self, input: &I) -> Array2<f64> {
+        // The entropy of a Gaussian does not depend on its mean:
+        let ge_mean = Array2::zeros(self.mean.weights_dim());
+        let ge_stddev = self.stddev.grad_entropy(input);
+
+        if ge_stddev.len() == 0 {
+            ge_mean
+        } else {
+            stack![Axis(0), ge_mean, ge_stddev]
+        }
+    }
 }
 
 impl<M, S> Parameterised for Gaussian<M, S>
@@ -182,3 +198,60 @@ where
         [rm + rs, cm.max(cs)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+        },
+        policies::{DifferentiablePolicy, Policy},
+    };
+    use std::f64::consts::{E, PI};
+    use super::{Gaussian, mean, stddev};
+
+    #[test]
+    fn test_entropy() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let p = Gaussian::new(
+            mean::Scalar(LFA::scalar(basis, SGD(1.0))),
+            stddev::Constant(0.5),
+        );
+
+        assert!((p.entropy(&vec![1.0]) - 0.5 * (2.0 * PI * E * 0.25).ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_grad_entropy() {
+        let basis = Polynomial::new(1, 1).with_constant();
+        let mut p = Gaussian::new(
+            mean::Scalar(LFA::scalar(basis.clone(), SGD(1.0))),
+            stddev::Scalar(LFA::scalar(basis, SGD(1.0))),
+        );
+        let s = vec![2.0];
+
+        for &w in [0.0, 0.3].iter() {
+            p.stddev.0.weights_view_mut().fill(w);
+
+            let grad = p.grad_entropy(&s);
+
+            assert_eq!(grad.shape(), &[4, 1]);
+
+            // The entropy does not depend on the mean:
+            assert!(grad.slice(s![0..2, ..]).iter().all(|&g| g == 0.0));
+
+            for i in 0..2 {
+                p.stddev.0.weights_view_mut()[(i, 0)] += 1e-6;
+                let h_plus = p.entropy(&s);
+
+                p.stddev.0.weights_view_mut()[(i, 0)] -= 2e-6;
+                let h_minus = p.entropy(&s);
+
+                p.stddev.0.weights_view_mut()[(i, 0)] += 1e-6;
+
+                assert!((grad[(2 + i, 0)] - (h_plus - h_minus) / 2e-6).abs() < 1e-5);
+            }
+        }
+    }
+}
@@ -19,12 +19,22 @@ fn gl_from_mv(a: f64, mean: f64, stddev: f64) -> f64 {
     (diff_sq / stddev / stddev / stddev - 1.0 / stddev)
 }
 
+/// Derivative of `ln sigma` wrt the raw output of the approximator, which
+/// vanishes where the standard deviation is clamped at zero.
+fn dlog_stddev(stddev: f64) -> f64 {
+    if stddev > 0.0 { 1.0 / stddev } else { 0.0 }
+}
+
 pub trait StdDev<I, M>: StateFunction<I> + Parameterised {
     fn stddev(&self, input: &I) -> Self::Output;
 
     fn grad_log(&self, input: &I, a: &M, mean: M) -> Array2<f64>;
 
     fn update_stddev(&mut self, input: &I, a: &M, mean: M, error: f64);
+
+    /// Return the gradient of the entropy of the Gaussian wrt the weights,
+    /// `grad log sigma` summed over the dimensions.
+    fn grad_entropy(&self, input: &I) -> Array2<f64>;
 }
 
 // Constant:
@@ -55,6 +65,8 @@ impl<I, V: Clone, M> StdDev<I, M> for Constant<V> {
     fn grad_log(&self, _: &I, _: &M, _: M) -> Array2<f64> { Array2::default((0, 0)) }
 
     fn update_stddev(&mut self, _: &I, _: &M, _: M, _: f64) {}
+
+    fn grad_entropy(&self, _: &I) -> Array2<f64> { Array2::default((0, 0)) }
 }
 
 // Scalar:
@@ -85,6 +97,10 @@ where F: DifferentiableStateFunction<I, Output = f64> + Parameterised
 
         self.update(input, gl_from_mv(*a, mean, stddev) * error);
     }
+
+    fn grad_entropy(&self, input: &I) -> Array2<f64> {
+        self.0.grad(input).into() * dlog_stddev(self.evaluate(input))
+    }
 }
 
 // Pair:
@@ -136,4 +152,14 @@ where F: DifferentiableStateFunction<I, Output = [f64; 2]> + Parameterised
             ],
         );
     }
+
+    fn grad_entropy(&self, input: &I) -> Array2<f64> {
+        let mut g = self.0.grad(input).into();
+        let stddev = self.evaluate(input);
+
+        g.column_mut(0).mul_assign(dlog_stddev(stddev[0]));
+        g.column_mut(1).mul_assign(dlog_stddev(stddev[1]));
+
+        g
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     fa::EnumerableStateActionFunction,
-    policies::{EnumerablePolicy, Policy},
+    policies::{entropy_of, EnumerablePolicy, Policy},
     utils::{argmaxima},
 };
 
@@ -22,6 +22,8 @@ impl<S, Q: EnumerableStateActionFunction<S>> Policy<S> for Greedy<Q> {
     }
 
     fn probability(&self, s: &S, a: &usize) -> f64 { self.probabilities(s)[*a] }
+
+    fn entropy(&self, s: &S) -> f64 { entropy_of(&self.probabilities(s)) }
 }
 
 impl<S, Q: EnumerableStateActionFunction<S>> EnumerablePolicy<S> for Greedy<Q> {
@@ -5,6 +5,32 @@ use crate::{
 use ndarray::{Array2, ArrayView2, Axis};
 use rand::Rng;
 
+/// Stack the gradients of each policy in the pair column-wise, padding the
+/// shorter of the two with zero rows.
+fn stack_grads(mut g_0: Array2<f64>, mut g_1: Array2<f64>) -> Array2<f64> {
+    let nr_0 = g_0.rows();
+    let nr_1 = g_1.rows();
+
+    fn resize(gl: Array2<f64>, n_rows: usize) -> Array2<f64> {
+        let gl_rows = gl.rows();
+
+        let mut new_gl = unsafe { Array2::uninitialized((n_rows, gl.cols())) };
+
+        new_gl.slice_mut(s![0..gl_rows, ..]).assign(&gl);
+        new_gl.slice_mut(s![gl_rows.., ..]).fill(0.0);
+
+        new_gl
+    }
+
+    if nr_0 > nr_1 {
+        g_1 = resize(g_1, nr_0);
+    } else if nr_0 < nr_1 {
+        g_0 = resize(g_0, nr_1);
+    }
+
+    stack![Axis(1), g_0, g_1]
+}
+
 /// Independent Policy Pair (IPP).
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
@@ -32,6 +58,10 @@ where
     fn probability(&self, s: &S, a: &(P1::Action, P2:: Action)) -> f64 {
         self.0.probability(s, &a.0) * self.1.probability(s, &a.1)
     }
+
+    fn entropy(&self, s: &S) -> f64 {
+        self.0.entropy(s) + self.1.entropy(s)
+    }
 }
 
 impl<S, P1, P2> DifferentiablePolicy<S> for IPP<P1, P2>
@@ -89,30 +119,11 @@ where
     }
 
     fn grad_log(&self, input: &S, a: &Self::Action) -> Array2<f64> {
-        let mut gl_0 = self.0.grad_log(input, &a.0);
-        let nr_0 = gl_0.rows();
-
-        let mut gl_1 = self.1.grad_log(input, &a.1);
-        let nr_1 = gl_1.rows();
-
-        fn resize(gl: Array2<f64>, n_rows: usize) -> Array2<f64> {
-            let gl_rows = gl.rows();
-
-            let mut new_gl = unsafe { Array2::uninitialized((n_rows, gl.cols())) };
-
-            new_gl.slice_mut(s![0..gl_rows, ..]).assign(&gl);
-            new_gl.slice_mut(s![gl_rows.., ..]).fill(0.0);
-
-            new_gl
-        }
-
-        if nr_0 > nr_1 {
-            gl_1 = resize(gl_1, nr_0);
-        } else if nr_0 < nr_1 {
-            gl_0 = resize(gl_0, nr_1);
-        }
+        stack_grads(self.0.grad_log(input, &a.0), self.1.grad_log(input, &a.1))
+    }
 
-        stack![Axis(1), gl_0, gl_1]
+    fn grad_entropy(&self, input: &S) -> Array2<f64> {
+        stack_grads(self.0.grad_entropy(input), self.1.grad_entropy(input))
     }
 }
 
@@ -53,6 +53,30 @@ pub(self) fn sample_probs_with_rng<R: Rng + ?Sized>(rng: &mut R, probabilities:
     }
 }
 
+/// Return the entropy of a discrete distribution, in nats.
+#[inline]
+fn entropy_of(probabilities: &[f64]) -> f64 {
+    -probabilities.iter().filter(|&&p| p > 0.0).fold(0.0, |acc, p| acc + p * p.ln())
+}
+
+/// Return the trigamma function, `d^2/dx^2 ln Gamma(x)`, for `x > 0`.
+///
+/// The argument is shifted above ten by the recurrence `psi'(x) = psi'(x + 1) +
+/// 1 / x^2`, after which the asymptotic series is accurate to within 1e-12.
+fn trigamma(mut x: f64) -> f64 {
+    let mut acc = 0.0;
+
+    while x < 10.0 {
+        acc += 1.0 / (x * x);
+        x += 1.0;
+    }
+
+    let x2 = 1.0 / (x * x);
+
+    acc + 1.0 / x + x2 / 2.0
+        + x2 / x * (1.0 / 6.0 - x2 * (1.0 / 30.0 - x2 * (1.0 / 42.0 - x2 / 30.0)))
+}
+
 /// Policy trait for functions that define a probability distribution over
 /// actions.
 pub trait Policy<S> {
@@ -68,6 +92,10 @@ pub trait Policy<S> {
 
     /// Return the probability of selecting an action for a given `state`.
     fn probability(&self, state: &S, a: &Self::Action) -> f64;
+
+    /// Return the entropy of the policy distribution for a given `state`. For
+    /// continuous policies this is the differential entropy.
+    fn entropy(&self, _: &S) -> f64 { unimplemented!("Entropy is not defined for this policy.") }
 }
 
 /// Trait for policies that are defined on an enumerable action space.
@@ -105,4 +133,9 @@ pub trait DifferentiablePolicy<S>: Policy<S> + Parameterised {
 
         self.grad(state, a) * p
     }
+
+    /// Compute the gradient of the policy entropy wrt the policy weights.
+    fn grad_entropy(&self, _: &S) -> Array2<f64> {
+        unimplemented!("The entropy gradient is not defined for this policy.")
+    }
 }
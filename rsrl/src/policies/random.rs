@@ -17,6 +17,8 @@ impl<S> Policy<S> for Random {
     }
 
     fn probability(&self, _: &S, _: &usize) -> f64 { 1.0 / self.0 as f64 }
+
+    fn entropy(&self, _: &S) -> f64 { (self.0 as f64).ln() }
 }
 
 impl<S> EnumerablePolicy<S> for Random {
@@ -14,6 +14,8 @@ impl<S, T: Policy<S>> Policy<S> for Shared<T> {
     fn probability(&self, state: &S, a: &Self::Action) -> f64 {
         self.borrow().probability(state, a)
     }
+
+    fn entropy(&self, state: &S) -> f64 { self.borrow().entropy(state) }
 }
 
 impl<S, T: EnumerablePolicy<S>> EnumerablePolicy<S> for Shared<T> {
@@ -42,4 +44,6 @@ impl<S, T: DifferentiablePolicy<S>> DifferentiablePolicy<S> for Shared<T> {
     fn grad_log(&self, state: &S, a: &Self::Action) -> Array2<f64> {
         self.borrow().grad_log(state, a)
     }
+
+    fn grad_entropy(&self, state: &S) -> Array2<f64> { self.borrow().grad_entropy(state) }
 }
@@ -4,6 +4,7 @@ use crate::{
         DifferentiableStateActionFunction, EnumerableStateActionFunction,
     },
     policies::{
+        entropy_of,
         sample_probs_with_rng,
         DifferentiablePolicy,
         EnumerablePolicy,
@@ -39,7 +40,8 @@ fn softmax_stable<C: FromIterator<f64>>(values: &[f64], tau: f64) -> C {
 
 pub type Gibbs<F> = Softmax<F>;
 
-#[derive(Parameterised)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Parameterised)]
 pub struct Softmax<F> {
     #[weights] fa: F,
 
@@ -89,6 +91,8 @@ impl<S, F: EnumerableStateActionFunction<S>> Policy<S> for Softmax<F> {
     }
 
     fn probability(&self, s: &S, a: &usize) -> f64 { self.probabilities(s)[*a] }
+
+    fn entropy(&self, s: &S) -> f64 { entropy_of(&self.probabilities(s)) }
 }
 
 impl<S, F: EnumerableStateActionFunction<S>> EnumerablePolicy<S> for Softmax<F> {
@@ -110,6 +114,24 @@ where
     }
 
     fn grad_log(&self, input: &S, a: &usize) -> Array2<f64> { self.gl_matrix(input, a) }
+
+    fn grad_entropy(&self, input: &S) -> Array2<f64> {
+        let ps = self.probabilities(input);
+        let entropy = entropy_of(&ps);
+
+        // dH/dQ_b = -p_b (ln p_b + H) / tau:
+        let mut jac = Array2::zeros(self.weights_dim());
+
+        for (ref col, p) in (0..self.n_actions()).zip(ps) {
+            if p > 0.0 {
+                let scale = -p * (p.ln() + entropy) / self.tau;
+
+                jac.scaled_add(scale, &self.fa.grad(input, col).into());
+            }
+        }
+
+        jac
+    }
 }
 
 #[cfg(test)]
@@ -188,4 +210,42 @@ mod tests {
         assert!(ps[0] < ps[1]);
         assert!(ps[2] < ps[1]);
     }
+
+    #[test]
+    fn test_entropy() {
+        let p = Softmax::new(MockQ::new_shared(None), 1.0);
+        let ps = [1.0 / (1.0 + E), E / (1.0 + E)];
+
+        let h = p.entropy(&vec![0.0, 1.0]);
+
+        assert!((h + ps[0] * ps[0].ln() + ps[1] * ps[1].ln()).abs() < 1e-6);
+        assert!((p.entropy(&vec![1.0, 1.0]) - 2.0f64.ln()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_grad_entropy() {
+        let fa = LFA::vector(Polynomial::new(1, 1).with_constant(), SGD(1.0), 3);
+        let mut p = Softmax::new(fa, 0.5);
+        let s = vec![0.5];
+
+        p.update(&s, &0, 1.0);
+        p.update(&s, &2, -0.5);
+
+        let grad = p.grad_entropy(&s);
+        let [n_rows, n_cols] = p.weights_dim();
+
+        for i in 0..n_rows {
+            for j in 0..n_cols {
+                p.weights_view_mut()[(i, j)] += 1e-6;
+                let h_plus = p.entropy(&s);
+
+                p.weights_view_mut()[(i, j)] -= 2e-6;
+                let h_minus = p.entropy(&s);
+
+                p.weights_view_mut()[(i, j)] += 1e-6;
+
+                assert!((grad[(i, j)] - (h_plus - h_minus) / 2e-6).abs() < 1e-5);
+            }
+        }
+    }
 }
@@ -0,0 +1,163 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::basis::kernels::Kernel,
+    },
+    prediction::{ValuePredictor, PosteriorValuePredictor},
+};
+use ndarray::{Array1, Array2, Axis};
+
+/// Gaussian-process temporal-difference learning with a sparse dictionary.
+///
+/// Places a Gaussian process prior with covariance `kernel` on the value
+/// function and treats each reward as a noisy observation of `V(s) - gamma
+/// V(s')`, with noise variance `noise`. The posterior is represented through
+/// the values at a dictionary of states, which grows only when a state is not
+/// approximately linearly dependent on the dictionary in feature space, that
+/// is, when its residual prior variance exceeds `threshold`. The weights are
+/// the posterior mean of the values at the dictionary states.
+///
+/// # References
+/// - Engel, Y., Mannor, S., Meir, R. (2003). Bayes meets Bellman: The
+///   Gaussian process approach to temporal difference learning. In
+///   Proceedings of the 20th International Conference on Machine Learning,
+///   pp. 154–161.
+/// - Engel, Y., Mannor, S., Meir, R. (2004). The kernel recursive
+///   least-squares algorithm. IEEE Transactions on Signal Processing, 52(8),
+///   2275–2285.
+pub struct GPTD<S, K> {
+    pub kernel: K,
+
+    pub gamma: f64,
+    pub noise: f64,
+    pub threshold: f64,
+
+    dictionary: Vec<S>,
+    k_inv: Array2<f64>,
+    mean: Weights,
+    cov: Array2<f64>,
+}
+
+impl<S, K> GPTD<S, K> {
+    pub fn new(kernel: K, gamma: f64, noise: f64, threshold: f64) -> Self {
+        GPTD {
+            kernel,
+
+            gamma,
+            noise,
+            threshold,
+
+            dictionary: vec![],
+            k_inv: Array2::zeros((0, 0)),
+            mean: Weights::zeros((0, 1)),
+            cov: Array2::zeros((0, 0)),
+        }
+    }
+
+    /// Return the states retained in the dictionary.
+    pub fn dictionary(&self) -> &[S] { &self.dictionary }
+}
+
+impl<S: Clone, K: Kernel<S>> GPTD<S, K> {
+    /// Return the coefficients of the projection of `s` onto the dictionary,
+    /// and the residual prior variance left unexplained by the projection.
+    fn project(&self, s: &S) -> (Array1<f64>, f64) {
+        let k: Array1<f64> = self.dictionary.iter().map(|d| self.kernel.kernel(d, s)).collect();
+        let a = self.k_inv.dot(&k);
+        let residual = self.kernel.kernel(s, s) - k.dot(&a);
+
+        (a, residual.max(0.0))
+    }
+
+    /// Add `s` to the dictionary if it is not approximately linearly dependent
+    /// on the existing entries.
+    fn expand(&mut self, s: &S) {
+        let (a, delta) = self.project(s);
+
+        if delta <= self.threshold { return; }
+
+        let m = self.dictionary.len();
+        let cov_a = self.cov.dot(&a);
+
+        // Block-wise inverse of the extended kernel matrix:
+        let mut k_inv = Array2::zeros((m + 1, m + 1));
+        let a_col = a.view().insert_axis(Axis(1));
+
+        k_inv.slice_mut(s![..m, ..m]).assign(&(&self.k_inv + &(a_col.dot(&a_col.t()) / delta)));
+        k_inv.slice_mut(s![..m, m]).assign(&(-&a / delta));
+        k_inv.slice_mut(s![m, ..m]).assign(&(-&a / delta));
+        k_inv[(m, m)] = 1.0 / delta;
+
+        // The new value is conditionally Gaussian given those of the dictionary:
+        let mut mean = Weights::zeros((m + 1, 1));
+
+        mean.slice_mut(s![..m, ..]).assign(&self.mean);
+        mean[(m, 0)] = a.dot(&self.mean.column(0));
+
+        let mut cov = Array2::zeros((m + 1, m + 1));
+
+        cov.slice_mut(s![..m, ..m]).assign(&self.cov);
+        cov.slice_mut(s![..m, m]).assign(&cov_a);
+        cov.slice_mut(s![m, ..m]).assign(&cov_a);
+        cov[(m, m)] = a.dot(&cov_a) + delta;
+
+        self.dictionary.push(s.clone());
+        self.k_inv = k_inv;
+        self.mean = mean;
+        self.cov = cov;
+    }
+}
+
+impl<S: Clone, K: Kernel<S>> Parameterised for GPTD<S, K> {
+    fn weights_view(&self) -> WeightsView<'_> { self.mean.view() }
+
+    fn weights_view_mut(&mut self) -> WeightsViewMut<'_> { self.mean.view_mut() }
+}
+
+impl<S: Clone, K: Kernel<S>, A> OnlineLearner<S, A> for GPTD<S, K> {
+    fn handle_transition(&mut self, t: &Transition<S, A>) {
+        let s = t.from.state();
+
+        self.expand(s);
+
+        if !t.terminated() { self.expand(t.to.state()); }
+
+        let (a, delta) = self.project(s);
+
+        // Projection residuals are absorbed into the observation noise:
+        let (h, noise) = if t.terminated() {
+            (a, self.noise + delta)
+        } else {
+            let (na, n_delta) = self.project(t.to.state());
+            let gamma = self.gamma;
+
+            (a - gamma * na, self.noise + delta + gamma * gamma * n_delta)
+        };
+
+        let cov_h = self.cov.dot(&h);
+        let innovation = t.reward - h.dot(&self.mean.column(0));
+        let innovation_var = h.dot(&cov_h) + noise;
+        let gain = cov_h / innovation_var;
+
+        self.mean.column_mut(0).scaled_add(innovation, &gain);
+        let gain_col = gain.view().insert_axis(Axis(1));
+
+        self.cov -= &(gain_col.dot(&gain_col.t()) * innovation_var);
+    }
+}
+
+impl<S: Clone, K: Kernel<S>> ValuePredictor<S> for GPTD<S, K> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.project(s).0.dot(&self.mean.column(0))
+    }
+}
+
+impl<S: Clone, K: Kernel<S>> PosteriorValuePredictor<S> for GPTD<S, K> {
+    fn predict_v_variance(&self, s: &S) -> f64 {
+        let (a, delta) = self.project(s);
+
+        a.dot(&self.cov.dot(&a)) + delta
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::LinearStateFunction,
+    },
+    prediction::{ValuePredictor, PosteriorValuePredictor},
+};
+use ndarray::{Array1, Array2, Axis};
+
+/// Kalman temporal-difference learning.
+///
+/// Treats the weights as the hidden state of a random walk, with variance
+/// `process_noise` per step, observed through the rewards, `r = (phi -
+/// gamma phi')^T theta + n` with `Var[n] = observation_noise`. Each
+/// transition is then a Kalman filter update, and the weight covariance, `P`,
+/// yields the posterior variance of the value estimates, `phi^T P phi`.
+///
+/// With linear function approximation the unscented transform of the original
+/// algorithm is exact, and reduces to the closed-form update used here.
+///
+/// # References
+/// - Geist, M., Pietquin, O. (2010). Kalman temporal differences. Journal of
+///   Artificial Intelligence Research, 39, 483–532.
+#[derive(Parameterised)]
+pub struct KTD<F> {
+    #[weights] pub fa_theta: F,
+
+    pub gamma: f64,
+    pub process_noise: f64,
+    pub observation_noise: f64,
+
+    p_mat: Array2<f64>,
+}
+
+impl<F: Parameterised> KTD<F> {
+    pub fn new(
+        fa_theta: F,
+        gamma: f64,
+        prior_variance: f64,
+        process_noise: f64,
+        observation_noise: f64,
+    ) -> Self {
+        let n_features = fa_theta.weights_dim()[0];
+
+        KTD {
+            fa_theta,
+
+            gamma,
+            process_noise,
+            observation_noise,
+
+            p_mat: Array2::eye(n_features) * prior_variance,
+        }
+    }
+
+    /// Return the posterior covariance of the weights.
+    pub fn covariance(&self) -> &Array2<f64> { &self.p_mat }
+}
+
+impl<S, A, F: LinearStateFunction<S>> OnlineLearner<S, A> for KTD<F> {
+    fn handle_transition(&mut self, t: &Transition<S, A>) {
+        let phi_s = self.fa_theta.features(t.from.state());
+        let v = self.fa_theta.evaluate_features(&phi_s);
+
+        let (td_error, h) = if t.terminated() {
+            (t.reward - v, phi_s.expanded())
+        } else {
+            let phi_ns = self.fa_theta.features(t.to.state());
+            let nv = self.fa_theta.evaluate_features(&phi_ns);
+
+            (t.reward + self.gamma * nv - v, phi_s.expanded() - self.gamma * phi_ns.expanded())
+        };
+
+        // Prediction step:
+        let process_noise = self.process_noise;
+
+        self.p_mat.diag_mut().mapv_inplace(|x| x + process_noise);
+
+        // Correction step, with the TD error as the innovation:
+        let p_h = self.p_mat.dot(&h);
+        let innovation_var = h.dot(&p_h) + self.observation_noise;
+        let gain: Array1<f64> = &p_h / innovation_var;
+
+        self.fa_theta.weights_view_mut().column_mut(0).scaled_add(td_error, &gain);
+        self.p_mat -= &gain.insert_axis(Axis(1)).dot(&p_h.insert_axis(Axis(0)));
+    }
+}
+
+impl<S, F: LinearStateFunction<S>> ValuePredictor<S> for KTD<F> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
+
+impl<S, F: LinearStateFunction<S>> PosteriorValuePredictor<S> for KTD<F> {
+    fn predict_v_variance(&self, s: &S) -> f64 {
+        let phi = self.fa_theta.features(s).expanded();
+
+        phi.dot(&self.p_mat.dot(&phi))
+    }
+}
@@ -0,0 +1,74 @@
+//! Bayesian prediction agents that maintain a posterior over value functions.
+import_all!(ktd);
+import_all!(gptd);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        domains::{Observation, Transition},
+        fa::linear::{LFA, basis::{Polynomial, kernels::ExpQuad}, optim::SGD},
+        prediction::PosteriorValuePredictor,
+    };
+    use super::{GPTD, KTD};
+
+    const GAMMA: f64 = 0.9;
+
+    fn one_hot(i: usize) -> Vec<f64> {
+        let mut s = vec![0.0; 3];
+        s[i] = 1.0;
+        s
+    }
+
+    /// Deterministic three-state chain with a unit reward on termination.
+    fn chain() -> Vec<Transition<Vec<f64>, ()>> {
+        (0..3).map(|i| Transition {
+            from: Observation::Full(one_hot(i)),
+            action: (),
+            reward: if i == 2 { 1.0 } else { 0.0 },
+            to: if i == 2 {
+                Observation::Terminal(one_hot(0))
+            } else {
+                Observation::Full(one_hot(i + 1))
+            },
+        }).collect()
+    }
+
+    fn check<L>(agent: &mut L, tol: f64)
+    where
+        L: OnlineLearner<Vec<f64>, ()> + PosteriorValuePredictor<Vec<f64>>,
+    {
+        let prior: Vec<f64> = (0..3).map(|i| agent.predict_v_variance(&one_hot(i))).collect();
+
+        for _ in 0..100 {
+            for t in chain() {
+                agent.handle_transition(&t);
+            }
+        }
+
+        assert!((agent.predict_v(&one_hot(0)) - GAMMA * GAMMA).abs() < tol);
+        assert!((agent.predict_v(&one_hot(1)) - GAMMA).abs() < tol);
+        assert!((agent.predict_v(&one_hot(2)) - 1.0).abs() < tol);
+
+        // The data shrinks the uncertainty about every state on the chain:
+        for (i, p) in prior.into_iter().enumerate() {
+            assert!(agent.predict_v_variance(&one_hot(i)) < 0.1 * p);
+        }
+    }
+
+    #[test]
+    fn test_ktd() {
+        let fa = LFA::scalar(Polynomial::new(3, 1), SGD(1.0));
+
+        check(&mut KTD::new(fa, GAMMA, 10.0, 0.0, 0.01), 1e-2);
+    }
+
+    #[test]
+    fn test_gptd() {
+        let mut agent = GPTD::new(ExpQuad::new(10.0, vec![0.1; 3]), GAMMA, 0.01, 1e-6);
+
+        check(&mut agent, 1e-2);
+
+        assert_eq!(agent.dictionary().len(), 3);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        StateActionFunction,
+        linear::{LFAGradient, LinearStateActionFunction},
+    },
+    linalg::MatrixLike,
+    policies::EnumerablePolicy,
+    prediction::{ValuePredictor, ActionValuePredictor, dot_weights},
+    traces::{Accumulating, Trace},
+};
+use std::ops::Deref;
+
+/// ABQ(zeta) for off-policy action-value prediction without importance
+/// sampling ratios in the eligibility trace.
+///
+/// The bootstrapping parameter is action-dependent, `lambda(s, a) = nu(zeta,
+/// s, a) mu(a|s)`, and chosen such that the product of `lambda` and the
+/// importance sampling ratio never exceeds one. The single parameter `zeta`
+/// interpolates between one-step GQ (`zeta = 0`) and the largest admissible
+/// amount of bootstrapping (`zeta = 1`). The auxiliary weights, `w`, are
+/// trained with step size `beta`.
+///
+/// # References
+/// - Mahmood, A. R., Yu, H., Sutton, R. S. (2017). Multi-step off-policy
+///   learning without importance sampling ratios. arXiv:1702.03006.
+#[derive(Parameterised)]
+pub struct ABQ<Q, T, B> {
+    #[weights] pub fa_q: Q,
+    pub w: Weights,
+
+    pub target: T,
+    pub behaviour: B,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub zeta: f64,
+
+    trace: Accumulating<LFAGradient>,
+}
+
+impl<Q: Parameterised, T, B> ABQ<Q, T, B> {
+    pub fn new(
+        fa_q: Q,
+        target: T,
+        behaviour: B,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+        zeta: f64,
+    ) -> Self {
+        let dim = fa_q.weights_dim();
+
+        ABQ {
+            fa_q,
+            w: Weights::zeros((dim[0], dim[1])),
+
+            target,
+            behaviour,
+
+            alpha,
+            beta,
+            gamma,
+            zeta,
+
+            trace: Accumulating::zeros(dim),
+        }
+    }
+
+    /// Return the action-dependent bootstrapping parameters, `lambda(s, a)`,
+    /// given the target and behaviour action probabilities in a state.
+    fn lambdas(&self, pi: &[f64], mu: &[f64]) -> Vec<f64> {
+        let max_of = |f: fn(f64, f64) -> f64| pi.iter().zip(mu.iter())
+            .fold(0.0f64, |acc, (&p, &m)| acc.max(f(p, m)));
+
+        let psi_0 = 1.0 / max_of(f64::max);
+        let psi = if self.zeta > 0.5 {
+            let psi_max = 1.0 / max_of(f64::min);
+
+            2.0 * self.zeta * psi_0 + (2.0 * self.zeta - 1.0) * (psi_max - 2.0 * psi_0)
+        } else {
+            2.0 * self.zeta * psi_0
+        };
+
+        pi.iter().zip(mu.iter()).map(|(&p, &m)| {
+            if m > 0.0 { psi.min(1.0 / p.max(m)) * m } else { 0.0 }
+        }).collect()
+    }
+
+    /// Return the expected action-value under the `target` policy, together
+    /// with the expected feature vector weighted by `1 - lambda(s, a)`.
+    fn expectation<S>(&self, s: &S) -> (LFAGradient, f64)
+    where
+        Q: LinearStateActionFunction<S, usize>,
+        T: EnumerablePolicy<S>,
+        B: EnumerablePolicy<S>,
+    {
+        let pi = self.target.probabilities(s);
+        let lambdas = self.lambdas(&pi, &self.behaviour.probabilities(s));
+
+        let mut phi_cut = LFAGradient::zeros(self.fa_q.weights_dim());
+        let mut v = 0.0;
+
+        for (a, (p, l)) in pi.into_iter().zip(lambdas).enumerate() {
+            phi_cut += &(self.fa_q.grad(s, &a) * (p * (1.0 - l)));
+            v += p * self.fa_q.evaluate_features(&self.fa_q.features(s, &a), &a);
+        }
+
+        (phi_cut, v)
+    }
+}
+
+impl<S, Q, T, B> OnlineLearner<S, usize> for ABQ<Q, T, B>
+where
+    Q: LinearStateActionFunction<S, usize>,
+    T: EnumerablePolicy<S>,
+    B: EnumerablePolicy<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+        let grad_s = self.fa_q.grad(s, &t.action);
+
+        // Product of lambda and the importance sampling ratio, nu(zeta, s, a) pi(a|s):
+        let decay = {
+            let pi = self.target.probabilities(s);
+            let mu = self.behaviour.probabilities(s);
+            let lambda = self.lambdas(&pi, &mu)[t.action];
+
+            self.gamma * lambda * pi[t.action] / mu[t.action]
+        };
+
+        self.trace.combine_inplace(&grad_s, move |x, y| decay * x + y);
+
+        let qsa = self.fa_q.evaluate_features(&self.fa_q.features(s, &t.action), &t.action);
+        let w_s = dot_weights(&self.w, &grad_s);
+        let w_z = dot_weights(&self.w, self.trace.deref());
+
+        if t.terminated() {
+            let td_error = t.reward - qsa;
+
+            self.fa_q.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+
+            self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+            grad_s.scaled_addto(-self.beta * w_s, &mut self.w);
+
+            self.trace.reset();
+        } else {
+            let (phi_cut, nv) = self.expectation(t.to.state());
+            let td_error = t.reward + self.gamma * nv - qsa;
+
+            self.fa_q.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+            self.fa_q.update_grad_scaled(&phi_cut, -self.alpha * self.gamma * w_z);
+
+            self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+            grad_s.scaled_addto(-self.beta * w_s, &mut self.w);
+        }
+    }
+
+    fn handle_terminal(&mut self) { self.trace.reset(); }
+}
+
+impl<S, Q, T, B> ValuePredictor<S> for ABQ<Q, T, B>
+where
+    Q: StateActionFunction<S, usize, Output = f64>,
+    T: EnumerablePolicy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.target.probabilities(s).into_iter().enumerate().fold(0.0, |acc, (a, p)| {
+            acc + p * self.fa_q.evaluate(s, &a)
+        })
+    }
+}
+
+impl<S, Q, T, B> ActionValuePredictor<S, usize> for ABQ<Q, T, B>
+where
+    Q: StateActionFunction<S, usize, Output = f64>,
+    T: EnumerablePolicy<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 { self.fa_q.evaluate(s, a) }
+}
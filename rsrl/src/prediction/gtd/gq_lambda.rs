@@ -0,0 +1,153 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        StateActionFunction,
+        linear::{LFAGradient, LinearStateActionFunction},
+    },
+    linalg::MatrixLike,
+    policies::{Policy, EnumerablePolicy},
+    prediction::{ValuePredictor, ActionValuePredictor, dot_weights},
+    traces::{Accumulating, Trace},
+};
+use std::ops::Deref;
+
+/// GQ(lambda) for off-policy action-value prediction.
+///
+/// Bootstraps from the expected action-value under the `target` policy, with
+/// an eligibility trace that is cut by the importance sampling ratio of each
+/// action taken by the `behaviour` policy. The auxiliary weights, `w`, are
+/// trained with step size `beta`.
+///
+/// # References
+/// - Maei, H. R., Sutton, R. S. (2010). GQ(lambda): A general gradient
+///   algorithm for temporal-difference prediction learning with eligibility
+///   traces. In Proceedings of the 3rd Conference on Artificial General
+///   Intelligence, pp. 91–96.
+#[derive(Parameterised)]
+pub struct GQLambda<Q, T, B> {
+    #[weights] pub fa_q: Q,
+    pub w: Weights,
+
+    pub target: T,
+    pub behaviour: B,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Accumulating<LFAGradient>,
+}
+
+impl<Q: Parameterised, T, B> GQLambda<Q, T, B> {
+    pub fn new(
+        fa_q: Q,
+        target: T,
+        behaviour: B,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+        lambda: f64,
+    ) -> Self {
+        let dim = fa_q.weights_dim();
+
+        GQLambda {
+            fa_q,
+            w: Weights::zeros((dim[0], dim[1])),
+
+            target,
+            behaviour,
+
+            alpha,
+            beta,
+            gamma,
+            lambda,
+
+            trace: Accumulating::zeros(dim),
+        }
+    }
+
+    /// Return the expected feature vector and action-value under the `target`
+    /// policy.
+    fn expectation<S>(&self, s: &S) -> (LFAGradient, f64)
+    where
+        Q: LinearStateActionFunction<S, usize>,
+        T: EnumerablePolicy<S>,
+    {
+        self.target.probabilities(s).into_iter().enumerate().fold(
+            (LFAGradient::zeros(self.fa_q.weights_dim()), 0.0),
+            |(mut phi_bar, v), (a, p)| {
+                phi_bar += &(self.fa_q.grad(s, &a) * p);
+
+                (phi_bar, v + p * self.fa_q.evaluate_features(&self.fa_q.features(s, &a), &a))
+            },
+        )
+    }
+}
+
+impl<S, Q, T, B> OnlineLearner<S, usize> for GQLambda<Q, T, B>
+where
+    Q: LinearStateActionFunction<S, usize>,
+    T: EnumerablePolicy<S>,
+    B: Policy<S, Action = usize>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, usize>) {
+        let s = t.from.state();
+        let grad_s = self.fa_q.grad(s, &t.action);
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let decay = self.gamma * self.lambda * rho;
+
+        self.trace.combine_inplace(&grad_s, move |x, y| decay * x + y);
+
+        let qsa = self.fa_q.evaluate_features(&self.fa_q.features(s, &t.action), &t.action);
+        let w_s = dot_weights(&self.w, &grad_s);
+        let w_z = dot_weights(&self.w, self.trace.deref());
+
+        if t.terminated() {
+            let td_error = t.reward - qsa;
+
+            self.fa_q.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+
+            self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+            grad_s.scaled_addto(-self.beta * w_s, &mut self.w);
+
+            self.trace.reset();
+        } else {
+            let (phi_bar, nv) = self.expectation(t.to.state());
+            let td_error = t.reward + self.gamma * nv - qsa;
+
+            self.fa_q.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+            self.fa_q.update_grad_scaled(
+                &phi_bar, -self.alpha * self.gamma * (1.0 - self.lambda) * w_z,
+            );
+
+            self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+            grad_s.scaled_addto(-self.beta * w_s, &mut self.w);
+        }
+    }
+
+    fn handle_terminal(&mut self) { self.trace.reset(); }
+}
+
+impl<S, Q, T, B> ValuePredictor<S> for GQLambda<Q, T, B>
+where
+    Q: StateActionFunction<S, usize, Output = f64>,
+    T: EnumerablePolicy<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 {
+        self.target.probabilities(s).into_iter().enumerate().fold(0.0, |acc, (a, p)| {
+            acc + p * self.fa_q.evaluate(s, &a)
+        })
+    }
+}
+
+impl<S, Q, T, B> ActionValuePredictor<S, usize> for GQLambda<Q, T, B>
+where
+    Q: StateActionFunction<S, usize, Output = f64>,
+    T: EnumerablePolicy<S>,
+{
+    fn predict_q(&self, s: &S, a: &usize) -> f64 { self.fa_q.evaluate(s, a) }
+}
@@ -1,145 +1,125 @@
 use crate::{
-    core::*,
+    OnlineLearner,
     domains::Transition,
-    fa::{VFunction, DifferentiableVFunction, Parameterised},
-    geometry::{Space, MatrixView, MatrixViewMut},
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
     policies::Policy,
+    prediction::{ValuePredictor, dot_weights},
+    traces::{Accumulating, Trace},
 };
-
+use std::ops::Deref;
+
+/// GTD(lambda) with importance sampling for off-policy prediction.
+///
+/// The eligibility trace is weighted by the importance sampling ratios between
+/// the `target` and `behaviour` policies. The auxiliary weights, `w`, estimate
+/// the expected TD error given the features and are trained with step size
+/// `beta`.
+///
+/// # References
+/// - Maei, H. R. (2011). Gradient temporal-difference learning algorithms.
+///   PhD thesis, University of Alberta.
+/// - Maei, H. R., Sutton, R. S. (2010). GQ(lambda): A general gradient
+///   algorithm for temporal-difference prediction learning with eligibility
+///   traces. In Proceedings of the 3rd Conference on Artificial General
+///   Intelligence, pp. 91–96.
 #[derive(Parameterised)]
 pub struct GTDLambdaIS<F, T, B> {
     #[weights] pub fa_theta: F,
-    pub fa_w: F,
-
-    trace: Trace,
+    pub w: Weights,
 
-    target: T,
-    behaviour: B,
+    pub target: T,
+    pub behaviour: B,
 
     pub alpha: f64,
     pub beta: f64,
     pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Accumulating<LFAGradient>,
 }
 
 impl<F: Parameterised, T, B> GTDLambdaIS<F, T, B> {
     pub fn new(
         fa_theta: F,
-        fa_w: F,
-        trace: Trace,
         target: T,
         behaviour: B,
         alpha: f64,
         beta: f64,
         gamma: f64,
+        lambda: f64,
     ) -> Self {
-        if fa_theta.weights_dim() != fa_w.weights_dim() {
-            panic!("fa_theta and fa_w must be equivalent function approximators.")
-        }
+        let dim = fa_theta.weights_dim();
 
         GTDLambdaIS {
             fa_theta,
-            fa_w,
-
-            trace,
+            w: Weights::zeros((dim[0], dim[1])),
 
             target,
             behaviour,
 
-            alpha: alpha.into(),
-            beta: beta.into(),
-            gamma: gamma.into(),
-        }
-    }
-}
-
-impl<F, T: Algorithm, B: Algorithm> Algorithm for GTDLambdaIS<F, T, B> {
-    fn handle_terminal(&mut self) {
-        self.target.handle_terminal();
-        self.behaviour.handle_terminal();
-
-        self.alpha = self.alpha.step();
-        self.beta = self.alpha.step();
-        self.gamma = self.gamma.step();
-        self.trace.lambda = self.trace.lambda.step();
-
-        self.trace.decay(0.0);
-    }
-}
-
-impl<F, T, B> GTDLambdaIS<F, T, B> {
-    fn update_trace<S>(&mut self, t: &Transition<S, T::Action>, phi: &Vector<f64>) -> Features
-    where
-        F: VFunction<S>,
-        T: Policy<S>,
-        B: Policy<S, Action = T::Action>,
-    {
-        let s = t.from.state();
-
-        let decay_rate = self.trace.lambda.value() * self.gamma.value();
-        let is_ratio = {
-            let pi = self.target.probability(s, &t.action);
-            let b = self.behaviour.probability(s, &t.action);
-
-            pi / b
-        };
-
-        self.trace.decay(decay_rate);
-        self.trace.update(phi);
-        self.trace.decay(is_ratio);
+            alpha,
+            beta,
+            gamma,
+            lambda,
 
-        Features::Dense(self.trace.get())
+            trace: Accumulating::zeros(dim),
+        }
     }
 }
 
 impl<S, F, T, B> OnlineLearner<S, T::Action> for GTDLambdaIS<F, T, B>
 where
-    F: VFunction<S>,
+    F: LinearStateFunction<S>,
     T: Policy<S>,
     B: Policy<S, Action = T::Action>,
 {
     fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
-        let phi_s = self.fa_theta.embed(t.from.state());
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let decay = self.gamma * self.lambda;
 
-        let v_s = self.fa_theta.evaluate(&phi_s).unwrap();
-        let w_s = self.fa_w.evaluate(&phi_s).unwrap();
+        self.trace.combine_inplace(&grad_s, move |x, y| rho * (decay * x + y));
 
-        let phi_s = phi_s.expanded(self.fa_theta.weights_dim().0);
-        let z = self.update_trace(t, &phi_s);
-        let phi_s = Features::Dense(phi_s);
+        let v = self.fa_theta.evaluate_features(phi_s);
+        let w_s = dot_weights(&self.w, &grad_s);
+        let w_z = dot_weights(&self.w, self.trace.deref());
 
         if t.terminated() {
-            let residual = t.reward - v_s;
+            let td_error = t.reward - v;
 
-            self.fa_theta.update(&z, self.alpha * residual).ok();
-            self.fa_w.update(&z, self.beta * residual).ok();
-            self.fa_w.update(&phi_s, self.beta * -w_s).ok();
-        } else {
-            let phi_ns = self.fa_theta.embed(t.to.state());
-            let v_ns = self.fa_theta.evaluate(&phi_ns).unwrap();
-            let w_z = self.fa_w.evaluate(&z).unwrap();
+            self.fa_theta.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+
+            self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+            grad_s.scaled_addto(-self.beta * w_s, &mut self.w);
 
-            let residual = t.reward + self.gamma * v_ns - v_s;
+            self.trace.reset();
+        } else {
+            let grad_ns = self.fa_theta.grad(t.to.state());
+            let nv = self.fa_theta.evaluate_features(grad_ns.features(&0).unwrap());
+            let td_error = t.reward + self.gamma * nv - v;
 
-            self.fa_theta.update(&z, self.alpha * residual).ok();
-            self.fa_theta.update(
-                &phi_s,
-                self.alpha * self.gamma * (self.trace.lambda - 1.0) * w_z
-            ).ok();
+            self.fa_theta.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+            self.fa_theta.update_grad_scaled(
+                &grad_ns, -self.alpha * self.gamma * (1.0 - self.lambda) * w_z,
+            );
 
-            self.fa_w.update(&z, self.beta * residual).ok();
-            self.fa_w.update(&phi_s, self.beta * -w_s).ok();
+            self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+            grad_s.scaled_addto(-self.beta * w_s, &mut self.w);
         }
     }
+
+    fn handle_terminal(&mut self) { self.trace.reset(); }
 }
 
-impl<S, F: VFunction<S>, T, B> ValuePredictor<S> for GTDLambdaIS<F, T, B> {
+impl<S, F: LinearStateFunction<S>, T, B> ValuePredictor<S> for GTDLambdaIS<F, T, B> {
     fn predict_v(&self, s: &S) -> f64 {
-        self.fa_theta.evaluate(&self.fa_theta.embed(s)).unwrap()
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
     }
 }
-
-impl<S, F, T, B> ActionValuePredictor<S, T::Action> for GTDLambdaIS<F, T, B>
-where
-    F: VFunction<S>,
-    T: Policy<S>,
-{}
@@ -1,11 +1,139 @@
 import_all!(tdc);
 import_all!(gtd2);
-// import_all!(gtd_lambda_is);
+import_all!(gtd_lambda_is);
+import_all!(gq_lambda);
+import_all!(abq);
+import_all!(gtd2_lambda_mp);
+import_all!(tdc_lambda_mp);
 
 // TODO:
-// ABQ(lambda) - https://arxiv.org/pdf/1702.03006.pdf
-// GQ(lambda) - http://agi-conf.org/2010/wp-content/uploads/2009/06/paper_21.pdf
-// GTD(lambda) - https://era.library.ualberta.ca/files/8s45q967t/Hamid_Maei_PhDThesis.pdf
 // True online GTD(lambda) - http://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.487.2451&rep=rep1&type=pdf
-// GTD2(lambda)-MP - https://arxiv.org/pdf/1602.08771.pdf
-// TDC(lambda)-MP - https://arxiv.org/pdf/1602.08771.pdf
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        fa::linear::{LFA, basis::Polynomial, optim::SGD},
+        policies::Random,
+        prediction::{
+            ActionValuePredictor,
+            mocking::{Biased, GAMMA, check_values, train},
+        },
+    };
+    use super::{ABQ, GQLambda, GTDLambdaIS, GTD2LambdaMP, TDCLambdaMP};
+
+    /// Check the action-values of the target policy that selects action 0
+    /// with probability `p`, having followed a uniform behaviour policy.
+    fn check_action_values<L>(agent: &mut L, p: f64)
+    where
+        L: OnlineLearner<Vec<f64>, usize> + ActionValuePredictor<Vec<f64>, usize>,
+    {
+        train(agent, 5000);
+
+        let s0 = vec![1.0, 0.0];
+        let s1 = vec![0.0, 1.0];
+
+        assert!((agent.predict_q(&s1, &0) - 1.0).abs() < 0.05);
+        assert!(agent.predict_q(&s1, &1).abs() < 0.05);
+        assert!((agent.predict_q(&s0, &0) - p * GAMMA).abs() < 0.05);
+        assert!((agent.predict_q(&s0, &1) - p * GAMMA).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_gq_lambda() {
+        let mut agent = GQLambda::new(
+            LFA::vector(Polynomial::new(2, 1), SGD(1.0), 2),
+            Random::new(2), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_action_values(&mut agent, 0.5);
+    }
+
+    #[test]
+    fn test_gq_lambda_off_policy() {
+        let mut agent = GQLambda::new(
+            LFA::vector(Polynomial::new(2, 1), SGD(1.0), 2),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_action_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_abq() {
+        let mut agent = ABQ::new(
+            LFA::vector(Polynomial::new(2, 1), SGD(1.0), 2),
+            Random::new(2), Random::new(2),
+            0.01, 0.01, GAMMA, 1.0,
+        );
+
+        check_action_values(&mut agent, 0.5);
+    }
+
+    #[test]
+    fn test_abq_off_policy() {
+        let mut agent = ABQ::new(
+            LFA::vector(Polynomial::new(2, 1), SGD(1.0), 2),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 1.0,
+        );
+
+        check_action_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_gtd_lambda_is_off_policy() {
+        let mut agent = GTDLambdaIS::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_gtd2_lambda_mp() {
+        let mut agent = GTD2LambdaMP::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Random::new(2), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.5);
+    }
+
+    #[test]
+    fn test_gtd2_lambda_mp_off_policy() {
+        let mut agent = GTD2LambdaMP::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_tdc_lambda_mp() {
+        let mut agent = TDCLambdaMP::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Random::new(2), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.5);
+    }
+    #[test]
+    fn test_tdc_lambda_mp_off_policy() {
+        let mut agent = TDCLambdaMP::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.9);
+    }
+}
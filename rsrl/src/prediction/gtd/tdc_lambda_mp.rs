@@ -0,0 +1,143 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
+    policies::Policy,
+    prediction::{ValuePredictor, dot_weights},
+    traces::{Accumulating, Trace},
+};
+use std::ops::Deref;
+
+/// TDC(lambda) with mirror-prox updates for off-policy prediction.
+///
+/// Each transition is used twice in an extragradient scheme: the gradients at
+/// the current weights define an intermediate point, and the gradients at
+/// that point are applied to the current weights. The auxiliary weights, `w`,
+/// are trained with step size `beta`.
+///
+/// # References
+/// - Liu, B., Liu, J., Ghavamzadeh, M., Mahadevan, S., Petrik, M. (2015).
+///   Finite-sample analysis of proximal gradient TD algorithms. In
+///   Proceedings of the 31st Conference on Uncertainty in Artificial
+///   Intelligence, pp. 504–513.
+/// - White, A., White, M. (2016). Investigating practical linear temporal
+///   difference learning. In Proceedings of the 15th International Conference
+///   on Autonomous Agents and Multiagent Systems, pp. 494–502.
+#[derive(Parameterised)]
+pub struct TDCLambdaMP<F, T, B> {
+    #[weights] pub fa_theta: F,
+    pub w: Weights,
+
+    pub target: T,
+    pub behaviour: B,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Accumulating<LFAGradient>,
+}
+
+impl<F: Parameterised, T, B> TDCLambdaMP<F, T, B> {
+    pub fn new(
+        fa_theta: F,
+        target: T,
+        behaviour: B,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+        lambda: f64,
+    ) -> Self {
+        let dim = fa_theta.weights_dim();
+
+        TDCLambdaMP {
+            fa_theta,
+            w: Weights::zeros((dim[0], dim[1])),
+
+            target,
+            behaviour,
+
+            alpha,
+            beta,
+            gamma,
+            lambda,
+
+            trace: Accumulating::zeros(dim),
+        }
+    }
+}
+
+impl<S, F, T, B> OnlineLearner<S, T::Action> for TDCLambdaMP<F, T, B>
+where
+    F: LinearStateFunction<S>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let decay = self.gamma * self.lambda;
+
+        self.trace.combine_inplace(&grad_s, move |x, y| rho * (decay * x + y));
+
+        let grad_ns = if t.terminated() { None } else { Some(self.fa_theta.grad(t.to.state())) };
+        let nv = grad_ns.as_ref().map_or(0.0, |g| {
+            self.fa_theta.evaluate_features(g.features(&0).unwrap())
+        });
+
+        let td_error = t.reward + self.gamma * nv - self.fa_theta.evaluate_features(phi_s);
+        let grad_diff = match grad_ns {
+            Some(ref g) => {
+                let gamma = self.gamma;
+
+                grad_s.clone().combine(g, move |x, y| x - gamma * y)
+            },
+            None => grad_s.clone(),
+        };
+        let c = self.gamma * (1.0 - self.lambda);
+
+        // Extragradient step to the intermediate point:
+        let w_z = dot_weights(&self.w, self.trace.deref());
+        let td_error_mid = {
+            let z_diff: f64 = self.trace.deref().dot(&grad_diff).values().sum();
+            let ns_diff: f64 = grad_ns.as_ref().map_or(0.0, |g| g.dot(&grad_diff).values().sum());
+
+            td_error - self.alpha * (td_error * z_diff - c * w_z * ns_diff)
+        };
+
+        let mut w_mid = self.w.clone();
+
+        self.trace.scaled_addto(self.beta * td_error, &mut w_mid);
+        grad_s.scaled_addto(-self.beta * dot_weights(&self.w, &grad_s), &mut w_mid);
+
+        // Update from the current point using gradients at the intermediate point:
+        self.fa_theta.update_grad_scaled(self.trace.deref(), self.alpha * td_error_mid);
+
+        if let Some(ref g) = grad_ns {
+            self.fa_theta.update_grad_scaled(
+                g, -self.alpha * c * dot_weights(&w_mid, self.trace.deref()),
+            );
+        }
+
+        self.trace.scaled_addto(self.beta * td_error_mid, &mut self.w);
+        grad_s.scaled_addto(-self.beta * dot_weights(&w_mid, &grad_s), &mut self.w);
+
+        if t.terminated() { self.trace.reset(); }
+    }
+
+    fn handle_terminal(&mut self) { self.trace.reset(); }
+}
+
+impl<S, F: LinearStateFunction<S>, T, B> ValuePredictor<S> for TDCLambdaMP<F, T, B> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
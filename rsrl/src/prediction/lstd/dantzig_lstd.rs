@@ -0,0 +1,127 @@
+use crate::{
+    BatchLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::LinearStateFunction,
+    },
+    prediction::ValuePredictor,
+};
+use ndarray::Array1;
+use super::{SolveError, SparseMatrix, accumulate};
+
+/// Dantzig-selector LSTD.
+///
+/// Finds the weights of least L1 norm for which every component of the LSTD
+/// residual, `b - A theta`, is at most `beta` in magnitude. The resulting
+/// linear program is solved by a linearised alternating direction method of
+/// multipliers, warm-started from the current weights, until both the primal
+/// and dual residuals fall below `tol`.
+///
+/// # References
+/// - Geist, M., Scherrer, B., Lazaric, A., Ghavamzadeh, M. (2012). A Dantzig
+///   selector approach to temporal difference learning. In Proceedings of the
+///   29th International Conference on Machine Learning, pp. 1399–1406.
+#[derive(Parameterised)]
+pub struct DantzigLSTD<F> {
+    #[weights] pub fa_theta: F,
+
+    pub gamma: f64,
+    pub beta: f64,
+
+    pub tol: f64,
+    pub max_iterations: usize,
+
+    a: SparseMatrix,
+    b: Array1<f64>,
+
+    last_error: Option<SolveError>,
+}
+
+impl<F: Parameterised> DantzigLSTD<F> {
+    pub fn new(fa_theta: F, gamma: f64, beta: f64) -> Self {
+        let dim = fa_theta.weights_dim();
+
+        DantzigLSTD {
+            fa_theta,
+
+            gamma,
+            beta,
+
+            tol: 1e-8,
+            max_iterations: 100_000,
+
+            a: SparseMatrix::zeros(dim[0]),
+            b: Array1::zeros(dim[0]),
+
+            last_error: None,
+        }
+    }
+
+    /// Return the error raised by the last solve performed in `handle_batch`,
+    /// if it failed.
+    pub fn last_error(&self) -> Option<&SolveError> { self.last_error.as_ref() }
+
+    /// Solve for the weights, which are left unchanged if this fails.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        let beta = self.beta;
+        let norm_sq = self.a.frobenius_sq();
+
+        if norm_sq <= 0.0 {
+            return if self.b.iter().all(|v| v.abs() <= beta) {
+                self.fa_theta.weights_view_mut().fill(0.0);
+
+                Ok(())
+            } else {
+                Err(SolveError::Singular)
+            };
+        }
+
+        // Step size bounded by the inverse squared norm of A:
+        let mu = 1.0 / norm_sq;
+
+        let mut theta = self.fa_theta.weights_view().column(0).to_owned();
+        let mut z = (self.a.dot(&theta) - &self.b).mapv(|v| v.max(-beta).min(beta));
+        let mut u = Array1::<f64>::zeros(theta.len());
+
+        for _ in 0..self.max_iterations {
+            let r = self.a.dot(&theta) - &self.b - &z + &u;
+
+            theta.scaled_add(-mu, &self.a.t_dot(&r));
+            theta.mapv_inplace(|v| v.signum() * (v.abs() - mu).max(0.0));
+
+            let residual = self.a.dot(&theta) - &self.b;
+            let z_new = (&residual + &u).mapv(|v| v.max(-beta).min(beta));
+
+            let primal = (&residual - &z_new).fold(0.0f64, |acc, v| acc.max(v.abs()));
+            let dual = (&z_new - &z).fold(0.0f64, |acc, v| acc.max(v.abs()));
+
+            u += &(residual - &z_new);
+            z = z_new;
+
+            if primal < self.tol && dual < self.tol {
+                self.fa_theta.weights_view_mut().column_mut(0).assign(&theta);
+
+                return Ok(());
+            }
+        }
+
+        Err(SolveError::NotConverged)
+    }
+}
+
+impl<S, A, F: LinearStateFunction<S>> BatchLearner<S, A> for DantzigLSTD<F> {
+    fn handle_batch(&mut self, ts: &[Transition<S, A>]) {
+        for t in ts {
+            accumulate(&self.fa_theta, self.gamma, &mut self.a, &mut self.b, t);
+        }
+
+        self.last_error = self.solve().err();
+    }
+}
+
+impl<S, F: LinearStateFunction<S>> ValuePredictor<S> for DantzigLSTD<F> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
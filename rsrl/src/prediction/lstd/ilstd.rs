@@ -9,8 +9,20 @@ use crate::{
     prediction::ValuePredictor,
     utils::argmaxima,
 };
-use ndarray::{Array1, Array2, Axis};
-
+use ndarray::Array1;
+use super::{SparseMatrix, active, difference};
+
+/// Incremental least-squares temporal-difference learning.
+///
+/// Accumulates `A` sparsely and tracks the residual `mu = b - A theta`
+/// incrementally, so each transition costs `O(k^2)` for `k` active features.
+/// The weights are then improved by `n_updates` greedy coordinate steps on the
+/// largest component of `mu`, each touching only one sparse column of `A`.
+///
+/// # References
+/// - Geramifard, A., Bowling, M., Sutton, R. S. (2006). Incremental
+///   least-squares temporal difference learning. In Proceedings of the 21st
+///   National Conference on Artificial Intelligence, pp. 356–361.
 #[allow(non_camel_case_types)]
 #[derive(Parameterised)]
 pub struct iLSTD<F> {
@@ -20,7 +32,7 @@ pub struct iLSTD<F> {
     pub gamma: f64,
     pub n_updates: usize,
 
-    a: Array2<f64>,
+    a: SparseMatrix,
     mu: Array1<f64>,
 }
 
@@ -35,7 +47,7 @@ impl<F: Parameterised> iLSTD<F> {
             gamma,
             n_updates,
 
-            a: Array2::eye(dim[0]),
+            a: SparseMatrix::eye(dim[0], 1.0),
             mu: Array1::zeros(dim[0]),
         }
     }
@@ -53,7 +65,10 @@ impl<F: Parameterised> iLSTD<F> {
                     let update = self.alpha * self.mu.uget(j);
 
                     *w.uget_mut((j, 0)) += update;
-                    self.mu.scaled_add(-update, &self.a.column(j));
+
+                    for (&i, &v) in self.a.column(j) {
+                        *self.mu.uget_mut(i) -= update * v;
+                    }
                 }
             }
         }
@@ -67,32 +82,26 @@ where
     fn handle_transition(&mut self, t: &Transition<S, A>) {
         let (s, ns) = t.states();
 
-        // (D x 1)
-        let phi_s = self.fa_theta.features(s).expanded();
-
-        self.mu.scaled_add(t.reward, &phi_s);
-
-        if t.terminated() {
-            let phi_s = phi_s.insert_axis(Axis(1));
-
-            // (D x D)
-            let delta_a = phi_s.dot(&phi_s.t());
-
-            self.a += &delta_a;
-            self.mu -= &delta_a.dot(&self.fa_theta.weights_view()).column(0);
+        let phi_s = active(&self.fa_theta.features(s));
+        let pd = if t.terminated() {
+            phi_s.clone()
         } else {
-            let phi_ns = self.fa_theta.features(ns).expanded();
-
-            // (1 x D)
-            let pd = ((-self.gamma * phi_ns) + &phi_s).insert_axis(Axis(0));
+            difference(&phi_s, &active(&self.fa_theta.features(ns)), self.gamma)
+        };
 
-            // (D x D)
-            let delta_a = phi_s.view().insert_axis(Axis(1)).dot(&pd);
+        // mu <- mu + r phi - phi (phi - gamma phi')^T theta:
+        let pd_theta = {
+            let w = self.fa_theta.weights_view();
 
-            self.a += &delta_a;
-            self.mu -= &delta_a.dot(&self.fa_theta.weights_view()).column(0);
+            pd.iter().fold(0.0, |acc, &(i, x)| acc + x * w[(i, 0)])
         };
 
+        for &(i, x) in phi_s.iter() {
+            self.mu[i] += (t.reward - pd_theta) * x;
+        }
+
+        self.a.add_outer(1.0, &phi_s, &pd);
+
         self.solve();
     }
 }
@@ -0,0 +1,100 @@
+use crate::{
+    BatchLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::LinearStateFunction,
+    },
+    prediction::ValuePredictor,
+};
+use ndarray::Array1;
+use super::{SolveError, SparseMatrix, accumulate, solve_system};
+
+/// L2-regularised least-squares temporal-difference learning.
+///
+/// Solves the ridge-penalised system `(A + eta I) theta = b`, which is well
+/// posed for any `eta > 0`, even with more features than samples. As in
+/// `LSTD`, `A` is stored sparsely and the system is solved iteratively,
+/// warm-started from the current weights, with a dense fallback.
+///
+/// # References
+/// - Kolter, J. Z., Ng, A. Y. (2009). Regularization and feature selection in
+///   least-squares temporal difference learning. In Proceedings of the 26th
+///   International Conference on Machine Learning, pp. 521–528.
+/// - Hoffman, M. W., Lazaric, A., Ghavamzadeh, M., Munos, R. (2012).
+///   Regularized least squares temporal difference learning with nested ℓ2
+///   and ℓ1 penalization. In Recent Advances in Reinforcement Learning, pp.
+///   102–114.
+#[derive(Parameterised)]
+pub struct L2LSTD<F> {
+    #[weights] pub fa_theta: F,
+
+    pub gamma: f64,
+    pub eta: f64,
+
+    pub tol: f64,
+    pub max_iterations: usize,
+
+    a: SparseMatrix,
+    b: Array1<f64>,
+
+    last_error: Option<SolveError>,
+}
+
+impl<F: Parameterised> L2LSTD<F> {
+    pub fn new(fa_theta: F, gamma: f64, eta: f64) -> Self {
+        let dim = fa_theta.weights_dim();
+
+        L2LSTD {
+            fa_theta,
+
+            gamma,
+            eta,
+
+            tol: 1e-10,
+            max_iterations: 10_000,
+
+            a: SparseMatrix::zeros(dim[0]),
+            b: Array1::zeros(dim[0]),
+
+            last_error: None,
+        }
+    }
+
+    /// Return the error raised by the last solve performed in `handle_batch`,
+    /// if it failed.
+    pub fn last_error(&self) -> Option<&SolveError> { self.last_error.as_ref() }
+
+    /// Solve for the weights, which are left unchanged if this fails.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        // Features that were never observed leave a column of zeros:
+        if self.eta == 0.0 && self.a.has_empty_column() {
+            return Err(SolveError::Singular);
+        }
+
+        let theta = self.fa_theta.weights_view().column(0).to_owned();
+        let theta = solve_system(
+            &self.a, self.eta, &self.b, theta, self.tol, self.max_iterations,
+        )?;
+
+        self.fa_theta.weights_view_mut().column_mut(0).assign(&theta);
+
+        Ok(())
+    }
+}
+
+impl<S, A, F: LinearStateFunction<S>> BatchLearner<S, A> for L2LSTD<F> {
+    fn handle_batch(&mut self, ts: &[Transition<S, A>]) {
+        for t in ts {
+            accumulate(&self.fa_theta, self.gamma, &mut self.a, &mut self.b, t);
+        }
+
+        self.last_error = self.solve().err();
+    }
+}
+
+impl<S, F: LinearStateFunction<S>> ValuePredictor<S> for L2LSTD<F> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
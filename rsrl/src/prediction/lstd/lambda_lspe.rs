@@ -7,12 +7,24 @@ use crate::{
         linear::LinearStateFunction,
     },
     prediction::ValuePredictor,
-    utils::pinv,
 };
-use ndarray::{Array1, Array2, Axis};
-use ndarray_linalg::Solve;
+use ndarray::Array1;
 use std::ops::MulAssign;
-
+use super::{SolveError, SparseMatrix, active, solve_system};
+
+/// Least-squares policy evaluation, LSPE(lambda).
+///
+/// Each batch is regressed onto its lambda-returns, which are computed from
+/// the current weights, and the weights are moved a step `alpha` towards the
+/// solution. The statistics are accumulated sparsely and the regression is
+/// solved iteratively, with a dense fallback. If it cannot be solved, the
+/// weights are left unchanged and the statistics carried over to the next
+/// batch; the error is then available from `last_error`.
+///
+/// # References
+/// - Nedić, A., Bertsekas, D. P. (2003). Least squares policy evaluation
+///   algorithms with linear function approximation. Discrete Event Dynamic
+///   Systems, 13(1–2), 79–110.
 #[derive(Parameterised)]
 pub struct LambdaLSPE<F> {
     #[weights] pub fa_theta: F,
@@ -21,9 +33,14 @@ pub struct LambdaLSPE<F> {
     pub gamma: f64,
     pub lambda: f64,
 
-    a: Array2<f64>,
+    pub tol: f64,
+    pub max_iterations: usize,
+
+    a: SparseMatrix,
     b: Array1<f64>,
     delta: f64,
+
+    last_error: Option<SolveError>,
 }
 
 impl<F: Parameterised> LambdaLSPE<F> {
@@ -37,28 +54,38 @@ impl<F: Parameterised> LambdaLSPE<F> {
             gamma,
             lambda,
 
-            a: Array2::eye(dim[0]) * 1e-6,
+            tol: 1e-10,
+            max_iterations: 10_000,
+
+            a: SparseMatrix::eye(dim[0], 1e-6),
             b: Array1::zeros(dim[0]),
             delta: 0.0,
+
+            last_error: None,
         }
     }
 }
 
 impl<F: Parameterised> LambdaLSPE<F> {
-    fn solve(&mut self) {
-        // First try the clean approach otherwise solve via SVD:
-        if let Ok(theta) = self.a.solve(&self.b).or_else(|_| {
-            pinv(&self.a).map(|ainv| ainv.dot(&self.b))
-        }) {
-            let mut w = self.fa_theta.weights_view_mut();
-
-            w.mul_assign(1.0 - self.alpha);
-            w.scaled_add(self.alpha, &theta);
-
-            self.a.fill(0.0);
-            self.b.fill(0.0);
-            self.delta = 0.0;
-        }
+    /// Return the error raised by the last solve performed in `handle_batch`,
+    /// if it failed.
+    pub fn last_error(&self) -> Option<&SolveError> { self.last_error.as_ref() }
+
+    /// Move the weights towards the regression solution and clear the
+    /// statistics, leaving both unchanged if the regression cannot be solved.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        let theta = self.fa_theta.weights_view().column(0).to_owned();
+        let theta = solve_system(&self.a, 0.0, &self.b, theta, self.tol, self.max_iterations)?;
+        let mut w = self.fa_theta.weights_view_mut();
+
+        w.mul_assign(1.0 - self.alpha);
+        w.column_mut(0).scaled_add(self.alpha, &theta);
+
+        self.a = SparseMatrix::zeros(self.b.len());
+        self.b.fill(0.0);
+        self.delta = 0.0;
+
+        Ok(())
     }
 }
 
@@ -67,36 +94,38 @@ where
     F: LinearStateFunction<S, Output = f64>,
 {
     fn handle_batch(&mut self, batch: &[Transition<S, A>]) {
-        batch.into_iter().rev().for_each(|ref t| {
+        for t in batch.iter().rev() {
             let (s, ns) = t.states();
             let phi_s = self.fa_theta.features(s);
 
             self.delta *= self.gamma * self.lambda;
 
-            if t.terminated() {
-                let phi_s = phi_s.expanded();
+            let target = if t.terminated() {
+                let target = self.delta + t.reward;
 
-                self.b.scaled_add(self.delta + t.reward, &phi_s);
-                self.a +=
-                    &phi_s.view().insert_axis(Axis(1))
-                    .dot(&(phi_s.view().insert_axis(Axis(0))));
                 self.delta = 0.0;
+
+                target
             } else {
                 let theta_s = self.fa_theta.evaluate_features(&phi_s);
-                let phi_s = phi_s.expanded();
-
                 let theta_ns = self.fa_theta.evaluate(ns);
                 let residual = t.reward + self.gamma * theta_ns - theta_s;
 
                 self.delta += residual;
-                self.b.scaled_add(theta_s + self.delta, &phi_s);
-                self.a +=
-                    &phi_s.view().insert_axis(Axis(1))
-                    .dot(&(phi_s.view().insert_axis(Axis(0))));
+
+                theta_s + self.delta
             };
-        });
 
-        self.solve();
+            let phi_s = active(&phi_s);
+
+            for &(i, v) in phi_s.iter() {
+                self.b[i] += target * v;
+            }
+
+            self.a.add_outer(1.0, &phi_s, &phi_s);
+        }
+
+        self.last_error = self.solve().err();
     }
 }
 
@@ -0,0 +1,161 @@
+use crate::{
+    BatchLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::LinearStateFunction,
+    },
+    prediction::ValuePredictor,
+};
+use ndarray::{Array1, Array2};
+use ndarray_linalg::Solve;
+use super::{SolveError, SparseMatrix, accumulate};
+
+/// Least-angle regression TD (LARS-TD) for L1-regularised LSTD.
+///
+/// Computes the LASSO-TD fixed point, `theta = argmin_w ||R + gamma Phi'
+/// theta - Phi w||^2 + beta ||w||_1`, by following the piecewise-linear
+/// homotopy path from `theta = 0` at the largest admissible regularisation
+/// down to `beta`, adding and removing features from the active set as their
+/// correlations with the residual reach the current penalty. Only the
+/// sub-matrix of `A` restricted to the active set is ever formed densely.
+///
+/// # References
+/// - Kolter, J. Z., Ng, A. Y. (2009). Regularization and feature selection in
+///   least-squares temporal difference learning. In Proceedings of the 26th
+///   International Conference on Machine Learning, pp. 521–528.
+#[derive(Parameterised)]
+pub struct LARSTD<F> {
+    #[weights] pub fa_theta: F,
+
+    pub gamma: f64,
+    pub beta: f64,
+
+    a: SparseMatrix,
+    b: Array1<f64>,
+
+    last_error: Option<SolveError>,
+}
+
+enum Event {
+    Add(usize),
+    Remove(usize),
+}
+
+impl<F: Parameterised> LARSTD<F> {
+    pub fn new(fa_theta: F, gamma: f64, beta: f64) -> Self {
+        let dim = fa_theta.weights_dim();
+
+        LARSTD {
+            fa_theta,
+
+            gamma,
+            beta,
+
+            a: SparseMatrix::zeros(dim[0]),
+            b: Array1::zeros(dim[0]),
+
+            last_error: None,
+        }
+    }
+
+    /// Return the error raised by the last solve performed in `handle_batch`,
+    /// if it failed.
+    pub fn last_error(&self) -> Option<&SolveError> { self.last_error.as_ref() }
+
+    /// Solve for the weights, which are left unchanged if this fails.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        let n = self.b.len();
+
+        let mut theta = Array1::<f64>::zeros(n);
+        let mut c = self.b.clone();
+
+        let (i0, mut beta_bar) = c.iter().enumerate().fold((0, 0.0f64), |(j, m), (i, v)| {
+            if v.abs() > m { (i, v.abs()) } else { (j, m) }
+        });
+        let mut active = vec![i0];
+
+        // Each feature enters and leaves the active set a finite number of times:
+        for _ in 0..(10 * n + 10) {
+            if beta_bar <= self.beta {
+                self.fa_theta.weights_view_mut().column_mut(0).assign(&theta);
+
+                return Ok(());
+            }
+
+            // Direction of change in the active weights:
+            let signs: Array1<f64> = active.iter().map(|&i| c[i].signum()).collect();
+            let a_active = Array2::from_shape_fn((active.len(), active.len()), |(i, j)| {
+                self.a.get(active[i], active[j])
+            });
+            let d_active = a_active.solve(&signs).map_err(|_| SolveError::Singular)?;
+
+            if d_active.iter().any(|v| !v.is_finite()) {
+                return Err(SolveError::Singular);
+            }
+
+            let mut d = Array1::zeros(n);
+
+            for (&i, &v) in active.iter().zip(d_active.iter()) {
+                d[i] = v;
+            }
+
+            let ad = self.a.dot(&d);
+
+            // Largest step before the active set changes:
+            let mut step = beta_bar - self.beta;
+            let mut event = None;
+
+            for i in (0..n).filter(|i| !active.contains(i)) {
+                let candidates = [
+                    (beta_bar - c[i]) / (1.0 - ad[i]),
+                    (beta_bar + c[i]) / (1.0 + ad[i]),
+                ];
+
+                for &s in candidates.iter() {
+                    if s > 1e-12 && s < step {
+                        step = s;
+                        event = Some(Event::Add(i));
+                    }
+                }
+            }
+
+            for (k, &i) in active.iter().enumerate() {
+                let s = -theta[i] / d[i];
+
+                if s > 1e-12 && s < step {
+                    step = s;
+                    event = Some(Event::Remove(k));
+                }
+            }
+
+            theta.scaled_add(step, &d);
+            beta_bar -= step;
+            c = &self.b - &self.a.dot(&theta);
+
+            match event {
+                Some(Event::Add(i)) => active.push(i),
+                Some(Event::Remove(k)) => theta[active.remove(k)] = 0.0,
+                None => beta_bar = self.beta,
+            }
+        }
+
+        Err(SolveError::NotConverged)
+    }
+}
+
+impl<S, A, F: LinearStateFunction<S>> BatchLearner<S, A> for LARSTD<F> {
+    fn handle_batch(&mut self, ts: &[Transition<S, A>]) {
+        for t in ts {
+            accumulate(&self.fa_theta, self.gamma, &mut self.a, &mut self.b, t);
+        }
+
+        self.last_error = self.solve().err();
+    }
+}
+
+impl<S, F: LinearStateFunction<S>> ValuePredictor<S> for LARSTD<F> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
@@ -7,19 +7,39 @@ use crate::{
         linear::LinearStateFunction,
     },
     prediction::ValuePredictor,
-    utils::pinv,
 };
-use ndarray::{Array1, Array2, Axis};
-use ndarray_linalg::Solve;
-
+use ndarray::Array1;
+use super::{SolveError, SparseMatrix, accumulate, solve_system};
+
+/// Least-squares temporal-difference learning.
+///
+/// The statistics `A` and `b` are accumulated sparsely, so each transition
+/// only touches the entries of the active features, and the system `A theta
+/// = b` is solved iteratively, warm-started from the current weights. Memory
+/// therefore scales with the number of non-zero entries of `A`, which keeps
+/// large sparse bases such as tile codings tractable. If the iterative solver
+/// fails, as it may when `A` is poorly conditioned, the system is solved
+/// densely instead, by direct factorisation or else via the pseudo-inverse.
+///
+/// # References
+/// - Bradtke, S. J., Barto, A. G. (1996). Linear least-squares algorithms for
+///   temporal difference learning. Machine Learning, 22(1–3), 33–57.
+/// - Van der Vorst, H. A. (1992). Bi-CGSTAB: A fast and smoothly converging
+///   variant of Bi-CG for the solution of nonsymmetric linear systems. SIAM
+///   Journal on Scientific and Statistical Computing, 13(2), 631–644.
 #[derive(Parameterised)]
 pub struct LSTD<F> {
     #[weights] pub fa_theta: F,
 
     pub gamma: f64,
 
-    a: Array2<f64>,
+    pub tol: f64,
+    pub max_iterations: usize,
+
+    a: SparseMatrix,
     b: Array1<f64>,
+
+    last_error: Option<SolveError>,
 }
 
 impl<F: Parameterised> LSTD<F> {
@@ -31,23 +51,30 @@ impl<F: Parameterised> LSTD<F> {
 
             gamma,
 
-            a: Array2::eye(dim[0]) * 1e-6,
+            tol: 1e-10,
+            max_iterations: 10_000,
+
+            a: SparseMatrix::eye(dim[0], 1e-6),
             b: Array1::zeros(dim[0]),
+
+            last_error: None,
         }
     }
 }
 
 impl<F: Parameterised> LSTD<F> {
-    pub fn solve(&mut self) {
-        let mut w = self.fa_theta.weights_view_mut();
-
-        if let Ok(theta) = self.a.solve(&self.b) {
-            // First try the clean approach:
-            w.assign(&theta);
-        } else if let Ok(ainv) = pinv(&self.a) {
-            // Otherwise solve via SVD:
-            w.assign(&ainv.dot(&self.b));
-        }
+    /// Return the error raised by the last solve performed in `handle_batch`,
+    /// if it failed.
+    pub fn last_error(&self) -> Option<&SolveError> { self.last_error.as_ref() }
+
+    /// Solve for the weights, which are left unchanged if this fails.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        let theta = self.fa_theta.weights_view().column(0).to_owned();
+        let theta = solve_system(&self.a, 0.0, &self.b, theta, self.tol, self.max_iterations)?;
+
+        self.fa_theta.weights_view_mut().column_mut(0).assign(&theta);
+
+        Ok(())
     }
 }
 
@@ -56,26 +83,11 @@ where
     F: LinearStateFunction<S, Output = f64>,
 {
     fn handle_batch(&mut self, ts: &[Transition<S, A>]) {
-        ts.into_iter().for_each(|ref t| {
-            let (s, ns) = t.states();
-
-            let phi_s = self.fa_theta.features(s).expanded();
-
-            self.b.scaled_add(t.reward, &phi_s);
-
-            if t.terminated() {
-                let phi_s = phi_s.insert_axis(Axis(1));
-
-                self.a += &phi_s.view().dot(&phi_s.t());
-            } else {
-                let phi_ns = self.fa_theta.features(ns).expanded();
-                let pd = (self.gamma * phi_ns - &phi_s).insert_axis(Axis(0));
-
-                self.a -= &phi_s.insert_axis(Axis(1)).dot(&pd);
-            }
-        });
+        for t in ts {
+            accumulate(&self.fa_theta, self.gamma, &mut self.a, &mut self.b, t);
+        }
 
-        self.solve();
+        self.last_error = self.solve().err();
     }
 }
 
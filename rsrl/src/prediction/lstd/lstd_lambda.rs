@@ -7,11 +7,21 @@ use crate::{
         linear::LinearStateFunction,
     },
     prediction::ValuePredictor,
-    utils::pinv,
 };
-use ndarray::{Array1, Array2, Axis};
-use ndarray_linalg::Solve;
-
+use ndarray::Array1;
+use std::collections::HashMap;
+use super::{SolveError, SparseMatrix, active, difference, solve_system};
+
+/// Least-squares temporal-difference learning with eligibility traces.
+///
+/// As in `LSTD`, the statistics are accumulated sparsely and the system is
+/// solved iteratively, with a dense fallback. The eligibility trace is stored
+/// by its non-zero entries, so each transition costs `O(zk)` for a trace with
+/// `z` non-zero entries and `k` active features.
+///
+/// # References
+/// - Boyan, J. A. (2002). Technical update: Least-squares temporal difference
+///   learning. Machine Learning, 49(2–3), 233–246.
 #[derive(Parameterised)]
 pub struct LSTDLambda<F> {
     #[weights] pub fa_theta: F,
@@ -19,10 +29,15 @@ pub struct LSTDLambda<F> {
     pub gamma: f64,
     pub lambda: f64,
 
-    z: Array1<f64>,
+    pub tol: f64,
+    pub max_iterations: usize,
+
+    z: HashMap<usize, f64>,
 
-    a: Array2<f64>,
+    a: SparseMatrix,
     b: Array1<f64>,
+
+    last_error: Option<SolveError>,
 }
 
 impl<F: Parameterised> LSTDLambda<F> {
@@ -35,25 +50,32 @@ impl<F: Parameterised> LSTDLambda<F> {
             gamma,
             lambda,
 
-            z: Array1::zeros(dim[0]),
+            tol: 1e-10,
+            max_iterations: 10_000,
+
+            z: HashMap::new(),
 
-            a: Array2::eye(dim[0]) * 1e-6,
+            a: SparseMatrix::eye(dim[0], 1e-6),
             b: Array1::zeros(dim[0]),
+
+            last_error: None,
         }
     }
 }
 
 impl<F: Parameterised> LSTDLambda<F> {
-    pub fn solve(&mut self) {
-        let mut w = self.fa_theta.weights_view_mut();
-
-        if let Ok(theta) = self.a.solve(&self.b) {
-            // First try the clean approach:
-            w.assign(&theta);
-        } else if let Ok(ainv) = pinv(&self.a) {
-            // Otherwise solve via SVD:
-            w.assign(&ainv.dot(&self.b));
-        }
+    /// Return the error raised by the last solve performed in `handle_batch`,
+    /// if it failed.
+    pub fn last_error(&self) -> Option<&SolveError> { self.last_error.as_ref() }
+
+    /// Solve for the weights, which are left unchanged if this fails.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        let theta = self.fa_theta.weights_view().column(0).to_owned();
+        let theta = solve_system(&self.a, 0.0, &self.b, theta, self.tol, self.max_iterations)?;
+
+        self.fa_theta.weights_view_mut().column_mut(0).assign(&theta);
+
+        Ok(())
     }
 }
 
@@ -62,32 +84,38 @@ where
     F: LinearStateFunction<S, Output = f64>,
 {
     fn handle_batch(&mut self, ts: &[Transition<S, A>]) {
-        ts.into_iter().for_each(|t| {
+        for t in ts {
             let (s, ns) = t.states();
 
-            let phi_s = self.fa_theta.features(s).expanded();
+            let phi_s = active(&self.fa_theta.features(s));
 
             // Update trace:
             let c = self.lambda * self.gamma;
 
-            self.z.zip_mut_with(&phi_s, move |x, &y| *x = c * *x + y);
+            self.z.values_mut().for_each(|x| *x *= c);
+
+            for &(i, v) in phi_s.iter() {
+                *self.z.entry(i).or_insert(0.0) += v;
+            }
 
             // Update matrices:
-            self.b.scaled_add(t.reward, &self.z);
+            let z: Vec<(usize, f64)> = self.z.iter().map(|(&i, &v)| (i, v)).collect();
+
+            for &(i, v) in z.iter() {
+                self.b[i] += t.reward * v;
+            }
 
             if t.terminated() {
-                self.a += &self.z.view().insert_axis(Axis(1)).dot(&phi_s.insert_axis(Axis(0)));
-                self.z.fill(0.0);
+                self.a.add_outer(1.0, &z, &phi_s);
+                self.z.clear();
             } else {
-                let mut pd = self.fa_theta.features(ns).expanded();
-
-                pd.zip_mut_with(&phi_s, |x, &y| *x = y - self.gamma * *x);
+                let pd = difference(&phi_s, &active(&self.fa_theta.features(ns)), self.gamma);
 
-                self.a += &self.z.view().insert_axis(Axis(1)).dot(&pd.insert_axis(Axis(0)));
+                self.a.add_outer(1.0, &z, &pd);
             }
-        });
+        }
 
-        self.solve();
+        self.last_error = self.solve().err();
     }
 }
 
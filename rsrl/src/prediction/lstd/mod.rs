@@ -1,7 +1,451 @@
-// TODO: Implement regularized LSTD "http://mlg.eng.cam.ac.uk/hoffmanm/papers/hoffman:2012b.pdf
+use crate::{
+    domains::Transition,
+    fa::linear::LinearStateFunction,
+    utils::pinv,
+};
+use ndarray::Array1;
+use ndarray_linalg::Solve;
+use std::{error, fmt};
+
+mod sparse;
+
+use self::sparse::{SparseMatrix, active, difference};
 
 import_all!(lstd);
 import_all!(ilstd);
 import_all!(lstd_lambda);
 import_all!(lambda_lspe);
 import_all!(recursive_lstd);
+import_all!(l2_lstd);
+import_all!(lars_td);
+import_all!(dantzig_lstd);
+
+/// Error raised when the linear system of a least-squares TD method cannot be
+/// solved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveError {
+    /// The system matrix is singular, and no solution could be recovered.
+    Singular,
+
+    /// The iterative solver failed to reach the required tolerance within the
+    /// iteration limit.
+    NotConverged,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Singular => write!(f, "system matrix is singular"),
+            SolveError::NotConverged => {
+                write!(f, "solver did not converge within the iteration limit")
+            },
+        }
+    }
+}
+
+impl error::Error for SolveError {}
+
+/// Accumulate the LSTD statistics, `A = sum phi (phi - gamma phi')^T` and `b =
+/// sum r phi`, for a single transition.
+///
+/// Only the active features are visited, so the cost is quadratic in the
+/// number of non-zero activations rather than the number of features.
+fn accumulate<S, A, F: LinearStateFunction<S>>(
+    fa: &F,
+    gamma: f64,
+    a: &mut SparseMatrix,
+    b: &mut Array1<f64>,
+    t: &Transition<S, A>,
+) {
+    let phi_s = active(&fa.features(t.from.state()));
+    let pd = if t.terminated() {
+        phi_s.clone()
+    } else {
+        difference(&phi_s, &active(&fa.features(t.to.state())), gamma)
+    };
+
+    for &(i, v) in phi_s.iter() {
+        b[i] += t.reward * v;
+    }
+
+    a.add_outer(1.0, &phi_s, &pd);
+}
+
+/// Solve the non-symmetric system `A x = b` by the stabilised bi-conjugate
+/// gradient method, starting from `x`.
+///
+/// The system matrix is only accessed through the product `matvec(x) = A x`,
+/// which allows sparse and implicitly regularised matrices to be used without
+/// ever forming a dense copy. Iteration stops once the residual norm falls
+/// below `tol` relative to the norm of `b`. The shadow residual is reset from
+/// the current residual whenever the recurrence breaks down, which is common
+/// for the highly structured systems produced by sparse features; a breakdown
+/// immediately after such a restart is reported as a singular system.
+fn bicgstab(
+    matvec: impl Fn(&Array1<f64>) -> Array1<f64>,
+    b: &Array1<f64>,
+    mut x: Array1<f64>,
+    tol: f64,
+    max_iterations: usize,
+) -> Result<Array1<f64>, SolveError> {
+    let n = b.len();
+    let threshold = tol * b.dot(b).sqrt().max(1e-300);
+
+    let mut r = b - &matvec(&x);
+    let mut r_hat = r.clone();
+
+    let mut rho = 1.0;
+    let mut alpha = 1.0;
+    let mut omega = 1.0;
+
+    let mut v = Array1::zeros(n);
+    let mut p = Array1::zeros(n);
+
+    let mut restarted = true;
+
+    macro_rules! restart {
+        () => {{
+            r_hat = r.clone();
+            rho = 1.0;
+            alpha = 1.0;
+            omega = 1.0;
+            v.fill(0.0);
+            p.fill(0.0);
+            restarted = true;
+
+            continue;
+        }};
+    }
+
+    for _ in 0..max_iterations {
+        if r.dot(&r).sqrt() <= threshold {
+            return Ok(x);
+        }
+
+        let rho_new = r_hat.dot(&r);
+
+        p = &r + &((p - &(omega * &v)) * (rho_new / rho * alpha / omega));
+        v = matvec(&p);
+
+        let r_hat_v = r_hat.dot(&v);
+
+        if rho_new == 0.0 || r_hat_v == 0.0 {
+            if restarted {
+                return Err(SolveError::Singular);
+            }
+
+            restart!();
+        }
+
+        alpha = rho_new / r_hat_v;
+
+        let s = &r - &(alpha * &v);
+
+        if s.dot(&s).sqrt() <= threshold {
+            x.scaled_add(alpha, &p);
+
+            return Ok(x);
+        }
+
+        let t = matvec(&s);
+        let t_s = t.dot(&s);
+        let t_sq = t.dot(&t);
+
+        // A non-zero vector in the null space of A:
+        if t_sq == 0.0 {
+            return Err(SolveError::Singular);
+        }
+
+        // Stagnation of the stabilising step; keep the progress and restart:
+        if t_s == 0.0 {
+            x.scaled_add(alpha, &p);
+            r = s;
+
+            restart!();
+        }
+
+        omega = t_s / t_sq;
+
+        x.scaled_add(alpha, &p);
+        x.scaled_add(omega, &s);
+        r = s - omega * t;
+        rho = rho_new;
+        restarted = false;
+
+        if x.iter().any(|v| !v.is_finite()) {
+            return Err(SolveError::Singular);
+        }
+    }
+
+    Err(SolveError::NotConverged)
+}
+
+/// Solve `(A + shift I) x = b`, starting from `x`.
+///
+/// The system is first solved iteratively with `bicgstab`. Should that fail,
+/// which is likely when `A` is poorly conditioned, a dense copy of the system
+/// is solved directly or, failing that, via the pseudo-inverse. The fallback
+/// costs `O(n^2)` memory and `O(n^3)` time for `n` features.
+fn solve_system(
+    a: &SparseMatrix,
+    shift: f64,
+    b: &Array1<f64>,
+    x: Array1<f64>,
+    tol: f64,
+    max_iterations: usize,
+) -> Result<Array1<f64>, SolveError> {
+    bicgstab(|x| a.dot(x) + shift * x, b, x, tol, max_iterations).or_else(|_| {
+        let mut dense = a.to_dense();
+
+        dense.diag_mut().map_inplace(|v| *v += shift);
+
+        dense.solve(b)
+            .or_else(|_| pinv(&dense).map(|ainv| ainv.dot(b)))
+            .ok()
+            .filter(|x| x.iter().all(|v| v.is_finite()))
+            .ok_or(SolveError::Singular)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BatchLearner, OnlineLearner,
+        domains::{Observation, Transition},
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::{Polynomial, UniformGrid}, optim::SGD},
+        },
+        prediction::ValuePredictor,
+        spaces::{Equipartition, ProductSpace},
+    };
+    use super::{
+        DantzigLSTD, L2LSTD, LARSTD, LSTD, LSTDLambda, LambdaLSPE, RecursiveLSTD, SolveError, iLSTD,
+    };
+
+    const GAMMA: f64 = 0.9;
+
+    fn one_hot(i: usize) -> Vec<f64> {
+        let mut s = vec![0.0; 3];
+        s[i] = 1.0;
+        s
+    }
+
+    /// Deterministic three-state chain with a unit reward on termination.
+    fn chain() -> Vec<Transition<Vec<f64>, ()>> {
+        (0..3).map(|i| Transition {
+            from: Observation::Full(one_hot(i)),
+            action: (),
+            reward: if i == 2 { 1.0 } else { 0.0 },
+            to: if i == 2 {
+                Observation::Terminal(one_hot(0))
+            } else {
+                Observation::Full(one_hot(i + 1))
+            },
+        }).collect()
+    }
+
+    fn check_values(agent: &impl ValuePredictor<Vec<f64>>, tol: f64) {
+        assert!((agent.predict_v(&one_hot(0)) - GAMMA * GAMMA).abs() < tol);
+        assert!((agent.predict_v(&one_hot(1)) - GAMMA).abs() < tol);
+        assert!((agent.predict_v(&one_hot(2)) - 1.0).abs() < tol);
+    }
+
+    /// Grid over the three state dimensions, yielding one active feature out of
+    /// eight for each state of the chain.
+    fn grid() -> UniformGrid {
+        UniformGrid::new(ProductSpace::new(vec![Equipartition::new(0.0, 2.0, 2); 3]))
+    }
+
+    #[test]
+    fn test_lstd() {
+        let mut agent = LSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA);
+
+        agent.handle_batch(&chain());
+
+        check_values(&agent, 1e-4);
+    }
+
+    #[test]
+    fn test_lstd_sparse() {
+        let mut agent = LSTD::new(LFA::scalar(grid(), SGD(1.0)), GAMMA);
+
+        agent.handle_batch(&chain());
+
+        check_values(&agent, 1e-4);
+    }
+
+    #[test]
+    fn test_lstd_fallback() {
+        let mut agent = LSTD::new(LFA::scalar(grid(), SGD(1.0)), GAMMA);
+
+        // Without any iterations, the dense fallback must be used:
+        agent.max_iterations = 0;
+        agent.handle_batch(&chain());
+
+        assert_eq!(agent.last_error(), None);
+        check_values(&agent, 1e-4);
+    }
+
+    #[test]
+    fn test_lstd_lambda_sparse() {
+        let mut agent = LSTDLambda::new(LFA::scalar(grid(), SGD(1.0)), GAMMA, 0.5);
+
+        agent.handle_batch(&chain());
+
+        assert_eq!(agent.last_error(), None);
+        check_values(&agent, 1e-4);
+    }
+
+    #[test]
+    fn test_lambda_lspe_sparse() {
+        let mut agent = LambdaLSPE::new(LFA::scalar(grid(), SGD(1.0)), 1.0, GAMMA, 1.0);
+
+        // Each batch propagates the return back by at least one state:
+        for _ in 0..5 {
+            agent.handle_batch(&chain());
+        }
+
+        assert_eq!(agent.last_error(), None);
+        check_values(&agent, 1e-4);
+    }
+
+    #[test]
+    fn test_recursive_lstd_sparse() {
+        let mut dense = RecursiveLSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA);
+        let mut sparse = RecursiveLSTD::new(LFA::scalar(grid(), SGD(1.0)), GAMMA);
+
+        for _ in 0..2 {
+            for t in chain() {
+                dense.handle_transition(&t);
+                sparse.handle_transition(&t);
+            }
+
+            OnlineLearner::<Vec<f64>, ()>::handle_terminal(&mut dense);
+            OnlineLearner::<Vec<f64>, ()>::handle_terminal(&mut sparse);
+        }
+
+        assert!(dense.predict_v(&one_hot(2)) > 0.0);
+
+        // Only the active features are visited, so both bases agree:
+        for i in 0..3 {
+            let (v_dense, v_sparse) = (dense.predict_v(&one_hot(i)), sparse.predict_v(&one_hot(i)));
+
+            assert!((v_dense - v_sparse).abs() <= 1e-9 * v_dense.abs().max(1e-12));
+        }
+    }
+
+    #[test]
+    fn test_recursive_lstd_episodes() {
+        let mut agent = RecursiveLSTD::new(LFA::scalar(grid(), SGD(1.0)), GAMMA);
+
+        // The inverse is kept across episodes, so every episode keeps moving
+        // the weights towards the true values:
+        for _ in 0..5 {
+            let before = agent.weights();
+            let v_before = agent.predict_v(&one_hot(2));
+
+            for t in chain() {
+                agent.handle_transition(&t);
+            }
+
+            OnlineLearner::<Vec<f64>, ()>::handle_terminal(&mut agent);
+
+            assert!(agent.weights().iter().zip(before.iter()).any(|(w, b)| w != b));
+            assert!(agent.predict_v(&one_hot(2)) > v_before);
+        }
+    }
+
+    #[test]
+    fn test_ilstd_sparse() {
+        let mut agent = iLSTD::new(LFA::scalar(grid(), SGD(1.0)), 0.005, GAMMA, 10);
+
+        for _ in 0..200 {
+            for t in chain() {
+                agent.handle_transition(&t);
+            }
+        }
+
+        check_values(&agent, 0.05);
+    }
+
+    #[test]
+    fn test_l2_lstd() {
+        let mut agent = L2LSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA, 1e-9);
+
+        agent.handle_batch(&chain());
+
+        check_values(&agent, 1e-6);
+    }
+
+    #[test]
+    fn test_l2_lstd_singular() {
+        let mut agent = L2LSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA, 0.0);
+
+        match agent.solve() {
+            Err(SolveError::Singular) => {},
+            _ => panic!("Expected a singular system."),
+        }
+    }
+
+    #[test]
+    fn test_lars_td() {
+        let mut agent = LARSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA, 0.0);
+
+        agent.handle_batch(&chain());
+
+        check_values(&agent, 1e-10);
+
+        // A penalty above the largest correlation yields the zero solution:
+        agent.beta = 1.5;
+        agent.solve().unwrap();
+
+        assert!(agent.weights().iter().all(|&w| w == 0.0));
+
+        // Intermediate penalties select only the most correlated feature:
+        agent.beta = 0.5;
+        agent.solve().unwrap();
+
+        assert!(agent.predict_v(&one_hot(2)) > 0.0);
+        assert_eq!(agent.predict_v(&one_hot(0)), 0.0);
+        assert_eq!(agent.predict_v(&one_hot(1)), 0.0);
+    }
+
+    #[test]
+    fn test_dantzig_lstd() {
+        let mut agent = DantzigLSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA, 0.1);
+
+        agent.handle_batch(&chain());
+
+        // With tabular features the weights are the state values:
+        let v: Vec<f64> = (0..3).map(|i| agent.predict_v(&one_hot(i))).collect();
+
+        // The LSTD solution is feasible, so the Dantzig solution is no larger in L1 norm:
+        let l1: f64 = v.iter().map(|x| x.abs()).sum();
+
+        assert!(l1 <= GAMMA * GAMMA + GAMMA + 1.0 + 1e-6);
+
+        // Each component of the residual, b - A theta, satisfies the constraint:
+        let residuals = [GAMMA * v[1] - v[0], GAMMA * v[2] - v[1], 1.0 - v[2]];
+
+        assert!(residuals.iter().all(|r| r.abs() <= 0.1 + 1e-6));
+    }
+
+    #[test]
+    fn test_dantzig_lstd_not_converged() {
+        let mut agent = DantzigLSTD::new(LFA::scalar(Polynomial::new(3, 1), SGD(1.0)), GAMMA, 0.1);
+
+        agent.max_iterations = 1;
+        agent.handle_batch(&chain());
+
+        // The failure is reported and the previous weights are kept:
+        assert_eq!(agent.last_error(), Some(&SolveError::NotConverged));
+        assert!(agent.weights().iter().all(|&w| w == 0.0));
+
+        agent.max_iterations = 100_000;
+        agent.handle_batch(&chain()[..0]);
+
+        assert_eq!(agent.last_error(), None);
+        assert!(agent.predict_v(&one_hot(2)) > 0.0);
+    }
+}
@@ -8,8 +8,28 @@ use crate::{
     },
     prediction::ValuePredictor,
 };
-use ndarray::{Array2, Axis};
-
+use ndarray::{Array1, Array2, Axis};
+use super::{active, difference};
+
+/// Recursive least-squares temporal-difference learning.
+///
+/// Maintains an estimate of the inverse of the LSTD matrix, initialised to
+/// `1e-5 I` and carried over between episodes, through Sherman–Morrison
+/// rank-one updates. The products with the inverse only visit
+/// the active features, costing `O(nk)` for `k` non-zero activations out of
+/// `n` features.
+///
+/// The inverse is nonetheless a dense `n x n` matrix, so memory is `O(n^2)` and
+/// each step ends with an `O(n^2)` update, however sparse the features. For
+/// large bases, such as tile codings, use `iLSTD` instead, whose memory and
+/// per-step cost scale with the number of non-zero entries of `A`.
+///
+/// # References
+/// - Bradtke, S. J., Barto, A. G. (1996). Linear least-squares algorithms for
+///   temporal difference learning. Machine Learning, 22(1–3), 33–57.
+/// - Xu, X., He, H., Hu, D. (2002). Efficient reinforcement learning using
+///   recursive least-squares methods. Journal of Artificial Intelligence
+///   Research, 16, 259–292.
 #[derive(Parameterised)]
 pub struct RecursiveLSTD<F> {
     #[weights] pub fa_theta: F,
@@ -42,57 +62,44 @@ where
 
         let phi_s = self.fa_theta.features(s);
         let theta_s = self.fa_theta.evaluate_features(&phi_s);
+        let phi_s = active(&phi_s);
 
-        if t.terminated() {
-            // (D x 1)
-            let phi_s = phi_s.expanded().insert_axis(Axis(1));
-
-            // (D x 1) <- (D x D) . (D x 1)
-            let v = self.c_mat.dot(&phi_s);
-
-            // (1 x D) <- (D x 1)T
-            let g = v.t();
-
-            // (1 x 1) <- (1 x D) . (D x 1)
-            let a = 1.0 + unsafe { g.dot(&phi_s).uget((0, 0)) };
-            let residual = t.reward - theta_s;
-
-            // (D x D) <- (D x 1) . (1 x D)
-            let vg = v.dot(&g);
-
-            self.c_mat.scaled_add(-1.0 / a, &vg);
-            self.fa_theta.weights_view_mut().scaled_add(residual / a, &v);
+        let (residual, pd) = if t.terminated() {
+            (t.reward - theta_s, phi_s.clone())
         } else {
             let phi_ns = self.fa_theta.features(ns);
             let theta_ns = self.fa_theta.evaluate_features(&phi_ns);
 
-            // (D x 1)
-            let phi_s = phi_s.expanded().insert_axis(Axis(1));
-            let phi_ns = phi_ns.expanded().insert_axis(Axis(1));
+            (
+                t.reward + self.gamma * theta_ns - theta_s,
+                difference(&phi_s, &active(&phi_ns), self.gamma),
+            )
+        };
 
-            let pd = (-self.gamma * phi_ns) + &phi_s;
+        let n = self.c_mat.rows();
 
-            // (1 x D) <- ((D x D) . (D x 1))T
-            //  Note: self.permuted_axes([1, 0]) is equivalent to taking the transpose.
-            let g = self.c_mat.dot(&pd).permuted_axes([1, 0]);
+        // v = C phi, and g = C^T (phi - gamma phi'):
+        let mut v = Array1::zeros(n);
+        let mut g = Array1::zeros(n);
 
-            // (1 x 1) <- (1 x D) . (D x 1)
-            let a = 1.0 + unsafe { g.dot(&phi_s).uget((0, 0)) };
+        for &(i, x) in phi_s.iter() {
+            v.scaled_add(x, &self.c_mat.column(i));
+        }
 
-            // (D x 1) <- (D x D) . (D x 1)
-            let v = self.c_mat.dot(&phi_s);
-            let residual = t.reward + self.gamma * theta_ns - theta_s;
+        for &(i, x) in pd.iter() {
+            g.scaled_add(x, &self.c_mat.row(i));
+        }
 
-            // (D x D) <- (D x 1) . (1 x D)
-            let vg = v.dot(&g);
+        let a = 1.0 + phi_s.iter().fold(0.0, |acc, &(i, x)| acc + g[i] * x);
 
-            self.c_mat.scaled_add(-1.0 / a, &vg);
-            self.fa_theta.weights_view_mut().scaled_add(residual / a, &v);
+        // C <- C - v g^T / a, one column at a time:
+        for (mut column, &gj) in self.c_mat.axis_iter_mut(Axis(1)).zip(g.iter()) {
+            if gj != 0.0 {
+                column.scaled_add(-gj / a, &v);
+            }
         }
-    }
 
-    fn handle_terminal(&mut self) {
-        self.c_mat.fill(0.0);
+        self.fa_theta.weights_view_mut().column_mut(0).scaled_add(residual / a, &v);
     }
 }
 
@@ -0,0 +1,140 @@
+use crate::fa::linear::Features;
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix1};
+use std::collections::HashMap;
+
+/// Return the non-zero entries of a feature vector.
+pub(super) fn active(features: &Features) -> Vec<(usize, f64)> {
+    match features {
+        Features::Dense(activations) => activations.indexed_iter()
+            .filter(|(_, v)| **v != 0.0)
+            .map(|(i, v)| (i, *v))
+            .collect(),
+        Features::Sparse(_, activations) => activations.iter().map(|(i, v)| (*i, *v)).collect(),
+    }
+}
+
+/// Return the non-zero entries of `x - scale y`.
+pub(super) fn difference(
+    x: &[(usize, f64)],
+    y: &[(usize, f64)],
+    scale: f64,
+) -> Vec<(usize, f64)> {
+    let mut out: HashMap<usize, f64> = x.iter().cloned().collect();
+
+    for &(i, v) in y {
+        *out.entry(i).or_insert(0.0) -= scale * v;
+    }
+
+    out.into_iter().filter(|(_, v)| *v != 0.0).collect()
+}
+
+/// Square sparse matrix stored by column.
+///
+/// Rank-one updates from sparse feature vectors with `k` active entries cost
+/// `O(k^2)`, and the memory footprint scales with the number of non-zero
+/// entries rather than the square of the number of features.
+#[derive(Clone, Debug)]
+pub(super) struct SparseMatrix {
+    columns: Vec<HashMap<usize, f64>>,
+}
+
+impl SparseMatrix {
+    pub fn zeros(n: usize) -> Self {
+        SparseMatrix {
+            columns: vec![HashMap::new(); n],
+        }
+    }
+
+    pub fn eye(n: usize, scale: f64) -> Self {
+        SparseMatrix {
+            columns: (0..n).map(|i| ::std::iter::once((i, scale)).collect()).collect(),
+        }
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        self.columns[j].get(&i).copied().unwrap_or(0.0)
+    }
+
+    pub fn column(&self, j: usize) -> &HashMap<usize, f64> { &self.columns[j] }
+
+    pub fn has_empty_column(&self) -> bool { self.columns.iter().any(|c| c.is_empty()) }
+
+    /// Perform the rank-one update `A += scale u v^T`.
+    pub fn add_outer(&mut self, scale: f64, u: &[(usize, f64)], v: &[(usize, f64)]) {
+        for &(j, vj) in v {
+            let column = &mut self.columns[j];
+
+            for &(i, ui) in u {
+                *column.entry(i).or_insert(0.0) += scale * ui * vj;
+            }
+        }
+    }
+
+    /// Return a dense copy of the matrix.
+    pub fn to_dense(&self) -> Array2<f64> {
+        let n = self.columns.len();
+        let mut dense = Array2::zeros((n, n));
+
+        for (j, column) in self.columns.iter().enumerate() {
+            for (&i, &v) in column {
+                dense[(i, j)] = v;
+            }
+        }
+
+        dense
+    }
+
+    /// Return the sum of squared entries.
+    pub fn frobenius_sq(&self) -> f64 {
+        self.columns.iter().flat_map(|c| c.values()).map(|v| v * v).sum()
+    }
+
+    /// Return the matrix-vector product `A x`.
+    pub fn dot<D: Data<Elem = f64>>(&self, x: &ArrayBase<D, Ix1>) -> Array1<f64> {
+        let mut out = Array1::zeros(self.columns.len());
+
+        for (column, &xj) in self.columns.iter().zip(x.iter()) {
+            if xj != 0.0 {
+                for (&i, &v) in column {
+                    out[i] += v * xj;
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Return the transposed matrix-vector product `A^T x`.
+    pub fn t_dot<D: Data<Elem = f64>>(&self, x: &ArrayBase<D, Ix1>) -> Array1<f64> {
+        self.columns.iter()
+            .map(|column| column.iter().map(|(&i, &v)| v * x[i]).sum())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+    use super::*;
+
+    #[test]
+    fn test_products() {
+        let mut a = SparseMatrix::eye(3, 1.0);
+
+        a.add_outer(2.0, &[(0, 1.0), (2, -1.0)], &[(1, 3.0)]);
+
+        let dense = arr2(&[
+            [1.0, 6.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -6.0, 1.0],
+        ]);
+        let x = arr1(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(a.column(1).len(), 3);
+        assert_eq!(a.get(2, 1), -6.0);
+        assert_eq!(a.dot(&x), dense.dot(&x));
+        assert_eq!(a.t_dot(&x), dense.t().dot(&x));
+        assert_eq!(a.frobenius_sq(), 75.0);
+        assert_eq!(a.to_dense(), dense);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    OnlineLearner,
+    domains::{Observation, Transition},
+    policies::{EnumerablePolicy, Policy},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use super::ValuePredictor;
+
+pub const GAMMA: f64 = 0.9;
+
+/// Target policy that selects action 0 with probability `p` in every state.
+pub struct Biased(pub f64);
+
+impl Policy<Vec<f64>> for Biased {
+    type Action = usize;
+
+    fn probability(&self, _: &Vec<f64>, a: &usize) -> f64 {
+        if *a == 0 { self.0 } else { 1.0 - self.0 }
+    }
+}
+
+impl EnumerablePolicy<Vec<f64>> for Biased {
+    fn n_actions(&self) -> usize { 2 }
+
+    fn probabilities(&self, _: &Vec<f64>) -> Vec<f64> { vec![self.0, 1.0 - self.0] }
+}
+
+/// Two-state chain, sampled under a uniform behaviour policy, in which only
+/// the action taken in the second state affects the reward.
+pub fn chain(rng: &mut impl Rng) -> [Transition<Vec<f64>, usize>; 2] {
+    let a0 = rng.gen_range(0, 2);
+    let a1 = rng.gen_range(0, 2);
+
+    [
+        Transition {
+            from: Observation::Full(vec![1.0, 0.0]),
+            action: a0,
+            reward: 0.0,
+            to: Observation::Full(vec![0.0, 1.0]),
+        },
+        Transition {
+            from: Observation::Full(vec![0.0, 1.0]),
+            action: a1,
+            reward: if a1 == 0 { 1.0 } else { 0.0 },
+            to: Observation::Terminal(vec![0.0, 0.0]),
+        },
+    ]
+}
+
+/// Train `agent` on `n_episodes` of the chain.
+pub fn train<L: OnlineLearner<Vec<f64>, usize>>(agent: &mut L, n_episodes: usize) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..n_episodes {
+        for t in chain(&mut rng).iter() {
+            agent.handle_transition(t);
+        }
+    }
+}
+
+/// Check the values of the target policy that selects action 0 with
+/// probability `p`, having followed a uniform behaviour policy.
+pub fn check_values<L>(agent: &mut L, p: f64)
+where
+    L: OnlineLearner<Vec<f64>, usize> + ValuePredictor<Vec<f64>>,
+{
+    train(agent, 5000);
+
+    assert!((agent.predict_v(&vec![0.0, 1.0]) - p).abs() < 0.05);
+    assert!((agent.predict_v(&vec![1.0, 0.0]) - p * GAMMA).abs() < 0.05);
+}
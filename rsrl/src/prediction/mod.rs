@@ -1,5 +1,5 @@
 //! Prediction agents module.
-use crate::Shared;
+use crate::{Shared, fa::Weights, linalg::MatrixLike};
 
 pub trait ValuePredictor<S> {
     /// Compute the estimated value of V(s).
@@ -10,6 +10,24 @@ impl<S, T: ValuePredictor<S>> ValuePredictor<S> for Shared<T> {
     fn predict_v(&self, s: &S) -> f64 { self.borrow().predict_v(s) }
 }
 
+pub trait VariancePredictor<S> {
+    /// Compute the estimated variance of the return, Var[G | s].
+    fn predict_var(&self, s: &S) -> f64;
+}
+
+impl<S, T: VariancePredictor<S>> VariancePredictor<S> for Shared<T> {
+    fn predict_var(&self, s: &S) -> f64 { self.borrow().predict_var(s) }
+}
+
+pub trait PosteriorValuePredictor<S>: ValuePredictor<S> {
+    /// Compute the posterior variance of the estimate of V(s).
+    fn predict_v_variance(&self, s: &S) -> f64;
+}
+
+impl<S, T: PosteriorValuePredictor<S>> PosteriorValuePredictor<S> for Shared<T> {
+    fn predict_v_variance(&self, s: &S) -> f64 { self.borrow().predict_v_variance(s) }
+}
+
 pub trait ActionValuePredictor<S, A> {
     /// Compute the estimated value of Q(s, a).
     fn predict_q(&self, s: &S, a: &A) -> f64;
@@ -19,6 +37,83 @@ impl<S, A, T: ActionValuePredictor<S, A>> ActionValuePredictor<S, A> for Shared<
     fn predict_q(&self, s: &S, a: &A) -> f64 { self.borrow().predict_q(s, a) }
 }
 
+pub trait DistributionPredictor<S, A>: ActionValuePredictor<S, A> {
+    /// Compute the distribution of the return from (s, a) as a set of atoms,
+    /// each paired with its probability mass.
+    fn predict_distribution(&self, s: &S, a: &A) -> Vec<(f64, f64)>;
+
+    /// Compute the `tau`-quantile of the distribution of the return from (s,
+    /// a).
+    fn predict_quantile(&self, s: &S, a: &A, tau: f64) -> f64 {
+        quantile(self.predict_distribution(s, a), tau)
+    }
+
+    /// Compute the conditional value at risk of the return from (s, a): the
+    /// expected return over the lowest `level` fraction of outcomes.
+    fn predict_cvar(&self, s: &S, a: &A, level: f64) -> f64 {
+        cvar(self.predict_distribution(s, a), level)
+    }
+}
+
+impl<S, A, T: DistributionPredictor<S, A>> DistributionPredictor<S, A> for Shared<T> {
+    fn predict_distribution(&self, s: &S, a: &A) -> Vec<(f64, f64)> {
+        self.borrow().predict_distribution(s, a)
+    }
+}
+
+fn sorted_atoms(mut atoms: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    atoms.sort_by(|x, y| x.0.total_cmp(&y.0));
+    atoms
+}
+
+/// Return the `tau`-quantile of a discrete distribution of (atom, mass) pairs.
+pub(crate) fn quantile(atoms: Vec<(f64, f64)>, tau: f64) -> f64 {
+    let atoms = sorted_atoms(atoms);
+    let mut cdf = 0.0;
+
+    for &(x, p) in atoms.iter() {
+        cdf += p;
+
+        if cdf >= tau - 1e-12 { return x; }
+    }
+
+    atoms.last().map_or(0.0, |&(x, _)| x)
+}
+
+/// Return the conditional value at risk at `level` of a discrete distribution
+/// of (atom, mass) pairs.
+pub(crate) fn cvar(atoms: Vec<(f64, f64)>, level: f64) -> f64 {
+    assert!(level > 0.0, "CVaR level must be strictly positive.");
+
+    let mut mass = level;
+    let mut acc = 0.0;
+
+    for (x, p) in sorted_atoms(atoms) {
+        let m = p.min(mass);
+
+        acc += m * x;
+        mass -= m;
+
+        if mass <= 0.0 { break; }
+    }
+
+    acc / level
+}
+
+/// Inner product between a set of auxiliary weights and a gradient-like
+/// quantity, such as a feature vector or eligibility trace.
+fn dot_weights<G: MatrixLike>(weights: &Weights, g: &G) -> f64 {
+    let mut acc = 0.0;
+
+    g.for_each(|pd| acc += weights[pd.index] * pd.gradient);
+
+    acc
+}
+
+#[cfg(test)]
+pub(crate) mod mocking;
+
+pub mod bayes;
 pub mod gtd;
 pub mod lstd;
 pub mod mc;
@@ -0,0 +1,132 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
+    policies::Policy,
+    prediction::ValuePredictor,
+    traces::{Accumulating, Trace},
+};
+use std::ops::Deref;
+
+/// Emphatic TD(lambda) for off-policy prediction.
+///
+/// Each update is weighted by an emphasis derived from a follow-on trace of
+/// the user-specified `interest` in each state, and the importance sampling
+/// ratios between the `target` and `behaviour` policies. The follow-on trace
+/// decays at rate `beta`, which is initialised to `gamma` as in ETD(lambda);
+/// assigning any other value yields ETD(beta, lambda).
+///
+/// # References
+/// - Sutton, R. S., Mahmood, A. R., White, M. (2016). An emphatic approach to
+///   the problem of off-policy temporal-difference learning. Journal of
+///   Machine Learning Research, 17(73):1–29.
+/// - Hallak, A., Tamar, A., Munos, R., Mannor, S. (2016). Generalized emphatic
+///   temporal difference learning: Bias-variance analysis. In Proceedings of
+///   the 30th AAAI Conference on Artificial Intelligence, pp. 1631–1637.
+#[derive(Parameterised)]
+pub struct ETDLambda<F, T, B, I> {
+    #[weights] pub fa_theta: F,
+
+    pub target: T,
+    pub behaviour: B,
+    pub interest: I,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Accumulating<LFAGradient>,
+    follow_on: f64,
+    rho_old: f64,
+}
+
+impl<F: Parameterised, T, B, I> ETDLambda<F, T, B, I> {
+    pub fn new(
+        fa_theta: F,
+        target: T,
+        behaviour: B,
+        interest: I,
+        alpha: f64,
+        gamma: f64,
+        lambda: f64,
+    ) -> Self {
+        let trace = Accumulating::zeros(fa_theta.weights_dim());
+
+        ETDLambda {
+            fa_theta,
+
+            target,
+            behaviour,
+            interest,
+
+            alpha,
+            beta: gamma,
+            gamma,
+            lambda,
+
+            trace,
+            follow_on: 0.0,
+            rho_old: 1.0,
+        }
+    }
+}
+
+impl<S, F, T, B, I> OnlineLearner<S, T::Action> for ETDLambda<F, T, B, I>
+where
+    F: LinearStateFunction<S>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+    I: Fn(&S) -> f64,
+{
+    fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let interest = (self.interest)(s);
+
+        self.follow_on = self.beta * self.rho_old * self.follow_on + interest;
+
+        let emphasis = self.lambda * interest + (1.0 - self.lambda) * self.follow_on;
+        let decay = self.gamma * self.lambda;
+
+        self.trace.combine_inplace(&grad_s, move |x, y| rho * (decay * x + emphasis * y));
+
+        let v = self.fa_theta.evaluate_features(phi_s);
+        let td_error = if t.terminated() {
+            t.reward - v
+        } else {
+            let nv = self.fa_theta.evaluate_features(&self.fa_theta.features(t.to.state()));
+
+            t.reward + self.gamma * nv - v
+        };
+
+        self.fa_theta.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+
+        if t.terminated() {
+            self.trace.reset();
+            self.follow_on = 0.0;
+            self.rho_old = 1.0;
+        } else {
+            self.rho_old = rho;
+        }
+    }
+
+    fn handle_terminal(&mut self) {
+        self.trace.reset();
+        self.follow_on = 0.0;
+        self.rho_old = 1.0;
+    }
+}
+
+impl<S, F: LinearStateFunction<S>, T, B, I> ValuePredictor<S> for ETDLambda<F, T, B, I> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
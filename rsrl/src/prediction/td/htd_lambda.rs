@@ -0,0 +1,132 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
+    policies::Policy,
+    prediction::{ValuePredictor, dot_weights},
+    traces::{Accumulating, Trace},
+};
+use std::ops::Deref;
+
+/// Hybrid TD(lambda) for off-policy prediction.
+///
+/// Maintains both an importance-weighted eligibility trace and one for the
+/// `behaviour` policy alone. The auxiliary weights, `w`, trained with step
+/// size `beta`, are only used to correct for the difference between the two
+/// traces, such that the update reduces to TD(lambda) when learning
+/// on-policy.
+///
+/// # References
+/// - Hackman, L. (2012). Faster gradient-TD algorithms. MSc thesis,
+///   University of Alberta.
+/// - White, A., White, M. (2016). Investigating practical linear temporal
+///   difference learning. In Proceedings of the 15th International Conference
+///   on Autonomous Agents and Multiagent Systems, pp. 494–502.
+#[derive(Parameterised)]
+pub struct HTDLambda<F, T, B> {
+    #[weights] pub fa_theta: F,
+    pub w: Weights,
+
+    pub target: T,
+    pub behaviour: B,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Accumulating<LFAGradient>,
+    trace_b: Accumulating<LFAGradient>,
+}
+
+impl<F: Parameterised, T, B> HTDLambda<F, T, B> {
+    pub fn new(
+        fa_theta: F,
+        target: T,
+        behaviour: B,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+        lambda: f64,
+    ) -> Self {
+        let dim = fa_theta.weights_dim();
+
+        HTDLambda {
+            fa_theta,
+            w: Weights::zeros((dim[0], dim[1])),
+
+            target,
+            behaviour,
+
+            alpha,
+            beta,
+            gamma,
+            lambda,
+
+            trace: Accumulating::zeros(dim),
+            trace_b: Accumulating::zeros(dim),
+        }
+    }
+}
+
+impl<S, F, T, B> OnlineLearner<S, T::Action> for HTDLambda<F, T, B>
+where
+    F: LinearStateFunction<S>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let decay = self.gamma * self.lambda;
+
+        self.trace.combine_inplace(&grad_s, move |x, y| rho * (decay * x + y));
+        self.trace_b.combine_inplace(&grad_s, move |x, y| decay * x + y);
+
+        let v = self.fa_theta.evaluate_features(phi_s);
+
+        // TD error and the difference between current and discounted next features:
+        let (td_error, grad_diff) = if t.terminated() {
+            (t.reward - v, grad_s.clone())
+        } else {
+            let grad_ns = self.fa_theta.grad(t.to.state());
+            let nv = self.fa_theta.evaluate_features(grad_ns.features(&0).unwrap());
+            let gamma = self.gamma;
+
+            (t.reward + gamma * nv - v, grad_s.clone().combine(&grad_ns, move |x, y| x - gamma * y))
+        };
+
+        let w_diff = dot_weights(&self.w, &grad_diff);
+        let w_traces = dot_weights(&self.w, self.trace.deref())
+            - dot_weights(&self.w, self.trace_b.deref());
+
+        self.fa_theta.update_grad_scaled(self.trace.deref(), self.alpha * td_error);
+        self.fa_theta.update_grad_scaled(&grad_diff, self.alpha * w_traces);
+
+        self.trace.scaled_addto(self.beta * td_error, &mut self.w);
+        self.trace_b.scaled_addto(-self.beta * w_diff, &mut self.w);
+
+        if t.terminated() {
+            self.trace.reset();
+            self.trace_b.reset();
+        }
+    }
+
+    fn handle_terminal(&mut self) {
+        self.trace.reset();
+        self.trace_b.reset();
+    }
+}
+
+impl<S, F: LinearStateFunction<S>, T, B> ValuePredictor<S> for HTDLambda<F, T, B> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
@@ -1,12 +1,109 @@
 import_all!(td);
 import_all!(td_lambda);
+import_all!(totd_lambda);
+import_all!(etd_lambda);
+import_all!(toetd_lambda);
+import_all!(htd_lambda);
+import_all!(tohtd_lambda);
+import_all!(td_var);
 
 // TODO:
 // n-step TD - Sutton & Barto
-// ETD(lambda) - https://arxiv.org/pdf/1503.04269.pdf
-// HTD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // PTD(lambda) - http://proceedings.mlr.press/v32/sutton14.pdf
-// True online TD(lambda) - http://proceedings.mlr.press/v32/seijen14.pdf
-// True online ETD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
-// True online ETD(beta, lambda) - https://arxiv.org/pdf/1602.08771.pdf
-// True online HTD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        fa::linear::{LFA, basis::{Constant, Polynomial}, optim::SGD},
+        policies::Random,
+        prediction::{
+            ValuePredictor,
+            mocking::{Biased, GAMMA, check_values, train},
+        },
+    };
+    use super::{ETDLambda, HTDLambda, TOETDLambda, TOHTDLambda};
+
+    /// Check that an aggregated value, shared by both states of the chain, is
+    /// fitted to the second state alone when only it is of interest. With
+    /// unit interest and `gamma = 0.5`, the first state would instead pull the
+    /// estimate down to roughly 0.7.
+    fn check_interest<L>(agent: &mut L, p: f64)
+    where
+        L: OnlineLearner<Vec<f64>, usize> + ValuePredictor<Vec<f64>>,
+    {
+        train(agent, 5000);
+
+        assert!((agent.predict_v(&vec![0.0, 1.0]) - p).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_etd_lambda_off_policy() {
+        let mut agent = ETDLambda::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2), |_: &Vec<f64>| 1.0,
+            0.01, GAMMA, 0.8,
+        );
+
+        agent.beta = 0.5;
+
+        check_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_etd_lambda_interest() {
+        let mut agent = ETDLambda::new(
+            LFA::scalar(Constant::unit(), SGD(1.0)),
+            Biased(0.9), Random::new(2), |s: &Vec<f64>| s[1],
+            0.01, 0.5, 0.8,
+        );
+
+        check_interest(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_toetd_lambda_off_policy() {
+        let mut agent = TOETDLambda::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2), |_: &Vec<f64>| 1.0,
+            0.01, GAMMA, 0.8,
+        );
+
+        agent.beta = 0.5;
+
+        check_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_toetd_lambda_interest() {
+        let mut agent = TOETDLambda::new(
+            LFA::scalar(Constant::unit(), SGD(1.0)),
+            Biased(0.9), Random::new(2), |s: &Vec<f64>| s[1],
+            0.01, 0.5, 0.8,
+        );
+
+        check_interest(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_htd_lambda_off_policy() {
+        let mut agent = HTDLambda::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.9);
+    }
+
+    #[test]
+    fn test_tohtd_lambda_off_policy() {
+        let mut agent = TOHTDLambda::new(
+            LFA::scalar(Polynomial::new(2, 1), SGD(1.0)),
+            Biased(0.9), Random::new(2),
+            0.01, 0.01, GAMMA, 0.8,
+        );
+
+        check_values(&mut agent, 0.9);
+    }
+}
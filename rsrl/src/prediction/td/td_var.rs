@@ -1,85 +1,110 @@
 use crate::{
-    Algorithm, OnlineLearner, Parameter,
+    OnlineLearner,
     domains::Transition,
-    fa::{Parameterised, StateFunction},
-    geometry::{Matrix, MatrixView, MatrixViewMut},
+    fa::{Weights, WeightsView, WeightsViewMut, Parameterised, StateFunction},
+    prediction::{ValuePredictor, VariancePredictor},
 };
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Direct TD estimation of the variance of the return.
+///
+/// Learns `Var[G | s]` by TD(0) on a meta-MDP whose rewards are the squared
+/// TD errors of `value_estimator` and whose discount is `gamma^2`. The value
+/// estimator is not trained here, and would typically be a shared critic.
+///
+/// # References
+/// - Sherstan, C., Ashley, D. R., Bennett, B., Young, K., White, A., White,
+///   M., Sutton, R. S. (2018). Comparing direct and indirect
+///   temporal-difference methods for estimating the variance of the return.
+///   In Proceedings of the 34th Conference on Uncertainty in Artificial
+///   Intelligence.
+/// - Tamar, A., Di Castro, D., Mannor, S. (2016). Learning the variance of
+///   the reward-to-go. Journal of Machine Learning Research, 17(13), 1–36.
+#[derive(Clone, Debug, Serialize, Deserialize, Parameterised)]
 pub struct VarianceTD<J, V> {
     pub value_estimator: J,
-    pub variance_estimator: V,
+    #[weights] pub variance_estimator: V,
 
-    pub alpha: Parameter,
-    pub gamma: Parameter,
+    pub alpha: f64,
+    pub gamma: f64,
 }
 
 impl<J, V> VarianceTD<J, V> {
-    pub fn new<T1, T2>(value_estimator: J, variance_estimator: V, alpha: T1, gamma: T2) -> Self
-    where
-        T1: Into<Parameter>,
-        T2: Into<Parameter>,
-    {
+    pub fn new(value_estimator: J, variance_estimator: V, alpha: f64, gamma: f64) -> Self {
         VarianceTD {
             value_estimator,
             variance_estimator,
 
-            alpha: alpha.into(),
-            gamma: gamma.into(),
+            alpha,
+            gamma,
         }
     }
 }
 
-impl<J, V> VarianceTD<J, V> {
-    fn compute_value_error<S>(&self, s: &S, reward: f64, ns: &S) -> f64
-    where
-        J: StateFunction<S, Output = f64>,
-    {
-        reward + self.value_estimator.evaluate(ns) - self.value_estimator.evaluate(s)
-    }
-}
-
-impl<J, V> Algorithm for VarianceTD<J, V> {
-    fn handle_terminal(&mut self) {
-        self.alpha = self.alpha.step();
-        self.gamma = self.gamma.step();
-    }
-}
-
 impl<S, A, J, V> OnlineLearner<S, A> for VarianceTD<J, V>
 where
-    J: StateFunction<S, Output = f64>,
+    J: ValuePredictor<S>,
     V: StateFunction<S, Output = f64>,
 {
     fn handle_transition(&mut self, t: &Transition<S, A>) {
-        let (s, ns) = t.states();
-
-        let value_error = self.compute_value_error(s, t.reward, ns);
-        let meta_reward = value_error * value_error;
-        let variance_est = self.variance_estimator.evaluate(s);
+        let s = t.from.state();
+        let v = self.value_estimator.predict_v(s);
+        let var = self.variance_estimator.evaluate(s);
 
         let td_error = if t.terminated() {
-            meta_reward - variance_est
+            let value_error = t.reward - v;
+
+            value_error * value_error - var
         } else {
+            let ns = t.to.state();
+            let value_error = t.reward + self.gamma * self.value_estimator.predict_v(ns) - v;
             let gamma_var = self.gamma * self.gamma;
 
-            meta_reward + gamma_var * self.variance_estimator.evaluate(ns) - variance_est
+            value_error * value_error + gamma_var * self.variance_estimator.evaluate(ns) - var
         };
 
         self.variance_estimator.update(s, self.alpha * td_error);
     }
 }
 
-impl<J, V: Parameterised> Parameterised for VarianceTD<J, V> {
-    fn weights(&self) -> Matrix<f64> {
-        self.variance_estimator.weights()
-    }
+impl<S, J, V> ValuePredictor<S> for VarianceTD<J, V>
+where
+    J: ValuePredictor<S>,
+{
+    fn predict_v(&self, s: &S) -> f64 { self.value_estimator.predict_v(s) }
+}
 
-    fn weights_view(&self) -> MatrixView<f64> {
-        self.variance_estimator.weights_view()
-    }
+impl<S, J, V> VariancePredictor<S> for VarianceTD<J, V>
+where
+    V: StateFunction<S, Output = f64>,
+{
+    fn predict_var(&self, s: &S) -> f64 { self.variance_estimator.evaluate(s) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        domains::{Observation, Transition},
+        fa::linear::{LFA, basis::{Polynomial, Projector}, optim::SGD},
+        prediction::{VariancePredictor, td::TD},
+    };
+    use super::VarianceTD;
+
+    #[test]
+    fn test_bernoulli_returns() {
+        // Single-step episodes with returns of +/-1, so Var[G] = 1 about a mean of 0.
+        let fa = LFA::scalar(Polynomial::new(1, 1).with_constant(), SGD(1.0));
+        let mut agent = VarianceTD::new(TD::new(fa.clone(), 0.0, 1.0), fa, 0.01, 1.0);
+
+        for i in 0..2000 {
+            agent.handle_transition(&Transition {
+                from: Observation::Full(vec![0.0]),
+                action: (),
+                reward: if i % 2 == 0 { 1.0 } else { -1.0 },
+                to: Observation::Terminal(vec![0.0]),
+            });
+        }
 
-    fn weights_view_mut(&mut self) -> MatrixViewMut<f64> {
-        self.variance_estimator.weights_view_mut()
+        assert!((agent.predict_var(&vec![0.0]) - 1.0).abs() < 0.05);
     }
 }
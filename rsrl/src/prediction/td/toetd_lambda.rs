@@ -0,0 +1,201 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
+    policies::Policy,
+    prediction::ValuePredictor,
+    traces::{Dutch, Trace},
+};
+use std::ops::Deref;
+
+/// True online emphatic TD(lambda) for off-policy prediction.
+///
+/// As `ETDLambda`, but using a dutch trace such that the updates match those
+/// of the online emphatic forward view. The follow-on trace decays at rate
+/// `beta`, which is initialised to `gamma`; assigning any other value yields
+/// true online ETD(beta, lambda).
+///
+/// # References
+/// - Van Seijen, H., Mahmood, A. R., Pilarski, P. M., Machado, M. C., Sutton,
+///   R. S. (2016). True online temporal-difference learning. Journal of
+///   Machine Learning Research, 17(145):1–40.
+/// - Sutton, R. S., Mahmood, A. R., White, M. (2016). An emphatic approach to
+///   the problem of off-policy temporal-difference learning. Journal of
+///   Machine Learning Research, 17(73):1–29.
+#[derive(Parameterised)]
+pub struct TOETDLambda<F, T, B, I> {
+    #[weights] pub fa_theta: F,
+
+    pub target: T,
+    pub behaviour: B,
+    pub interest: I,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Dutch<LFAGradient>,
+    follow_on: f64,
+    rho_old: f64,
+    v_old: f64,
+}
+
+impl<F: Parameterised, T, B, I> TOETDLambda<F, T, B, I> {
+    pub fn new(
+        fa_theta: F,
+        target: T,
+        behaviour: B,
+        interest: I,
+        alpha: f64,
+        gamma: f64,
+        lambda: f64,
+    ) -> Self {
+        let trace = Dutch::zeros(alpha, fa_theta.weights_dim());
+
+        TOETDLambda {
+            fa_theta,
+
+            target,
+            behaviour,
+            interest,
+
+            alpha,
+            beta: gamma,
+            gamma,
+            lambda,
+
+            trace,
+            follow_on: 0.0,
+            rho_old: 1.0,
+            v_old: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.trace.reset();
+        self.follow_on = 0.0;
+        self.rho_old = 1.0;
+        self.v_old = 0.0;
+    }
+}
+
+impl<S, F, T, B, I> OnlineLearner<S, T::Action> for TOETDLambda<F, T, B, I>
+where
+    F: LinearStateFunction<S>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+    I: Fn(&S) -> f64,
+{
+    fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let interest = (self.interest)(s);
+
+        self.follow_on = self.beta * self.rho_old * self.follow_on + interest;
+
+        let emphasis = self.lambda * interest + (1.0 - self.lambda) * self.follow_on;
+
+        let v = self.fa_theta.evaluate_features(phi_s);
+        let nv = if t.terminated() {
+            0.0
+        } else {
+            self.fa_theta.evaluate_features(&self.fa_theta.features(t.to.state()))
+        };
+
+        // Update trace with latest feature vector (step size included):
+        {
+            let a = self.alpha;
+            let c = self.gamma * self.lambda;
+            let dotted = self.trace.deref().dot(&grad_s).get(&0).copied().unwrap_or(0.0);
+
+            self.trace.combine_inplace(&grad_s, move |x, y| {
+                rho * (c * x + a * emphasis * (1.0 - rho * c * dotted) * y)
+            });
+        }
+
+        let td_error = t.reward + self.gamma * nv - v;
+
+        self.fa_theta.update_grad_scaled(self.trace.deref(), td_error + v - self.v_old);
+        self.fa_theta.update_grad_scaled(
+            &grad_s, self.alpha * emphasis * rho * (self.v_old - v),
+        );
+
+        if t.terminated() {
+            self.reset();
+        } else {
+            self.rho_old = rho;
+            self.v_old = nv;
+        }
+    }
+
+    fn handle_terminal(&mut self) { self.reset(); }
+}
+
+impl<S, F: LinearStateFunction<S>, T, B, I> ValuePredictor<S> for TOETDLambda<F, T, B, I> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        domains::{Observation, Transition},
+        fa::{
+            Parameterised,
+            linear::{LFA, basis::Polynomial, optim::SGD},
+        },
+        policies::Random,
+        prediction::td::TOTDLambda,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use super::TOETDLambda;
+
+    #[test]
+    fn test_on_policy_equivalence() {
+        // With equal policies, unit interest and beta = 0, the emphasis is
+        // always one and true online ETD reduces to true online TD(lambda).
+        let mut rng = StdRng::seed_from_u64(0);
+        let fa = LFA::scalar(Polynomial::new(1, 3), SGD(1.0));
+
+        let mut totd = TOTDLambda::new(fa.clone(), 0.05, 0.9, 0.8);
+        let mut toetd = TOETDLambda::new(
+            fa, Random::new(2), Random::new(2), |_: &Vec<f64>| 1.0,
+            0.05, 0.9, 0.8,
+        );
+
+        toetd.beta = 0.0;
+
+        for _ in 0..200 {
+            let s = rng.gen_range(0.0, 1.0);
+            let ns = rng.gen_range(0.0, 1.0);
+            let t = Transition {
+                from: Observation::Full(vec![s]),
+                action: rng.gen_range(0, 2),
+                reward: rng.gen_range(-1.0, 1.0),
+                to: if rng.gen_bool(0.1) {
+                    Observation::Terminal(vec![ns])
+                } else {
+                    Observation::Full(vec![ns])
+                },
+            };
+
+            totd.handle_transition(&t);
+            toetd.handle_transition(&t);
+        }
+
+        let w1 = totd.weights();
+        let w2 = toetd.weights();
+
+        assert!(w1.iter().zip(w2.iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    }
+}
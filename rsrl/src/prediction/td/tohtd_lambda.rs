@@ -0,0 +1,159 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
+    policies::Policy,
+    prediction::{ValuePredictor, dot_weights},
+    traces::{Accumulating, Dutch, Trace},
+};
+use std::ops::Deref;
+
+/// True online hybrid TD(lambda).
+///
+/// Combines the off-policy true online TD(lambda) update, using dutch traces
+/// weighted by importance sampling ratios, with the hybrid correction of
+/// HTD(lambda). The auxiliary weights, `w`, are trained with step size `beta`
+/// from accumulating traces. With equal `target` and `behaviour` policies the
+/// correction vanishes and the algorithm reduces to true online TD(lambda).
+///
+/// # References
+/// - White, A., White, M. (2016). Investigating practical linear temporal
+///   difference learning. In Proceedings of the 15th International Conference
+///   on Autonomous Agents and Multiagent Systems, pp. 494–502.
+/// - Van Hasselt, H., Mahmood, A. R., Sutton, R. S. (2014). Off-policy
+///   TD(lambda) with a true online equivalence. In Proceedings of the 30th
+///   Conference on Uncertainty in Artificial Intelligence, pp. 330–339.
+#[derive(Parameterised)]
+pub struct TOHTDLambda<F, T, B> {
+    #[weights] pub fa_theta: F,
+    pub w: Weights,
+
+    pub target: T,
+    pub behaviour: B,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Dutch<LFAGradient>,
+    trace_b: Dutch<LFAGradient>,
+    trace_w: Accumulating<LFAGradient>,
+    trace_wb: Accumulating<LFAGradient>,
+    v_old: f64,
+}
+
+impl<F: Parameterised, T, B> TOHTDLambda<F, T, B> {
+    pub fn new(
+        fa_theta: F,
+        target: T,
+        behaviour: B,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+        lambda: f64,
+    ) -> Self {
+        let dim = fa_theta.weights_dim();
+
+        TOHTDLambda {
+            fa_theta,
+            w: Weights::zeros((dim[0], dim[1])),
+
+            target,
+            behaviour,
+
+            alpha,
+            beta,
+            gamma,
+            lambda,
+
+            trace: Dutch::zeros(alpha, dim),
+            trace_b: Dutch::zeros(alpha, dim),
+            trace_w: Accumulating::zeros(dim),
+            trace_wb: Accumulating::zeros(dim),
+            v_old: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.trace.reset();
+        self.trace_b.reset();
+        self.trace_w.reset();
+        self.trace_wb.reset();
+        self.v_old = 0.0;
+    }
+}
+
+impl<S, F, T, B> OnlineLearner<S, T::Action> for TOHTDLambda<F, T, B>
+where
+    F: LinearStateFunction<S>,
+    T: Policy<S>,
+    B: Policy<S, Action = T::Action>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, T::Action>) {
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let rho = self.target.probability(s, &t.action) / self.behaviour.probability(s, &t.action);
+        let a = self.alpha;
+        let c = self.gamma * self.lambda;
+
+        // Update traces with latest feature vector:
+        {
+            let dotted = self.trace.deref().dot(&grad_s).get(&0).copied().unwrap_or(0.0);
+            let dotted_b = self.trace_b.deref().dot(&grad_s).get(&0).copied().unwrap_or(0.0);
+
+            self.trace.combine_inplace(&grad_s, move |x, y| {
+                rho * (c * x + (1.0 - a * rho * c * dotted) * y)
+            });
+            self.trace_b.combine_inplace(&grad_s, move |x, y| c * x + (1.0 - a * c * dotted_b) * y);
+            self.trace_w.combine_inplace(&grad_s, move |x, y| rho * (c * x + y));
+            self.trace_wb.combine_inplace(&grad_s, move |x, y| c * x + y);
+        }
+
+        let v = self.fa_theta.evaluate_features(phi_s);
+        let (nv, grad_diff) = if t.terminated() {
+            (0.0, grad_s.clone())
+        } else {
+            let grad_ns = self.fa_theta.grad(t.to.state());
+            let nv = self.fa_theta.evaluate_features(grad_ns.features(&0).unwrap());
+            let gamma = self.gamma;
+
+            (nv, grad_s.clone().combine(&grad_ns, move |x, y| x - gamma * y))
+        };
+
+        let td_error = t.reward + self.gamma * nv - v;
+        let w_diff = dot_weights(&self.w, &grad_diff);
+        let w_traces = dot_weights(&self.w, self.trace.deref())
+            - dot_weights(&self.w, self.trace_b.deref());
+
+        self.fa_theta.update_grad_scaled(
+            self.trace.deref(), self.alpha * (td_error + v - self.v_old),
+        );
+        self.fa_theta.update_grad_scaled(&grad_s, self.alpha * rho * (self.v_old - v));
+        self.fa_theta.update_grad_scaled(&grad_diff, self.alpha * w_traces);
+
+        self.trace_w.scaled_addto(self.beta * td_error, &mut self.w);
+        self.trace_wb.scaled_addto(-self.beta * w_diff, &mut self.w);
+
+        if t.terminated() {
+            self.reset();
+        } else {
+            self.v_old = nv;
+        }
+    }
+
+    fn handle_terminal(&mut self) { self.reset(); }
+}
+
+impl<S, F: LinearStateFunction<S>, T, B> ValuePredictor<S> for TOHTDLambda<F, T, B> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
+
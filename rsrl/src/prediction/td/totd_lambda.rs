@@ -0,0 +1,239 @@
+use crate::{
+    OnlineLearner,
+    domains::Transition,
+    fa::{
+        Weights, WeightsView, WeightsViewMut, Parameterised,
+        linear::{LFAGradient, LinearStateFunction},
+    },
+    linalg::MatrixLike,
+    prediction::ValuePredictor,
+    traces::{Dutch, Trace},
+};
+use std::ops::Deref;
+
+/// True online TD(lambda).
+///
+/// Exactly reproduces, at every time step, the weights obtained by the online
+/// forward view of the lambda-return algorithm, using a dutch trace.
+///
+/// # References
+/// - Van Seijen, H., Sutton, R. S. (2014). True online TD(lambda). In
+///   Proceedings of the 31st International Conference on Machine Learning,
+///   pp. 692–700.
+/// - Van Seijen, H., Mahmood, A. R., Pilarski, P. M., Machado, M. C., Sutton,
+///   R. S. (2016). True online temporal-difference learning. Journal of
+///   Machine Learning Research, 17(145):1–40.
+#[derive(Parameterised)]
+pub struct TOTDLambda<F> {
+    #[weights] pub fa_theta: F,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Dutch<LFAGradient>,
+    v_old: f64,
+}
+
+impl<F: Parameterised> TOTDLambda<F> {
+    pub fn new(fa_theta: F, alpha: f64, gamma: f64, lambda: f64) -> Self {
+        let trace = Dutch::zeros(alpha, fa_theta.weights_dim());
+
+        TOTDLambda {
+            fa_theta,
+
+            alpha,
+            gamma,
+            lambda,
+
+            trace,
+            v_old: 0.0,
+        }
+    }
+}
+
+impl<S, A, F> OnlineLearner<S, A> for TOTDLambda<F>
+where
+    F: LinearStateFunction<S>,
+{
+    fn handle_transition(&mut self, t: &Transition<S, A>) {
+        let s = t.from.state();
+        let grad_s = self.fa_theta.grad(s);
+        let phi_s = grad_s.features(&0).unwrap();
+
+        let v = self.fa_theta.evaluate_features(phi_s);
+        let nv = if t.terminated() {
+            0.0
+        } else {
+            self.fa_theta.evaluate_features(&self.fa_theta.features(t.to.state()))
+        };
+
+        // Update trace with latest feature vector:
+        {
+            let a = self.alpha;
+            let c = self.lambda * self.gamma;
+            let dotted = self.trace.deref().dot(&grad_s).get(&0).copied().unwrap_or(0.0);
+
+            self.trace.combine_inplace(&grad_s, move |x, y| c * x + (1.0 - a * c * dotted) * y);
+        }
+
+        let td_error = t.reward + self.gamma * nv - v;
+
+        self.fa_theta.update_grad_scaled(
+            self.trace.deref(), self.alpha * (td_error + v - self.v_old),
+        );
+        self.fa_theta.update_grad_scaled(&grad_s, self.alpha * (self.v_old - v));
+
+        if t.terminated() {
+            self.v_old = 0.0;
+            self.trace.reset();
+        } else {
+            self.v_old = nv;
+        }
+    }
+
+    fn handle_terminal(&mut self) {
+        self.v_old = 0.0;
+        self.trace.reset();
+    }
+}
+
+impl<S, F: LinearStateFunction<S>> ValuePredictor<S> for TOTDLambda<F> {
+    fn predict_v(&self, s: &S) -> f64 {
+        self.fa_theta.evaluate_features(&self.fa_theta.features(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        OnlineLearner,
+        domains::{Observation, Transition},
+        fa::{
+            Parameterised,
+            linear::{LFA, LinearStateFunction, basis::Polynomial, optim::SGD},
+        },
+    };
+    use ndarray::Array1;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use super::TOTDLambda;
+
+    const ALPHA: f64 = 0.05;
+    const GAMMA: f64 = 0.9;
+    const LAMBDA: f64 = 0.8;
+
+    /// Generate an episode of a 5-state random walk with noisy rewards.
+    fn random_walk(rng: &mut impl Rng) -> Vec<Transition<Vec<f64>, ()>> {
+        let mut s: i32 = 2;
+        let mut episode = vec![];
+
+        loop {
+            let ns = if rng.gen_bool(0.5) { s + 1 } else { s - 1 };
+            let to = if !(0..=4).contains(&ns) {
+                Observation::Terminal(vec![ns as f64 / 4.0])
+            } else {
+                Observation::Full(vec![ns as f64 / 4.0])
+            };
+            let reward = if ns > 4 { 1.0 } else { rng.gen_range(-0.1, 0.1) };
+            let done = to.is_terminal();
+
+            episode.push(Transition {
+                from: Observation::Full(vec![s as f64 / 4.0]),
+                action: (),
+                reward,
+                to,
+            });
+
+            if done { break episode; }
+
+            s = ns;
+        }
+    }
+
+    /// Online lambda-return algorithm: returns the weights at the end of each
+    /// horizon, `theta_h^h`, for `h = 1..=T`.
+    fn forward_view(phis: &[Array1<f64>], rewards: &[f64], theta_0: Array1<f64>) -> Vec<Array1<f64>> {
+        let n_steps = rewards.len();
+        let mut thetas = vec![theta_0.clone()];
+
+        for h in 1..=n_steps {
+            let mut theta = theta_0.clone();
+
+            for k in 0..h {
+                let n_return = |n: usize| -> f64 {
+                    let rewards = (0..n).fold(0.0, |acc, i| acc + GAMMA.powi(i as i32) * rewards[k + i]);
+                    let bootstrap = if k + n == n_steps {
+                        0.0
+                    } else {
+                        thetas[k + n - 1].dot(&phis[k + n])
+                    };
+
+                    rewards + GAMMA.powi(n as i32) * bootstrap
+                };
+
+                let lambda_return = (1..(h - k)).fold(0.0, |acc, n| {
+                    acc + (1.0 - LAMBDA) * LAMBDA.powi(n as i32 - 1) * n_return(n)
+                }) + LAMBDA.powi((h - k - 1) as i32) * n_return(h - k);
+
+                let error = lambda_return - theta.dot(&phis[k]);
+
+                theta.scaled_add(ALPHA * error, &phis[k]);
+            }
+
+            thetas.push(theta);
+        }
+
+        thetas.split_off(1)
+    }
+
+    fn check_episode(agent: &mut TOTDLambda<impl LinearStateFunction<Vec<f64>>>, rng: &mut impl Rng) {
+        let episode = random_walk(rng);
+
+        let phis: Vec<Array1<f64>> = episode.iter()
+            .map(|t| agent.fa_theta.features(t.from.state()).expanded())
+            .collect();
+        let rewards: Vec<f64> = episode.iter().map(|t| t.reward).collect();
+        let expected = forward_view(&phis, &rewards, agent.weights().column(0).to_owned());
+
+        for (t, theta) in episode.iter().zip(expected) {
+            agent.handle_transition(t);
+
+            let actual = agent.weights().column(0).to_owned();
+
+            assert!(actual.iter().zip(theta.iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+        }
+    }
+
+    #[test]
+    fn test_lambda_return_equivalence() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = TOTDLambda::new(
+            LFA::scalar(Polynomial::new(1, 3), SGD(1.0)),
+            ALPHA, GAMMA, LAMBDA,
+        );
+
+        for _ in 0..5 {
+            check_episode(&mut agent, &mut rng);
+        }
+    }
+
+    #[test]
+    fn test_episode_reset() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut agent = TOTDLambda::new(
+            LFA::scalar(Polynomial::new(1, 3), SGD(1.0)),
+            ALPHA, GAMMA, LAMBDA,
+        );
+
+        // Truncate an episode part-way through without a terminal transition:
+        let episode = random_walk(&mut rng);
+
+        for t in episode.iter().take(episode.len() / 2) {
+            agent.handle_transition(t);
+        }
+
+        OnlineLearner::<Vec<f64>, ()>::handle_terminal(&mut agent);
+
+        check_episode(&mut agent, &mut rng);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::spaces::{TwoSpace, discrete::Ordinal};
 use ndarray::Array2;
-use super::{Domain, Observation, Transition, grid_world::{GridWorld, Motion}};
+use super::{Domain, FiniteMDP, Observation, Transition, grid_world::{GridWorld, Motion}};
 
 const ALL_ACTIONS: [Motion; 4] = [
     Motion::North(1),
@@ -25,6 +25,20 @@ impl CliffWalk {
     }
 }
 
+impl CliffWalk {
+    fn is_terminal_loc(&self, loc: [usize; 2]) -> bool { loc[0] > 0 && loc[1] == 0 }
+
+    fn reward(&self, to: [usize; 2]) -> f64 {
+        if !self.is_terminal_loc(to) {
+            0.0
+        } else if to[0] == self.gw.width() - 1 {
+            50.0
+        } else {
+            -50.0
+        }
+    }
+}
+
 impl Default for CliffWalk {
     fn default() -> CliffWalk { CliffWalk::new(5, 12) }
 }
@@ -34,7 +48,7 @@ impl Domain for CliffWalk {
     type ActionSpace = Ordinal;
 
     fn emit(&self) -> Observation<[usize; 2]> {
-        if self.loc[0] > 0 && self.loc[1] == 0 {
+        if self.is_terminal_loc(self.loc) {
             Observation::Terminal(self.loc)
         } else {
             Observation::Full(self.loc)
@@ -51,11 +65,7 @@ impl Domain for CliffWalk {
         Transition {
             from,
             action,
-            reward: match to {
-                Observation::Terminal(s) if s[0] == self.gw.width() - 1 => 50.0,
-                Observation::Terminal(_) => -50.0,
-                _ => 0.0
-            },
+            reward: self.reward(self.loc),
             to,
         }
     }
@@ -70,9 +80,33 @@ impl Domain for CliffWalk {
     fn action_space(&self) -> Ordinal { Ordinal::new(4) }
 }
 
+impl FiniteMDP for CliffWalk {
+    fn n_states(&self) -> usize { self.gw.width() * self.gw.height() }
+
+    fn n_actions(&self) -> usize { 4 }
+
+    fn state_index(&self, state: &[usize; 2]) -> usize { state[1] * self.gw.width() + state[0] }
+
+    fn index_state(&self, index: usize) -> [usize; 2] {
+        [index % self.gw.width(), index / self.gw.width()]
+    }
+
+    fn is_terminal(&self, s: usize) -> bool { self.is_terminal_loc(self.index_state(s)) }
+
+    fn transition_probabilities(&self, s: usize, a: usize) -> Vec<(usize, f64)> {
+        let to = self.gw.perform_motion(self.index_state(s), ALL_ACTIONS[a]);
+
+        vec![(self.state_index(&to), 1.0)]
+    }
+
+    fn expected_reward(&self, s: usize, a: usize) -> f64 {
+        self.reward(self.gw.perform_motion(self.index_state(s), ALL_ACTIONS[a]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{CliffWalk, Domain};
+    use super::{CliffWalk, Domain, FiniteMDP};
 
     #[test]
     fn test_cliff_direct() {
@@ -137,4 +171,31 @@ mod tests {
         assert!(t.to.is_terminal());
         assert!(t.reward.is_sign_positive());
     }
+
+    #[test]
+    fn test_mdp() {
+        let mut cw = CliffWalk::default();
+
+        assert_eq!(cw.n_states(), 60);
+        assert_eq!(cw.n_actions(), 4);
+
+        for i in 0..cw.n_states() {
+            assert_eq!(cw.state_index(&cw.index_state(i)), i);
+        }
+
+        assert!(!cw.is_terminal(0));
+        assert!(cw.is_terminal(1));
+        assert!(cw.is_terminal(11));
+
+        for &a in [0, 1, 1, 2].iter() {
+            let s = cw.state_index(cw.emit().state());
+            let t = cw.step(a);
+
+            assert_eq!(cw.transition_probabilities(s, a), vec![(cw.state_index(t.to.state()), 1.0)]);
+            assert_eq!(cw.expected_reward(s, a), t.reward);
+        }
+
+        assert_eq!(cw.expected_reward(0, 1), -50.0);
+        assert_eq!(cw.expected_reward(23, 2), 50.0);
+    }
 }
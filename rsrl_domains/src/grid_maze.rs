@@ -0,0 +1,267 @@
+use crate::spaces::{TwoSpace, discrete::Ordinal};
+use rand::{thread_rng, Rng, rngs::ThreadRng};
+use std::str::FromStr;
+use super::{Domain, FiniteMDP, Observation, Transition, grid_world::{GridWorld, Motion}};
+
+const ALL_ACTIONS: [Motion; 4] = [
+    Motion::North(1),
+    Motion::East(1),
+    Motion::South(1),
+    Motion::West(1),
+];
+
+const STEP_REWARD: f64 = -1.0;
+const PIT_REWARD: f64 = -100.0;
+
+/// Cell types of a `GridMaze` layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cell {
+    Empty,
+    Wall,
+    Start,
+    Goal,
+    Pit,
+}
+
+impl FromStr for Cell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Cell, String> {
+        match s {
+            "." => Ok(Cell::Empty),
+            "#" => Ok(Cell::Wall),
+            "S" => Ok(Cell::Start),
+            "G" => Ok(Cell::Goal),
+            "X" => Ok(Cell::Pit),
+            _ => Err(format!("Unknown cell type {}!", s)),
+        }
+    }
+}
+
+/// Gridworld domain defined by a text layout.
+///
+/// Layouts are given as rows of whitespace-separated cells, with the first row
+/// being the northern-most: `.` for empty cells, `#` for walls, `S` for the
+/// start, `G` for goals and `X` for pits. Goals and pits are terminal. Each
+/// step yields a reward of -1, and -100 on entering a pit. Moves into walls or
+/// off the grid leave the agent in place. With probability `slip`, the chosen
+/// action is replaced by one drawn uniformly at random.
+pub struct GridMaze {
+    gw: GridWorld<Cell>,
+    start: [usize; 2],
+    loc: [usize; 2],
+    slip: f64,
+
+    rng: ThreadRng,
+}
+
+impl GridMaze {
+    pub fn new(layout: &str, slip: f64) -> GridMaze {
+        let gw = GridWorld::<Cell>::from_str(layout);
+        let start = (0..gw.width())
+            .flat_map(|x| (0..gw.height()).map(move |y| [x, y]))
+            .find(|&loc| gw.get([gw.height() - 1 - loc[1], loc[0]]) == Some(&Cell::Start))
+            .expect("GridMaze layouts must contain a start cell.");
+
+        GridMaze {
+            gw,
+            start,
+            loc: start,
+            slip,
+
+            rng: thread_rng(),
+        }
+    }
+
+    pub fn cell(&self, loc: [usize; 2]) -> Cell {
+        self.gw.get([self.gw.height() - 1 - loc[1], loc[0]]).copied().unwrap_or(Cell::Wall)
+    }
+
+    pub fn reset(&mut self) { self.loc = self.start; }
+
+    fn is_terminal_loc(&self, loc: [usize; 2]) -> bool {
+        matches!(self.cell(loc), Cell::Goal | Cell::Pit)
+    }
+
+    fn move_from(&self, loc: [usize; 2], action: usize) -> [usize; 2] {
+        let to = self.gw.perform_motion(loc, ALL_ACTIONS[action]);
+
+        if self.cell(to) == Cell::Wall { loc } else { to }
+    }
+
+    fn reward(&self, to: [usize; 2]) -> f64 {
+        match self.cell(to) {
+            Cell::Pit => PIT_REWARD,
+            _ => STEP_REWARD,
+        }
+    }
+}
+
+impl Domain for GridMaze {
+    type StateSpace = TwoSpace<Ordinal>;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<[usize; 2]> {
+        if self.is_terminal_loc(self.loc) {
+            Observation::Terminal(self.loc)
+        } else {
+            Observation::Full(self.loc)
+        }
+    }
+
+    fn step(&mut self, action: usize) -> Transition<[usize; 2], usize> {
+        let from = self.emit();
+        let motion = if self.slip > 0.0 && self.rng.gen_bool(self.slip) {
+            self.rng.gen_range(0, 4)
+        } else {
+            action
+        };
+
+        self.loc = self.move_from(self.loc, motion);
+
+        Transition {
+            from,
+            action,
+            reward: self.reward(self.loc),
+            to: self.emit(),
+        }
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        TwoSpace::new([
+            Ordinal::new(self.gw.width()),
+            Ordinal::new(self.gw.height()),
+        ])
+    }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+}
+
+impl FiniteMDP for GridMaze {
+    fn n_states(&self) -> usize { self.gw.width() * self.gw.height() }
+
+    fn n_actions(&self) -> usize { 4 }
+
+    fn state_index(&self, state: &[usize; 2]) -> usize { state[1] * self.gw.width() + state[0] }
+
+    fn index_state(&self, index: usize) -> [usize; 2] {
+        [index % self.gw.width(), index / self.gw.width()]
+    }
+
+    fn is_terminal(&self, s: usize) -> bool { self.is_terminal_loc(self.index_state(s)) }
+
+    fn transition_probabilities(&self, s: usize, a: usize) -> Vec<(usize, f64)> {
+        let loc = self.index_state(s);
+        let mut probs: Vec<(usize, f64)> = vec![];
+
+        for motion in 0..4 {
+            let p = if motion == a {
+                1.0 - self.slip + self.slip / 4.0
+            } else {
+                self.slip / 4.0
+            };
+
+            if p <= 0.0 { continue; }
+
+            let ns = self.state_index(&self.move_from(loc, motion));
+
+            match probs.iter_mut().find(|(i, _)| *i == ns) {
+                Some((_, q)) => *q += p,
+                None => probs.push((ns, p)),
+            }
+        }
+
+        probs
+    }
+
+    fn expected_reward(&self, s: usize, a: usize) -> f64 {
+        self.transition_probabilities(s, a).into_iter().fold(0.0, |acc, (ns, p)| {
+            acc + p * self.reward(self.index_state(ns))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cell, Domain, FiniteMDP, GridMaze, PIT_REWARD, STEP_REWARD};
+
+    const LAYOUT: &str = ". . . G\n. # . X\nS . . .";
+
+    #[test]
+    fn test_layout() {
+        let gm = GridMaze::new(LAYOUT, 0.0);
+
+        assert_eq!(gm.emit().state(), &[0, 0]);
+        assert_eq!(gm.cell([0, 0]), Cell::Start);
+        assert_eq!(gm.cell([1, 1]), Cell::Wall);
+        assert_eq!(gm.cell([3, 1]), Cell::Pit);
+        assert_eq!(gm.cell([3, 2]), Cell::Goal);
+        assert_eq!(gm.cell([2, 2]), Cell::Empty);
+    }
+
+    #[test]
+    fn test_walls() {
+        let mut gm = GridMaze::new(LAYOUT, 0.0);
+
+        gm.step(1);
+        let t = gm.step(0);
+
+        assert_eq!(t.to.state(), &[1, 0]);
+        assert_eq!(t.reward, -1.0);
+    }
+
+    #[test]
+    fn test_terminals() {
+        let mut gm = GridMaze::new(LAYOUT, 0.0);
+
+        for _ in 0..3 { gm.step(1); }
+        let t = gm.step(0);
+
+        assert!(t.terminated());
+        assert_eq!(t.reward, -100.0);
+
+        gm.reset();
+
+        for _ in 0..2 { gm.step(0); }
+        for _ in 0..2 { gm.step(1); }
+        assert!(!gm.emit().is_terminal());
+
+        let t = gm.step(1);
+
+        assert!(t.terminated());
+        assert_eq!(t.reward, -1.0);
+    }
+
+    #[test]
+    fn test_mdp() {
+        let gm = GridMaze::new(LAYOUT, 0.2);
+
+        assert_eq!(gm.n_states(), 12);
+        assert!(gm.is_terminal(7));
+        assert!(gm.is_terminal(11));
+        assert!(!gm.is_terminal(0));
+
+        for s in 0..gm.n_states() {
+            assert_eq!(gm.state_index(&gm.index_state(s)), s);
+
+            for a in 0..gm.n_actions() {
+                let total: f64 = gm.transition_probabilities(s, a).into_iter().map(|(_, p)| p).sum();
+
+                assert!((total - 1.0).abs() < 1e-10);
+            }
+        }
+
+        // From the start, slipping south or west leaves the agent in place.
+        let probs = gm.transition_probabilities(0, 0);
+
+        assert_eq!(probs.len(), 3);
+        assert_eq!(probs[0].0, 4);
+        assert!((probs[0].1 - 0.85).abs() < 1e-10);
+        assert_eq!(probs[2].0, 0);
+        assert!((probs[2].1 - 0.1).abs() < 1e-10);
+
+        let r = gm.expected_reward(6, 1);
+
+        assert!((r - (0.85 * PIT_REWARD + 0.15 * STEP_REWARD)).abs() < 1e-10);
+    }
+}
@@ -221,6 +221,35 @@ pub trait Domain {
     fn action_space(&self) -> Self::ActionSpace;
 }
 
+/// An interface for domains with finite state and action spaces whose dynamics
+/// are known explicitly.
+///
+/// States and actions are enumerated as `0..n_states()` and `0..n_actions()`,
+/// respectively. Terminal states are absorbing and yield no further reward.
+pub trait FiniteMDP: Domain {
+    /// Returns the number of states in the domain.
+    fn n_states(&self) -> usize;
+
+    /// Returns the number of actions available in each state.
+    fn n_actions(&self) -> usize;
+
+    /// Map a state of the domain onto its index.
+    fn state_index(&self, state: &State<Self>) -> usize;
+
+    /// Map an index onto the corresponding state of the domain.
+    fn index_state(&self, index: usize) -> State<Self>;
+
+    /// Returns true if the state with index `s` is terminal.
+    fn is_terminal(&self, s: usize) -> bool;
+
+    /// Returns the successors of state `s` under action `a` as a list of
+    /// `(successor, probability)` pairs with non-zero probability.
+    fn transition_probabilities(&self, s: usize, a: usize) -> Vec<(usize, f64)>;
+
+    /// Returns the expected reward for taking action `a` in state `s`.
+    fn expected_reward(&self, s: usize, a: usize) -> f64;
+}
+
 mod consts;
 mod macros;
 mod grid_world;
@@ -243,6 +272,9 @@ pub use self::hiv::*;
 mod cliff_walk;
 pub use self::cliff_walk::*;
 
+mod grid_maze;
+pub use self::grid_maze::*;
+
 mod roulette;
 pub use self::roulette::*;
 